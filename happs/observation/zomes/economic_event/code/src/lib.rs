@@ -13,78 +13,62 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
 use hc_zome_rea_economic_event_defs::*;
 use hc_zome_rea_economic_event_lib::*;
 use hc_zome_rea_economic_event_rpc::*;
 use hc_zome_rea_economic_resource_rpc::CreateRequest as EconomicResourceCreateRequest;
 
-#[zome]
-mod rea_economic_event_zome {
-
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn event_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn event_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
+record_zome! {
+    zome: rea_economic_event_zome,
+    record: event,
+    records: events,
+    receive: economic_event,
+    receive_many: events,
+    id: EventAddress,
+    api: [get, get_history, update, delete, query, scope, aggregate, check_index_integrity, migrate_entries],
+    {
+        #[entry_def]
+        fn event_time_index_entry_def() -> ValidatingEntryType {
+            time_index_entry_def()
+        }
+
+        #[entry_def]
+        fn event_classification_index_entry_def() -> ValidatingEntryType {
+            classification_index_entry_def()
+        }
+
+        #[entry_def]
+        fn event_scope_index_entry_def() -> ValidatingEntryType {
+            scope_index_entry_def()
+        }
+
+        // creation accepts an additional parameter for initialising inventoried resources
+        #[zome_fn("hc_public")]
+        fn create_event(event: CreateRequest, new_inventoried_resource: Option<EconomicResourceCreateRequest>) -> ZomeApiResult<ResponseData> {
+            receive_create_economic_event(event, new_inventoried_resource)
+        }
+
+        #[zome_fn("hc_public")]
+        fn get_all_events(params: ListParams) -> ZomeApiResult<ResponsePage> {
+            receive_get_all_economic_events(params)
+        }
+
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("economic_event", &[
+                "delete_event",
+                "update_event",
+            ], request)?)
+        }
+
+        // :TODO:
+        // receive: |from, payload| {
+        //   format!("Received: {} from {}", payload, from)
+        // }
     }
-
-    #[entry_def]
-    fn event_root_entry_def() -> ValidatingEntryType {
-        root_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn create_event(event: CreateRequest, new_inventoried_resource: Option<EconomicResourceCreateRequest>) -> ZomeApiResult<ResponseData> {
-        receive_create_economic_event(event, new_inventoried_resource)
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_event(address: EventAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_economic_event(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn update_event(event: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_economic_event(event)
-    }
-
-    #[zome_fn("hc_public")]
-    fn delete_event(address: EventAddress) -> ZomeApiResult<bool> {
-        receive_delete_economic_event(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_all_events() -> ZomeApiResult<Vec<ResponseData>> {
-        receive_get_all_economic_events()
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_events(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-        receive_query_events(params)
-    }
-
-
-
-    // :TODO:
-    // receive: |from, payload| {
-    //   format!("Received: {} from {}", payload, from)
-    // }
-
-
-
 }
@@ -13,66 +13,66 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
 use hc_zome_rea_economic_resource_defs::*;
 use hc_zome_rea_economic_resource_lib::*;
 use hc_zome_rea_economic_resource_rpc::*;
-use hc_zome_rea_economic_event_rpc::ResourceResponseData as ResponseData;
-
-#[zome]
-mod rea_economic_resource_zome {
-
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn resource_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn resource_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
-
-    #[entry_def]
-    fn resource_root_entry_def() -> ValidatingEntryType {
-        root_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_resource(address: ResourceAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_economic_resource(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn update_resource(resource: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_economic_resource(resource)
+use hc_zome_rea_economic_event_rpc::{
+    ResourceResponseData as ResponseData,
+    ResourceResponsePage as ResponsePage,
+    ResourceRevisionResponse as RevisionResponse,
+    ResourceQueryResponse as QueryResponse,
+};
+
+record_zome! {
+    zome: rea_economic_resource_zome,
+    record: resource,
+    records: resources,
+    receive: economic_resource,
+    receive_many: economic_resources,
+    id: ResourceAddress,
+    capabilities: ECONOMIC_RESOURCE_REMOTE_CAPABILITIES,
+    api: [get, get_history, update, query, check_index_integrity, migrate_entries],
+    {
+        #[entry_def]
+        fn resource_time_index_entry_def() -> ValidatingEntryType {
+            time_index_entry_def()
+        }
+
+        #[entry_def]
+        fn resource_classification_index_entry_def() -> ValidatingEntryType {
+            classification_index_entry_def()
+        }
+
+        #[entry_def]
+        fn resource_unit_index_entry_def() -> ValidatingEntryType {
+            unit_index_entry_def()
+        }
+
+        #[zome_fn("hc_public")]
+        fn get_all_resources(params: ListParams) -> ZomeApiResult<ResponsePage> {
+            receive_get_all_economic_resources(params)
+        }
+
+        #[zome_fn("remote_index")]
+        fn get_unit_resource_ids(address: Address) -> ZomeApiResult<Vec<Address>> {
+            receive_get_unit_resource_ids(address)
+        }
+
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("economic_resource", &[
+                "get_unit_resource_ids",
+            ], request)?)
+        }
+
+        // :TODO:
+        // receive: |from, payload| {
+        //     format!("Received: {} from {}", payload, from)
+        // }
     }
-
-    #[zome_fn("hc_public")]
-    fn get_all_resources() -> ZomeApiResult<Vec<ResponseData>> {
-        receive_get_all_economic_resources()
-    }
-
-
-    #[zome_fn("hc_public")]
-    fn query_resources(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-        receive_query_economic_resources(params)
-    }
-
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
-
 }
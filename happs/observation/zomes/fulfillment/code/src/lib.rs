@@ -16,64 +16,50 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
 use hc_zome_rea_fulfillment_defs::{ entry_def, remote_entry_def };
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_lib_destination::*;
 
-#[zome]
-mod rea_fulfillment_zome {
+record_zome! {
+    zome: rea_fulfillment_zome,
+    record: fulfillment,
+    records: fulfillments,
+    id: FulfillmentAddress,
+    base_entry_def: remote_entry_def,
+    api: [get, get_history, query, check_index_integrity],
+    {
+        #[zome_fn("remote_index")]
+        fn fulfillment_created(fulfillment: CreateRequest) -> ZomeApiResult<ResponseData> {
+            receive_create_fulfillment(fulfillment)
+        }
 
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn fulfillment_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn fulfillment_base_entry_def() -> ValidatingEntryType {
-        remote_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn fulfillment_created(fulfillment: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_fulfillment(fulfillment)
-    }
-
-    #[zome_fn("hc_public")]
-    fn fulfillment_updated(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_fulfillment(fulfillment)
-    }
+        #[zome_fn("remote_index")]
+        fn fulfillment_updated(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
+            receive_update_fulfillment(fulfillment)
+        }
 
-    #[zome_fn("hc_public")]
-    fn fulfillment_deleted(address: FulfillmentAddress) -> ZomeApiResult<bool> {
-        receive_delete_fulfillment(address)
-    }
+        #[zome_fn("remote_index")]
+        fn fulfillment_deleted(address: FulfillmentAddress) -> ZomeApiResult<bool> {
+            receive_delete_fulfillment(address)
+        }
 
-    #[zome_fn("hc_public")]
-    fn get_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_fulfillment(address)
-    }
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("fulfillment", &[
+                "fulfillment_created",
+                "fulfillment_updated",
+                "fulfillment_deleted",
+            ], request)?)
+        }
 
-    #[zome_fn("hc_public")]
-    fn query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-        receive_query_fulfillments(params)
+        // :TODO:
+        // receive: |from, payload| {
+        //     format!("Received: {} from {}", payload, from)
+        // }
     }
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    //   }
-
-
 }
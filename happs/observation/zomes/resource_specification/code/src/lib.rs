@@ -3,7 +3,8 @@
  * Holo-REA resource specification index zome API definition
  *
  * Provides remote indexing capability for resource specifications inside the observation
- * DNA such that resources can be queried by their specifications.
+ * DNA such that resources can be queried by their specifications, and such that the
+ * specification DNA can determine which resources refer to a specification.
  *
  * @package Holo-REA
  */
@@ -14,7 +15,15 @@ extern crate hdk_proc_macros;
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
 
-use hc_zome_rea_resource_specification_storage_consts::{ ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE };
+use hdk_graph_helpers::{
+    delete_policies::get_remote_index_referencing_ids,
+    rpc::{ CapabilityRequest, grant_capability },
+};
+
+use hc_zome_rea_resource_specification_storage_consts::{
+    ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+    RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
+};
 use hc_zome_rea_economic_resource_storage_consts::RESOURCE_BASE_ENTRY_TYPE;
 
 #[zome]
@@ -56,4 +65,20 @@ mod rea_resource_specification_index_zome {
             ]
         )
     }
+
+    #[zome_fn("remote_index")]
+    fn get_conforming_resource_ids(address: Address) -> ZomeApiResult<Vec<Address>> {
+        Ok(get_remote_index_referencing_ids(
+            ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+            RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
+            &address,
+        )?)
+    }
+
+    #[zome_fn("hc_public")]
+    fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+        Ok(grant_capability("resource_specification", &[
+            "get_conforming_resource_ids",
+        ], request)?)
+    }
 }
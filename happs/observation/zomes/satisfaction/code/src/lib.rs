@@ -15,63 +15,50 @@ extern crate serde_json;
 extern crate hdk;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
 use hc_zome_rea_satisfaction_defs::{ entry_def, remote_entry_def };
 use hc_zome_rea_satisfaction_rpc::*;
 use hc_zome_rea_satisfaction_lib_destination::*;
 
-#[zome]
-mod rea_satisfaction_zome {
+record_zome! {
+    zome: rea_satisfaction_zome,
+    record: satisfaction,
+    records: satisfactions,
+    id: SatisfactionAddress,
+    base_entry_def: remote_entry_def,
+    api: [get, get_history, query, check_index_integrity],
+    {
+        #[zome_fn("remote_index")]
+        fn satisfaction_created(satisfaction: CreateRequest) -> ZomeApiResult<ResponseData> {
+            receive_create_satisfaction(satisfaction)
+        }
 
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn satisfaction_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
+        #[zome_fn("remote_index")]
+        fn satisfaction_updated(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
+            receive_update_satisfaction(satisfaction)
+        }
 
-    #[entry_def]
-    fn satisfaction_base_entry_def() -> ValidatingEntryType {
-        remote_entry_def()
-    }
+        #[zome_fn("remote_index")]
+        fn satisfaction_deleted(address: SatisfactionAddress) -> ZomeApiResult<bool> {
+            receive_delete_satisfaction(address)
+        }
 
-    #[zome_fn("hc_public")]
-    fn satisfaction_created(satisfaction: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_satisfaction(satisfaction)
-    }
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("satisfaction", &[
+                "satisfaction_created",
+                "satisfaction_updated",
+                "satisfaction_deleted",
+            ], request)?)
+        }
 
-    #[zome_fn("hc_public")]
-    fn satisfaction_updated(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_satisfaction(satisfaction)
+        // :TODO:
+        // receive: |from, payload| {
+        //     format!("Received: {} from {}", payload, from)
+        // }
     }
-
-    #[zome_fn("hc_public")]
-    fn satisfaction_deleted(address: SatisfactionAddress) -> ZomeApiResult<bool> {
-        receive_delete_satisfaction(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_satisfaction(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-        receive_query_satisfactions(params)
-    }
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
-
 }
@@ -17,64 +17,51 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
-use hc_zome_rea_commitment_defs::{ entry_def, base_entry_def };
+use hc_zome_rea_commitment_defs::{ entry_def, base_entry_def, classification_index_entry_def, scope_index_entry_def, time_index_entry_def, due_index_entry_def };
 use hc_zome_rea_commitment_rpc::*;
 use hc_zome_rea_commitment_lib::*;
 
 // Zome entry type wrappers
-#[zome]
-mod rea_commitment_zome {
+record_zome! {
+    zome: rea_commitment_zome,
+    record: commitment,
+    records: commitments,
+    id: CommitmentAddress,
+    capabilities: COMMITMENT_REMOTE_CAPABILITIES,
+    api: [create, get, get_history, update, delete, query, scope, aggregate, check_index_integrity, migrate_entries, remote_index_outbox],
+    {
+        #[entry_def]
+        fn commitment_classification_index_entry_def() -> ValidatingEntryType {
+            classification_index_entry_def()
+        }
 
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn commitment_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
+        #[entry_def]
+        fn commitment_scope_index_entry_def() -> ValidatingEntryType {
+            scope_index_entry_def()
+        }
 
-    #[entry_def]
-    fn commitment_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
+        #[entry_def]
+        fn commitment_time_index_entry_def() -> ValidatingEntryType {
+            time_index_entry_def()
+        }
 
-    #[zome_fn("hc_public")]
-    fn create_commitment(commitment: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_commitment(commitment)
-    }
+        #[entry_def]
+        fn commitment_due_index_entry_def() -> ValidatingEntryType {
+            due_index_entry_def()
+        }
 
-    #[zome_fn("hc_public")]
-    fn get_commitment(address: CommitmentAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_commitment(address)
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("commitment", &[
+                "get_commitment",
+                "delete_commitment",
+                "update_commitment",
+            ], request)?)
+        }
     }
-
-    #[zome_fn("hc_public")]
-    fn update_commitment(commitment: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_commitment(commitment)
-    }
-
-    #[zome_fn("hc_public")]
-    fn delete_commitment(address: CommitmentAddress) -> ZomeApiResult<bool> {
-        receive_delete_commitment(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_commitments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
-        receive_query_commitments(params)
-    }
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    //   }
-
 }
@@ -15,6 +15,8 @@ extern crate hdk_proc_macros;
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
 
+use hdk_graph_helpers::rpc::{ register_remote_capabilities, require_local_agent };
+
 use hc_zome_rea_fulfillment_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_lib_origin::*;
@@ -25,6 +27,8 @@ mod rea_fulfillment_zome {
 
     #[init]
     fn init() {
+        // :NOTE: bridged DNAs may not yet be available; capabilities are otherwise requested upon first use
+        let _ = register_remote_capabilities(FULFILLMENT_REMOTE_CAPABILITIES);
         Ok(())
     }
 
@@ -53,6 +57,11 @@ mod rea_fulfillment_zome {
         receive_get_fulfillment(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_fulfillment_history(address: FulfillmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_fulfillment_history(address)
+    }
+
     #[zome_fn("hc_public")]
     fn update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_fulfillment(fulfillment)
@@ -64,10 +73,16 @@ mod rea_fulfillment_zome {
     }
 
     #[zome_fn("hc_public")]
-    fn query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
+    fn query_fulfillments(params: QueryParams) -> ZomeApiResult<QueryResponse>{
         receive_query_fulfillments(params)
     }
 
+    #[zome_fn("hc_public")]
+    fn check_index_integrity(repair: bool) -> ZomeApiResult<IntegrityReport> {
+        require_local_agent("fulfillment", "check_index_integrity")?;
+        receive_check_fulfillment_indexes(repair)
+    }
+
     // :TODO: wire up remote indexing API if necessary
 
     // :TODO:
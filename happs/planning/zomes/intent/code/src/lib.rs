@@ -13,65 +13,57 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
-use hc_zome_rea_intent_defs::{ entry_def, base_entry_def };
+use hc_zome_rea_intent_defs::{ entry_def, base_entry_def, search_index_entry_def, classification_index_entry_def, scope_index_entry_def, time_index_entry_def, due_index_entry_def };
 use hc_zome_rea_intent_rpc::*;
 use hc_zome_rea_intent_lib::*;
 
 // Zome entry type wrappers
-#[zome]
-mod rea_intent_zome {
+record_zome! {
+    zome: rea_intent_zome,
+    record: intent,
+    records: intents,
+    id: IntentAddress,
+    capabilities: INTENT_REMOTE_CAPABILITIES,
+    api: [create, get, get_history, update, delete, restore, query, scope, check_index_integrity, migrate_entries, remote_index_outbox],
+    {
+        #[entry_def]
+        fn intent_search_index_entry_def() -> ValidatingEntryType {
+            search_index_entry_def()
+        }
 
-    #[init]
-    fn init() {
-        Ok(())
-    }
+        #[entry_def]
+        fn intent_classification_index_entry_def() -> ValidatingEntryType {
+            classification_index_entry_def()
+        }
 
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
+        #[entry_def]
+        fn intent_scope_index_entry_def() -> ValidatingEntryType {
+            scope_index_entry_def()
+        }
 
-    #[entry_def]
-    fn intent_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
+        #[entry_def]
+        fn intent_time_index_entry_def() -> ValidatingEntryType {
+            time_index_entry_def()
+        }
 
-    #[entry_def]
-    fn intent_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
+        #[entry_def]
+        fn intent_due_index_entry_def() -> ValidatingEntryType {
+            due_index_entry_def()
+        }
 
-    #[zome_fn("hc_public")]
-    fn create_intent(intent: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_intent(intent)
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_intent(address: IntentAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_intent(address)
-    }
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("intent", &[
+                "delete_intent",
+                "update_intent",
+            ], request)?)
+        }
 
-    #[zome_fn("hc_public")]
-    fn update_intent(intent: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_intent(intent)
+        // :TODO: wire up remote indexing API if necessary
     }
-
-    #[zome_fn("hc_public")]
-    fn delete_intent(address: IntentAddress) -> ZomeApiResult<bool> {
-        receive_delete_intent(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
-        receive_query_intents(params)
-    }
-
-    // :TODO: wire up remote indexing API if necessary
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
 }
@@ -15,6 +15,7 @@ extern crate serde;
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
 
+use hdk_graph_helpers::rpc::{ CapabilityRequest, grant_capability };
 // use hdk_graph_helpers::remote_indexes::RemoteEntryLinkRespnse; // :TODO: wire up remote indexing API if necessary
 
 use hc_zome_rea_proposed_intent_defs::{base_entry_def, entry_def};
@@ -45,7 +46,7 @@ mod rea_proposed_intent_zome {
         base_entry_def()
     }
 
-    #[zome_fn("hc_public")]
+    #[zome_fn("remote_index")]
     fn created_proposed_intent(proposed_intent: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_proposed_intent(proposed_intent)
     }
@@ -56,10 +57,23 @@ mod rea_proposed_intent_zome {
     }
 
     #[zome_fn("hc_public")]
+    fn get_proposed_intent_history(address: ProposedIntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_proposed_intent_history(address)
+    }
+
+    #[zome_fn("remote_index")]
     fn deleted_proposed_intent(address: ProposedIntentAddress) -> ZomeApiResult<bool> {
         receive_delete_proposed_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+        Ok(grant_capability("proposed_intent", &[
+            "created_proposed_intent",
+            "deleted_proposed_intent",
+        ], request)?)
+    }
+
     // :TODO:
     // receive: |from, payload| {
     //     format!("Received: {} from {}", payload, from)
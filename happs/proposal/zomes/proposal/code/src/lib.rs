@@ -10,66 +10,32 @@
  */
 extern crate hdk;
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::record_zome;
 
 use vf_core::type_aliases::ProposalAddress;
 
-use hc_zome_rea_proposal_defs::{base_entry_def, entry_def};
+use hc_zome_rea_proposal_defs::{base_entry_def, entry_def, search_index_entry_def, scope_index_entry_def};
 use hc_zome_rea_proposal_lib::*;
 use hc_zome_rea_proposal_rpc::*;
 
 // Zome entry type wrappers
-#[zome]
-mod rea_proposal_zome {
-    #[init]
-    fn init() {
-        Ok(())
+record_zome! {
+    zome: rea_proposal_zome,
+    record: proposal,
+    records: proposals,
+    id: ProposalAddress,
+    api: [create, get, get_history, update, delete, restore, query, scope, check_index_integrity, migrate_entries],
+    {
+        #[entry_def]
+        fn proposal_search_index_entry_def() -> ValidatingEntryType {
+            search_index_entry_def()
+        }
+
+        #[entry_def]
+        fn proposal_scope_index_entry_def() -> ValidatingEntryType {
+            scope_index_entry_def()
+        }
+
+        // :TODO: wire up remote indexing API if necessary
     }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn proposal_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn proposal_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn create_proposal(proposal: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_proposal(proposal)
-    }
-
-    #[zome_fn("hc_public")]
-    fn get_proposal(address: ProposalAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_proposal(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn update_proposal(proposal: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_proposal(proposal)
-    }
-
-    #[zome_fn("hc_public")]
-    fn delete_proposal(address: ProposalAddress) -> ZomeApiResult<bool> {
-        receive_delete_proposal(address)
-    }
-
-    // #[zome_fn("hc_public")]
-    // fn query_proposals(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    //     receive_query_proposals(params)
-    // }
-
-    // :TODO: wire up remote indexing API if necessary
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
 }
@@ -15,6 +15,7 @@ extern crate serde;
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
 
+use hdk_graph_helpers::rpc::{ register_remote_capabilities, require_local_agent };
 // use hdk_graph_helpers::remote_indexes::RemoteEntryLinkRespnse; // :TODO: wire up remote indexing API if necessary
 
 use hc_zome_rea_proposed_intent_defs::{base_entry_def, entry_def};
@@ -27,6 +28,8 @@ mod rea_proposed_intent_zome {
 
     #[init]
     fn init() {
+        // :NOTE: bridged DNAs may not yet be available; capabilities are otherwise requested upon first use
+        let _ = register_remote_capabilities(PROPOSED_INTENT_REMOTE_CAPABILITIES);
         Ok(())
     }
 
@@ -55,11 +58,22 @@ mod rea_proposed_intent_zome {
         receive_get_proposed_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_proposed_intent_history(address: ProposedIntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_proposed_intent_history(address)
+    }
+
     #[zome_fn("hc_public")]
     fn delete_proposed_intent(address: ProposedIntentAddress) -> ZomeApiResult<bool> {
         receive_delete_proposed_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn check_index_integrity(repair: bool) -> ZomeApiResult<IntegrityReport> {
+        require_local_agent("proposed_intent", "check_index_integrity")?;
+        receive_check_proposed_intent_indexes(repair)
+    }
+
     // :TODO: wire up remote indexing API if necessary
 
     // :TODO:
@@ -16,6 +16,7 @@ extern crate hdk_proc_macros;
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
 
+use hdk_graph_helpers::rpc::require_local_agent;
 // use hdk_graph_helpers::remote_indexes::RemoteEntryLinkResponse; // :TODO: wire up remote indexing API if necessary
 
 use hc_zome_rea_proposed_to_defs::{base_entry_def, entry_def};
@@ -57,11 +58,22 @@ mod rea_proposed_to_zome {
         receive_get_proposed_to(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_proposed_to_history(address: ProposedToAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_proposed_to_history(address)
+    }
+
     #[zome_fn("hc_public")]
     fn delete_proposed_to(address: ProposedToAddress) -> ZomeApiResult<bool> {
         receive_delete_proposed_to(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn check_index_integrity(repair: bool) -> ZomeApiResult<IntegrityReport> {
+        require_local_agent("proposed_to", "check_index_integrity")?;
+        receive_check_proposed_to_indexes(repair)
+    }
+
     // :TODO: wire up remote indexing API if necessary
 
     // :TODO:
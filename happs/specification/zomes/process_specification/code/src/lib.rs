@@ -13,66 +13,20 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::record_zome;
 
 use hc_zome_rea_process_specification_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_process_specification_rpc::*;
 use hc_zome_rea_process_specification_lib::*;
 
-
 // Zome entry type wrappers
-#[zome]
-mod rea_process_specification_zome {
-
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn process_specification_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn process_specification_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn create_process_specification(process_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_process_specification(process_specification)
+record_zome! {
+    zome: rea_process_specification_zome,
+    record: process_specification,
+    records: process_specifications,
+    id: ProcessSpecificationAddress,
+    api: [create, get, get_history, update, delete, query, check_index_integrity, migrate_entries],
+    {
+        // :TODO: wire up remote indexing API if necessary
     }
-
-    #[zome_fn("hc_public")]
-    fn get_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_process_specification(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn update_process_specification(process_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_process_specification(process_specification)
-    }
-
-    #[zome_fn("hc_public")]
-    fn delete_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<bool> {
-        receive_delete_process_specification(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_process_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
-        receive_query_process_specifications(params)
-    }
-
-    // :TODO: wire up remote indexing API if necessary
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
 }
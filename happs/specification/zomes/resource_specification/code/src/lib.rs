@@ -13,66 +13,34 @@ extern crate hdk;
 extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
-use hdk_proc_macros::zome;
+use hdk_graph_helpers::{
+    record_zome,
+    rpc::{ CapabilityRequest, grant_capability },
+};
 
-use hc_zome_rea_resource_specification_defs::{ entry_def, base_entry_def };
+use hc_zome_rea_resource_specification_defs::{ entry_def, base_entry_def, search_index_entry_def };
 use hc_zome_rea_resource_specification_rpc::*;
 use hc_zome_rea_resource_specification_lib::*;
 
-
 // Zome entry type wrappers
-#[zome]
-mod rea_resource_specification_zome {
-
-    #[init]
-    fn init() {
-        Ok(())
-    }
-
-    #[validate_agent]
-    pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
-        Ok(())
-    }
-
-    #[entry_def]
-    fn resource_specification_entry_def() -> ValidatingEntryType {
-        entry_def()
-    }
-
-    #[entry_def]
-    fn resource_specification_base_entry_def() -> ValidatingEntryType {
-        base_entry_def()
-    }
-
-    #[zome_fn("hc_public")]
-    fn create_resource_specification(resource_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
-        receive_create_resource_specification(resource_specification)
+record_zome! {
+    zome: rea_resource_specification_zome,
+    record: resource_specification,
+    records: resource_specifications,
+    id: ResourceSpecificationAddress,
+    capabilities: RESOURCE_SPECIFICATION_REMOTE_CAPABILITIES,
+    api: [create, get, get_history, update, delete -> DeleteReport, query, check_index_integrity, migrate_entries],
+    {
+        #[entry_def]
+        fn resource_specification_search_index_entry_def() -> ValidatingEntryType {
+            search_index_entry_def()
+        }
+
+        #[zome_fn("hc_public")]
+        fn request_capability(request: CapabilityRequest) -> ZomeApiResult<Address> {
+            Ok(grant_capability("resource_specification", &[
+                "get_resource_specification",
+            ], request)?)
+        }
     }
-
-    #[zome_fn("hc_public")]
-    fn get_resource_specification(address: ResourceSpecificationAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_resource_specification(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn update_resource_specification(resource_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
-        receive_update_resource_specification(resource_specification)
-    }
-
-    #[zome_fn("hc_public")]
-    fn delete_resource_specification(address: ResourceSpecificationAddress) -> ZomeApiResult<bool> {
-        receive_delete_resource_specification(address)
-    }
-
-    #[zome_fn("hc_public")]
-    fn query_resource_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
-        receive_query_resource_specifications(params)
-    }
-
-    // :TODO: wire up remote indexing API if necessary
-
-    // :TODO:
-    // receive: |from, payload| {
-    //     format!("Received: {} from {}", payload, from)
-    // }
 }
@@ -14,6 +14,7 @@ extern crate hdk_proc_macros;
 
 use hdk::prelude::*;
 use hdk_proc_macros::zome;
+use hdk_graph_helpers::rpc::register_remote_capabilities;
 
 use hc_zome_rea_unit_defs::{ entry_def, id_anchor_entry_def };
 use hc_zome_rea_unit_rpc::*;
@@ -26,6 +27,8 @@ mod rea_unit_zome {
 
     #[init]
     fn init() {
+        // :NOTE: bridged DNAs may not yet be available; capabilities are otherwise requested upon first use
+        let _ = register_remote_capabilities(UNIT_REMOTE_CAPABILITIES);
         Ok(())
     }
 
@@ -54,18 +57,23 @@ mod rea_unit_zome {
         receive_get_unit(id)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_unit_history(id: UnitId) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_unit_history(id)
+    }
+
     #[zome_fn("hc_public")]
     fn update_unit(unit: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_unit(unit)
     }
 
     #[zome_fn("hc_public")]
-    fn delete_unit(id: UnitId) -> ZomeApiResult<bool> {
+    fn delete_unit(id: UnitId) -> ZomeApiResult<DeleteReport> {
         receive_delete_unit(id)
     }
 
     #[zome_fn("hc_public")]
-    fn query_units(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+    fn query_units(params: QueryParams) -> ZomeApiResult<QueryResponse> {
         receive_query_units(params)
     }
 
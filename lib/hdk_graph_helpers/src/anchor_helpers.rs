@@ -17,16 +17,16 @@ use hdk::{
         entry::entry_type::AppEntryType,
         entry::AppEntryValue,
     },
-    error::{ ZomeApiResult, ZomeApiError },
-    entry_address,
-    commit_entry,
-    remove_entry,
-    link_entries,
-    remove_link,
+    get_links,
+    LinkValidationData,
+    holochain_core_types::link::LinkMatch,
 };
 
 use super::{
-    identifiers::{ ANCHOR_POINTER_LINK_TAG, ERR_MSG_ENTRY_NOT_FOUND },
+    read_cache::{ commit_entry, remove_entry, link_entries, remove_link },
+    storage::entry_address,
+    identifiers::{ ANCHOR_POINTER_LINK_TAG },
+    error::{ GraphAPIResult, GraphHelperError },
     links::{
         get_linked_addresses,
     },
@@ -40,18 +40,35 @@ pub fn get_anchor_index_entry_address<E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<Option<Address>>
+) -> GraphAPIResult<Option<Address>>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
     let anchor_address = determine_anchor_index_address(id_entry_type, id_string)?;
 
     // query linked entry
+    // :NOTE: multiple targets should be prevented by `check_anchor_available` & `validate_anchor_link`
     let mut entries: Vec<Address> = get_linked_addresses(&anchor_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
-    // :TODO: ensure only 1 anchor per entry?
     Ok(entries.pop())
 }
 
+/// Ensures that no entry other than `entry_address` (if given) is already registered under
+/// the `anchor index` `id_string`. Returns `GraphHelperError::AlreadyExists` otherwise.
+///
+pub fn check_anchor_available<E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+    entry_address: Option<&Address>,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    match get_anchor_index_entry_address(id_entry_type, id_link_type, id_string)? {
+        Some(existing) if Some(&existing) != entry_address => Err(GraphHelperError::AlreadyExists(id_string.to_owned())),
+        _ => Ok(()),
+    }
+}
+
 /// Reads a set of entries which have been referenced from a base `anchor index`.
 ///
 /// Follows an anchor identified by `anchor_entry_type`, `anchor_link_type` and
@@ -69,7 +86,7 @@ pub fn read_anchored_record_entries<T, E, A>(
     anchor_entry_type: &E,
     anchor_link_type: &str,
     anchor_string: &String,
-) -> ZomeApiResult<Vec<(A, Option<T>)>>
+) -> GraphAPIResult<Vec<(A, GraphAPIResult<T>)>>
     where E: Into<AppEntryType> + Clone,
         A: From<Address>,
         T: Clone + TryFrom<AppEntryValue>,
@@ -83,11 +100,11 @@ pub fn read_anchored_record_entries<T, E, A>(
 fn determine_anchor_index_address<E>(
     id_entry_type: &E,
     id_string: &String,
-) -> ZomeApiResult<Address>
+) -> GraphAPIResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
     let anchor_entry = AppEntry(id_entry_type.to_owned().into(), Some((*id_string).to_owned()).into());
-    entry_address(&anchor_entry)
+    Ok(entry_address(&anchor_entry)?)
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -103,19 +120,16 @@ pub fn create_anchor_index<E>(
     id_link_type: &str,
     id_string: &String,
     entry_address: &Address,
-) -> ZomeApiResult<Address>
+) -> GraphAPIResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
+    check_anchor_available(id_entry_type, id_link_type, id_string, Some(entry_address))?;
+
     let anchor_entry = AppEntry(id_entry_type.to_owned().into(), Some((*id_string).to_owned()).into());
-    let maybe_anchor_address = commit_entry(&anchor_entry);
+    let anchor_address = commit_entry(&anchor_entry)?;
 
-    match maybe_anchor_address {
-        Ok(anchor_address) => {
-            link_entries(&anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;  // :TODO: error handling? Probably fine to treat as critical?
-            Ok(anchor_address)
-        },
-        Err(e) => Err(e),
-    }
+    link_entries(&anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;  // :TODO: error handling? Probably fine to treat as critical?
+    Ok(anchor_address)
 }
 
 //-------------------------------[ UPDATE ]-------------------------------------
@@ -131,7 +145,7 @@ pub fn update_anchor_index<E>(
     entry_address: &Address,
     old_id_string: &String,
     new_id_string: &String,
-) -> ZomeApiResult<Address>
+) -> GraphAPIResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
@@ -142,6 +156,9 @@ pub fn update_anchor_index<E>(
         return Ok(old_anchor_address);
     }
 
+    // check new ID is free before removing the old one
+    check_anchor_available(id_entry_type, id_link_type, new_id_string, Some(entry_address))?;
+
     // wipe old anchor
     remove_link(&old_anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
     remove_entry(&old_anchor_address)?;
@@ -156,7 +173,7 @@ pub fn delete_anchor_index<E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<bool>
+) -> GraphAPIResult<bool>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
@@ -167,7 +184,7 @@ pub fn delete_anchor_index<E>(
 
     // if all validates, wipe anchoring entry & corresponding link
     match check_entry_addr {
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        None => Err(GraphHelperError::IndexMissing(anchor_address)),
         Some(entry_addr) => {
             remove_link(&anchor_address, &entry_addr, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
             remove_entry(&anchor_address)?;
@@ -175,3 +192,29 @@ pub fn delete_anchor_index<E>(
         },
     }
 }
+
+//------------------------------[ VALIDATION ]----------------------------------
+
+/// Link validation callback for the links between an `anchor index` and its target entry.
+/// Rejects any link from an anchor which already points to some other entry, such that
+/// only one record can be registered under each ID.
+///
+/// :NOTE: two agents creating the same ID simultaneously may each pass validation before
+/// seeing the other's link. `check_anchor_available` will report `AlreadyExists` for any
+/// subsequent writes once the DHT has converged.
+///
+pub fn validate_anchor_link(validation_data: &LinkValidationData) -> Result<(), String> {
+    match validation_data {
+        LinkValidationData::LinkAdd { link, .. } => {
+            let link = link.link();
+            let existing = get_links(link.base(), LinkMatch::Exactly(link.link_type()), LinkMatch::Exactly(ANCHOR_POINTER_LINK_TAG))
+                .map_err(|e| e.to_string())?;
+
+            if existing.addresses().iter().any(|addr| addr != link.target()) {
+                return Err(format!("Anchor {} already references another entry", link.base()));
+            }
+            Ok(())
+        },
+        LinkValidationData::LinkRemove { .. } => Ok(()),
+    }
+}
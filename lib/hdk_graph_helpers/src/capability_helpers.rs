@@ -0,0 +1,240 @@
+/**
+ * Capability registry for calls between cooperating zomes & DNAs
+ *
+ * Zome API methods which only need to be called by cooperating zomes (such as the destination
+ * side of `remote indexes`) need not be public. Instead, the zome exposes a public
+ * `request_capability` method which uses `grant_capability` to grant access to a fixed set of
+ * such methods. Capabilities are only granted to the agent running the zome (as the agent of every
+ * bridged DNA), and are assigned to that agent, such that other agents cannot obtain or use them.
+ *
+ * Calling zomes request the capabilities they need with `register_remote_capabilities`, usually
+ * from their `init` callback, and the tokens received are stored as private capability claims.
+ * `capability_token` then looks up the token to use for any given remote zome API method, and
+ * `read_with_capability` calls a method with it. Where no token has been stored (for example,
+ * because the bridged DNA was unavailable at init time), one is requested on demand. Repeated
+ * requests for the same methods are answered with the capability granted previously.
+ *
+ * Administrative zome API methods which must remain public in order to be called by the agent's UI
+ * (such as index integrity checks) are restricted to the local agent with `require_local_agent`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-14
+ */
+use std::convert::TryFrom;
+use serde::de::DeserializeOwned;
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::{
+            Entry,
+            entry_type::EntryType,
+            cap_entries::{ CapabilityType, CapFunctions },
+        },
+    },
+    PUBLIC_TOKEN,
+    AGENT_ADDRESS,
+    CAPABILITY_REQ,
+    commit_capability_grant,
+    commit_capability_claim,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    error::{ GraphAPIResult, GraphHelperError },
+    read_cache::get_entry,
+    storage::query,
+    rpc_helpers::read_from_zome,
+};
+
+/// Name of the public zome API method which grants capabilities to other zomes & DNAs
+///
+pub const CAPABILITY_REQUEST_METHOD: &str = "request_capability";
+
+/// Request format for `CAPABILITY_REQUEST_METHOD`
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityRequest {
+    pub functions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct CapabilityGrantResponse(Address);
+
+/// A set of zome API methods in some other zome or DNA which a zome needs to call
+///
+#[derive(Debug, Clone)]
+pub struct RemoteCapability<'a> {
+    pub dna: &'a str,
+    pub zome: &'a str,
+    pub functions: &'a [&'a str],
+}
+
+/// Identifier of the capability claim holding the token for some remote zome API method
+///
+fn claim_id(dna: &str, zome: &str, fn_name: &str) -> String {
+    format!("{}/{}/{}", dna, zome, fn_name)
+}
+
+/// Identifier of the capability grant for some set of zome API methods
+///
+fn grant_id(zome_name: &str, functions: &[String]) -> String {
+    format!("{}/{}", zome_name, functions.join(","))
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Read the stored capability token for calling `fn_name` in `zome` of the bridged `dna`, if any
+///
+pub fn get_capability_token(dna: &str, zome: &str, fn_name: &str) -> GraphAPIResult<Option<Address>> {
+    let id = claim_id(dna, zome, fn_name);
+    let claim_type = EntryType::CapTokenClaim.to_string();
+
+    // later claims supersede earlier ones
+    for address in query(claim_type.as_str())?.iter().rev() {
+        if let Some(Entry::CapTokenClaim(claim)) = get_entry(address)? {
+            if claim.id() == id {
+                return Ok(Some(claim.token()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Determine the capability token to use for calling `fn_name` in `zome` of the bridged `dna`,
+/// requesting one from the remote zome if none is stored.
+///
+/// Any failure to obtain a capability is returned as an error. Calls to DNAs which are not bridged
+/// fail with `GraphHelperError::BridgeMissing`.
+///
+pub fn capability_token(dna: &str, zome: &str, fn_name: &str) -> GraphAPIResult<Address> {
+    match get_capability_token(dna, zome, fn_name)? {
+        Some(token) => Ok(token),
+        None => request_remote_capability(dna, zome, &[fn_name]),
+    }
+}
+
+/// Call `fn_name` in `zome` of the bridged `dna` using the capability token determined by
+/// `capability_token`. Failures to obtain a token are returned in the same way as failed calls.
+/// @see `rpc::read_from_zome`
+///
+pub fn read_with_capability<R>(dna: &str, zome: &str, fn_name: &str, fn_args: JsonString) -> GraphAPIResult<R>
+    where R: TryFrom<JsonString> + Into<JsonString> + DeserializeOwned,
+{
+    read_from_zome(dna, zome, capability_token(dna, zome, fn_name)?, fn_name, fn_args)
+}
+
+/// Determine whether `fn_name` in `zome` of the bridged `dna` may be called, requesting a capability
+/// for it if none is stored.
+///
+/// Returns `false` only where `dna` is not bridged to this DNA. Any other failure to obtain a
+/// capability (such as the remote zome being unavailable or refusing the request) is returned as
+/// an error, since the method may well exist.
+///
+pub fn is_remote_method_available(dna: &str, zome: &str, fn_name: &str) -> GraphAPIResult<bool> {
+    if get_capability_token(dna, zome, fn_name)?.is_some() {
+        return Ok(true);
+    }
+    match request_remote_capability(dna, zome, &[fn_name]) {
+        Ok(_) => Ok(true),
+        Err(GraphHelperError::BridgeMissing(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Ensure that `fn_name` of `zome_name` is being called by the agent running this zome, either
+/// directly or from one of their bridged DNAs. Calls made by any other agent are rejected with
+/// `GraphHelperError::CapabilityDenied`.
+///
+pub fn require_local_agent(zome_name: &str, fn_name: &str) -> GraphAPIResult<()> {
+    if CAPABILITY_REQ.provenance.source() != *AGENT_ADDRESS {
+        return Err(GraphHelperError::CapabilityDenied {
+            zome: zome_name.to_string(),
+            fn_name: fn_name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Request all `capabilities` from their respective zomes, storing the tokens received.
+///
+pub fn register_remote_capabilities(capabilities: &[RemoteCapability]) -> GraphAPIResult<()> {
+    for capability in capabilities.iter() {
+        request_remote_capability(capability.dna, capability.zome, capability.functions)?;
+    }
+    Ok(())
+}
+
+/// Request a capability for `functions` in `zome` of the bridged `dna`, and store the token received
+/// as a claim against each method.
+///
+fn request_remote_capability(dna: &str, zome: &str, functions: &[&str]) -> GraphAPIResult<Address> {
+    let response: CapabilityGrantResponse = read_from_zome(
+        dna, zome, Address::from(PUBLIC_TOKEN.to_string()), CAPABILITY_REQUEST_METHOD,
+        CapabilityRequest { functions: functions.iter().map(|f| f.to_string()).collect() }.into(),
+    )?;
+    let token = response.0;
+
+    // :NOTE: bridged instances are run by the same agent, who is thus also the grantor
+    for fn_name in functions.iter() {
+        commit_capability_claim(claim_id(dna, zome, fn_name), AGENT_ADDRESS.clone(), token.clone())?;
+    }
+
+    Ok(token)
+}
+
+/// Grant a capability to call the requested zome API methods of `zome_name`, for use in the zome's
+/// `request_capability` method. Only methods listed in `grantable` may be requested; any others
+/// cause the request to be rejected with `GraphHelperError::CapabilityDenied`, as do requests made
+/// by any agent other than the one running the zome.
+///
+/// Returns the capability token to be used by the requestor, which is assigned to the local agent.
+/// Where the same set of methods has been granted before, the existing grant is returned rather
+/// than committing another.
+///
+pub fn grant_capability(zome_name: &str, grantable: &[&str], request: CapabilityRequest) -> GraphAPIResult<Address> {
+    require_local_agent(zome_name, CAPABILITY_REQUEST_METHOD)?;
+
+    if let Some(denied) = request.functions.iter().find(|f| !grantable.contains(&f.as_str())) {
+        return Err(GraphHelperError::CapabilityDenied {
+            zome: zome_name.to_string(),
+            fn_name: denied.to_string(),
+        });
+    }
+
+    let mut requested = request.functions.clone();
+    requested.sort();
+    requested.dedup();
+    let id = grant_id(zome_name, &requested);
+
+    if let Some(token) = find_capability_grant(&id)? {
+        return Ok(token);
+    }
+
+    let mut functions = CapFunctions::new();
+    functions.insert(zome_name.to_string(), requested);
+
+    Ok(commit_capability_grant(id, CapabilityType::Assigned, Some(vec![AGENT_ADDRESS.clone()]), functions)?)
+}
+
+/// Read the token of a capability previously granted with the given `id`, if any.
+/// The token of a grant is the address of its entry.
+///
+fn find_capability_grant(id: &str) -> GraphAPIResult<Option<Address>> {
+    let grant_type = EntryType::CapTokenGrant.to_string();
+
+    for address in query(grant_type.as_str())?.iter().rev() {
+        if let Some(Entry::CapTokenGrant(grant)) = get_entry(address)? {
+            if grant.id() == id {
+                return Ok(Some(address.clone()));
+            }
+        }
+    }
+
+    Ok(None)
+}
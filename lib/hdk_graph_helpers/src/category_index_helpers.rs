@@ -0,0 +1,185 @@
+/**
+ * Helper methods related to `category indexes`.
+ *
+ * A `category index` groups records by a set of string values which they are tagged with,
+ * such as the classification URLs of a resource. Each distinct value is an `anchor` entry,
+ * which links to every record tagged with it. A record may be tagged with any number of values.
+ *
+ * Links are tagged with the value itself, so that all records in a category can be read
+ * without knowing anything else about them.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-19
+ */
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry,
+        entry::Entry::App as AppEntry,
+        entry::entry_type::AppEntryType,
+    },
+};
+
+use super::{
+    read_cache::{ commit_entry, remove_link },
+    storage::entry_address,
+    error::GraphAPIResult,
+    links::{ get_linked_addresses, link_entries_if_absent },
+};
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Determine the addresses of all records in a `category index` which are tagged with `value`
+///
+pub fn read_category_index_addresses<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    value: &str,
+) -> GraphAPIResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    let anchor_address = category_index_anchor_address(index_entry_type, index_id, value)?;
+    get_linked_addresses(&anchor_address, entry_link_type, value)
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Adds the record at `entry_address` to a `category index` under each of the given `values`.
+/// Any anchors not yet present in the index are created, and values the record is already
+/// indexed under are not linked again.
+///
+/// Returns the addresses of the anchors the record was linked from.
+///
+pub fn create_category_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    values: &[String],
+    entry_address: &Address,
+) -> GraphAPIResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    values.iter()
+        .map(|value| {
+            let anchor_address = commit_entry(&category_index_anchor(index_entry_type, index_id, value))?;
+            link_entries_if_absent(&anchor_address, entry_address, entry_link_type, value)?;
+            Ok(anchor_address)
+        })
+        .collect()
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+/// Re-indexes the record at `entry_address` after the values it is tagged with change from
+/// `previous_values` to `values`. Only links for values which have been added or removed are written.
+///
+pub fn update_category_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    previous_values: &[String],
+    values: &[String],
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let (removed, added) = diff_category_values(previous_values, values);
+
+    delete_category_index(index_entry_type, index_id, entry_link_type, &removed, entry_address)?;
+    create_category_index(index_entry_type, index_id, entry_link_type, &added, entry_address)?;
+    Ok(())
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Removes the record at `entry_address` from a `category index` under each of the given `values`.
+///
+/// Anchors are left in place, since they may be shared with other records.
+///
+pub fn delete_category_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    values: &[String],
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    for value in values.iter() {
+        let anchor_address = category_index_anchor_address(index_entry_type, index_id, value)?;
+        remove_link(&anchor_address, entry_address, entry_link_type, value)?;
+    }
+    Ok(())
+}
+
+//------------------------------[ INTERNALS ]-----------------------------------
+
+/// Builds the anchor entry for `value` in the category index named `index_id`
+///
+pub (crate) fn category_index_anchor<E>(index_entry_type: &E, index_id: &str, value: &str) -> Entry
+    where E: Into<AppEntryType> + Clone,
+{
+    AppEntry(index_entry_type.to_owned().into(), Some(format!("{}/{}", index_id, value)).into())
+}
+
+fn category_index_anchor_address<E>(index_entry_type: &E, index_id: &str, value: &str) -> GraphAPIResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    Ok(entry_address(&category_index_anchor(index_entry_type, index_id, value))?)
+}
+
+/// Determines the values removed from & added to `previous_values` in `values`, respectively
+///
+fn diff_category_values(previous_values: &[String], values: &[String]) -> (Vec<String>, Vec<String>) {
+    (
+        previous_values.iter().filter(|value| !values.contains(value)).cloned().collect(),
+        values.iter().filter(|value| !previous_values.contains(value)).cloned().collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+    const INDEX_TYPE: &str = "test_category_index";
+    const LINK_TYPE: &str = "test_category_index_entry";
+
+    fn category(value: &str) -> Vec<Address> {
+        read_category_index_addresses(&INDEX_TYPE.to_string(), "all", LINK_TYPE, value).unwrap()
+    }
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_category_index() {
+        let storage = Rc::new(MockStorage::new());
+        let first = storage.commit_entry(&AppEntry("test_entry".into(), Some("first".to_string()).into())).unwrap();
+        let second = storage.commit_entry(&AppEntry("test_entry".into(), Some("second".to_string()).into())).unwrap();
+
+        with_storage_backend(storage.clone(), || {
+            create_category_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, &values(&["http://x.org/beef", "http://x.org/meat"]), &first).unwrap();
+            create_category_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, &values(&["http://x.org/meat"]), &second).unwrap();
+
+            assert_eq!(category("http://x.org/meat"), vec![first.clone(), second.clone()]);
+            assert_eq!(category("http://x.org/beef"), vec![first.clone()]);
+            assert_eq!(category("http://x.org/"), vec![], "values are matched exactly");
+
+            create_category_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, &values(&["http://x.org/meat"]), &second).unwrap();
+            assert_eq!(storage.get_links(&category_index_anchor_address(&INDEX_TYPE.to_string(), "all", "http://x.org/meat").unwrap(), LINK_TYPE, Some("http://x.org/meat")).unwrap().len(), 2, "existing links are not duplicated");
+
+            update_category_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, &values(&["http://x.org/beef", "http://x.org/meat"]), &values(&["http://x.org/meat", "http://x.org/pork"]), &first).unwrap();
+            assert_eq!(category("http://x.org/beef"), vec![]);
+            assert_eq!(category("http://x.org/pork"), vec![first.clone()]);
+            assert_eq!(category("http://x.org/meat"), vec![first.clone(), second.clone()]);
+
+            delete_category_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, &values(&["http://x.org/meat"]), &second).unwrap();
+            assert_eq!(category("http://x.org/meat"), vec![first.clone()]);
+        });
+    }
+}
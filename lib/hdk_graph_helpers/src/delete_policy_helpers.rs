@@ -0,0 +1,340 @@
+/**
+ * Referential integrity rules for record deletion
+ *
+ * When a record is deleted, other records may still refer to it via `direct indexes`
+ * or `remote indexes`. Each such relationship can be given a `DeletePolicy`:
+ *
+ * - `Restrict` prevents the deletion whilst any references remain
+ * - `Cascade` deletes the referencing records along with the record
+ * - `Nullify` clears the referencing field in each referencing record
+ *
+ * References are discovered from `direct indexes`, from the origin side of `remote indexes`,
+ * or by asking a foreign DNA which holds the destination side of a `remote index`.
+ * Referencing records are modified via their zome API, so that any indexes they manage
+ * are updated in the same way as for a user-initiated change. For records in other DNAs,
+ * this requires a bridge to the DNA which holds them. Where references are read from a
+ * foreign DNA which is not bridged, there can be no referencing records and none are reported.
+ * Any other failure to read references prevents the deletion.
+ *
+ * Changes made to referencing records in other zomes & DNAs cannot be reverted. All policies
+ * are therefore checked before any referencing record is modified, and processing stops at the
+ * first referencing record which cannot be handled.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-10
+ */
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    error::{ GraphAPIResult, GraphHelperError },
+    keys::{ get_key_index_address, determine_key_index_address },
+    links::get_linked_addresses,
+    rpc::{ read_with_capability, is_remote_method_available },
+};
+
+/// Action to take upon records which reference some record being deleted
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeletePolicy {
+    Restrict,
+    Cascade,
+    Nullify,
+}
+
+/// Declares how records referencing the record being deleted via `link_type` & `link_tag` are handled.
+///
+/// `handler` must be provided for `Cascade` and `Nullify` policies.
+///
+#[derive(Debug, Clone)]
+pub struct ReferencePolicy<'a> {
+    pub link_type: &'a str,
+    pub link_tag: &'a str,
+    pub policy: DeletePolicy,
+    pub source: ReferenceSource<'a>,
+    pub handler: Option<ReferenceHandler<'a>>,
+}
+
+/// Where the IDs of referencing records are read from
+///
+#[derive(Debug, Clone)]
+pub enum ReferenceSource<'a> {
+    /// Link targets are the `key indexes` of records in the same DNA
+    Local,
+    /// Link targets are the dangling `key indexes` of records in another DNA
+    RemoteIndex,
+    /// The links are held by the destination side of a `remote index` in another DNA, and
+    /// must be read via `read_fn`. The method must accept an `address` parameter and return
+    /// a list of record IDs; see `get_remote_index_referencing_ids`. If the DNA is not bridged,
+    /// no references are returned; any other failure to call the method is returned as an error.
+    RemoteQuery { dna: &'a str, zome: &'a str, read_fn: &'a str },
+}
+
+/// Location of the zome API methods used to delete or modify referencing records.
+///
+/// `delete_fn` must accept an `address` parameter. `update_fn` is called with a partial update
+/// payload of the form `{ [record_field]: { id, [reference_field]: null } }`. Both are called
+/// with the capability token determined by `rpc::capability_token`.
+///
+#[derive(Debug, Clone)]
+pub struct ReferenceHandler<'a> {
+    pub dna: &'a str,
+    pub zome: &'a str,
+    pub delete_fn: &'a str,
+    pub update_fn: &'a str,
+    pub record_field: &'a str,
+    pub reference_field: &'a str,
+}
+
+impl<'a> ReferencePolicy<'a> {
+    /// Declare a policy for references held by records in the same DNA
+    ///
+    pub fn local(link_type: &'a str, link_tag: &'a str, policy: DeletePolicy) -> Self {
+        ReferencePolicy { link_type, link_tag, policy, source: ReferenceSource::Local, handler: None }
+    }
+
+    /// Declare a policy for references held by records in another DNA, where this DNA
+    /// holds the origin side of the `remote index`
+    ///
+    pub fn remote(link_type: &'a str, link_tag: &'a str, policy: DeletePolicy) -> Self {
+        ReferencePolicy { link_type, link_tag, policy, source: ReferenceSource::RemoteIndex, handler: None }
+    }
+
+    /// Declare a policy for references held by records in another DNA, where only the foreign
+    /// DNA indexes the relationship. `link_type` & `link_tag` identify the relationship in errors.
+    ///
+    pub fn remote_query(
+        link_type: &'a str, link_tag: &'a str, policy: DeletePolicy,
+        dna: &'a str, zome: &'a str, read_fn: &'a str,
+    ) -> Self {
+        ReferencePolicy {
+            link_type, link_tag, policy,
+            source: ReferenceSource::RemoteQuery { dna, zome, read_fn },
+            handler: None,
+        }
+    }
+
+    /// Assign the zome API methods used to cascade or nullify references.
+    /// Use `hdk::THIS_INSTANCE` as the `dna` for records managed by another zome in the same DNA.
+    ///
+    pub fn with_handler(
+        mut self,
+        dna: &'a str, zome: &'a str, delete_fn: &'a str, update_fn: &'a str,
+        record_field: &'a str, reference_field: &'a str,
+    ) -> Self {
+        self.handler = Some(ReferenceHandler { dna, zome, delete_fn, update_fn, record_field, reference_field });
+        self
+    }
+}
+
+/// A referencing record which could not be deleted or modified
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceFailure {
+    pub record: Address,
+    pub policy: DeletePolicy,
+    pub cause: GraphHelperError,
+}
+
+/// Outcome of a deletion made via `delete_record_with_policies`.
+///
+/// The record is only `deleted` if every referencing record was handled successfully.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteReport {
+    pub deleted: bool,
+    pub cascaded: Vec<Address>,
+    pub nullified: Vec<Address>,
+    pub failed: Vec<ReferenceFailure>,
+}
+
+// Request & response formats for reading & modifying referencing records.
+// Modification responses are not decoded, since only the success of the call is of interest.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct ReferenceRequest {
+    address: Address,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct ReferenceActionResponse(serde_json::Value);
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct ReferenceQueryResponse(Vec<Address>);
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Read the IDs of all records in this DNA which reference the foreign record `remote_address`
+/// via the destination side of a `remote index`. For use in the zome API method named by
+/// `ReferenceSource::RemoteQuery`.
+///
+pub fn get_remote_index_referencing_ids(
+    remote_base_entry_type: &str,
+    link_type: &str,
+    link_tag: &str,
+    remote_address: &Address,
+) -> GraphAPIResult<Vec<Address>> {
+    let base_address: Address = determine_key_index_address(remote_base_entry_type.to_string(), remote_address)?;
+    get_linked_addresses(&base_address, link_type, link_tag)
+}
+
+/// Read the IDs of all records referencing `address` via the given relationship
+///
+fn get_referencing_ids(address: &Address, reference: &ReferencePolicy) -> GraphAPIResult<Vec<Address>> {
+    match &reference.source {
+        ReferenceSource::Local => get_linked_addresses(address, reference.link_type, reference.link_tag),
+        ReferenceSource::RemoteIndex => {
+            // remote `key indexes` contain the foreign record ID
+            let targets = get_linked_addresses(address, reference.link_type, reference.link_tag)?;
+            targets.iter().map(get_key_index_address).collect()
+        },
+        ReferenceSource::RemoteQuery { dna, zome, read_fn } => {
+            // records in a DNA which is not bridged to this one cannot reference it
+            if !is_remote_method_available(dna, zome, read_fn)? {
+                return Ok(vec![]);
+            }
+            let result: ReferenceQueryResponse = read_with_capability(
+                dna, zome, read_fn,
+                ReferenceRequest { address: address.clone() }.into(),
+            )?;
+            Ok(result.0)
+        },
+    }
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Deletes the record at `address` using `delete_fn`, after first applying the given `references` policies.
+///
+/// If any `Restrict` policy matches referencing records, the deletion is rejected with
+/// `GraphHelperError::DeleteRestricted` before anything is modified, as it is if references
+/// cannot be read or no handler is configured to cascade or nullify them. Otherwise, referencing
+/// records are cascaded or nullified in turn and the outcome recorded in the returned report.
+/// Upon the first failure, no further records are modified and `delete_fn` is not run.
+///
+pub fn delete_record_with_policies<F>(
+    address: &Address,
+    references: &[ReferencePolicy],
+    delete_fn: F,
+) -> GraphAPIResult<DeleteReport>
+    where F: FnOnce() -> GraphAPIResult<bool>,
+{
+    // determine all referencing records up-front, so that restrictions are enforced before any changes are made
+    let mut referencing = vec![];
+    for reference in references.iter() {
+        let ids = get_referencing_ids(address, reference)?;
+        if ids.is_empty() {
+            continue;
+        }
+        if reference.policy == DeletePolicy::Restrict {
+            return Err(GraphHelperError::DeleteRestricted {
+                link_type: reference.link_type.to_string(),
+                referenced_by: ids,
+            });
+        }
+        if reference.handler.is_none() {
+            return Err(GraphHelperError::Internal(format!("No handler configured for references via {}", reference.link_type)));
+        }
+        referencing.push((reference, ids));
+    }
+
+    let mut report = DeleteReport::default();
+
+    for (reference, ids) in referencing.iter() {
+        for id in ids.iter() {
+            if let Err(cause) = apply_policy(reference, id) {
+                report.failed.push(ReferenceFailure { record: id.clone(), policy: reference.policy, cause });
+                return Ok(report);
+            }
+            match reference.policy {
+                DeletePolicy::Cascade => report.cascaded.push(id.clone()),
+                DeletePolicy::Nullify => report.nullified.push(id.clone()),
+                DeletePolicy::Restrict => (),
+            }
+        }
+    }
+
+    report.deleted = delete_fn()?;
+
+    Ok(report)
+}
+
+/// Cascade or nullify a single referencing record via its zome API
+///
+fn apply_policy(reference: &ReferencePolicy, id: &Address) -> GraphAPIResult<()> {
+    let handler = match (&reference.policy, &reference.handler) {
+        (DeletePolicy::Restrict, _) => return Ok(()),
+        (_, Some(handler)) => handler,
+        (_, None) => return Err(GraphHelperError::Internal(format!("No handler configured for references via {}", reference.link_type))),
+    };
+
+    let result: GraphAPIResult<ReferenceActionResponse> = match reference.policy {
+        DeletePolicy::Cascade => read_with_capability(
+            handler.dna, handler.zome, handler.delete_fn,
+            ReferenceRequest { address: id.clone() }.into(),
+        ),
+        _ => {
+            let mut record = serde_json::Map::new();
+            record.insert("id".to_string(), serde_json::Value::String(id.to_string()));
+            record.insert(handler.reference_field.to_string(), serde_json::Value::Null);
+            let mut payload = serde_json::Map::new();
+            payload.insert(handler.record_field.to_string(), serde_json::Value::Object(record));
+
+            read_with_capability(
+                handler.dna, handler.zome, handler.update_fn,
+                JsonString::from_json(&serde_json::Value::Object(payload).to_string()),
+            )
+        },
+    };
+
+    result.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use std::cell::Cell;
+    use hdk::error::ZomeApiError;
+    use crate::storage::{ MockStorage, with_storage_backend };
+    use crate::rpc::CAPABILITY_REQUEST_METHOD;
+
+    fn unit_policy<'a>() -> ReferencePolicy<'a> {
+        ReferencePolicy::remote_query(
+            "measured_resources", "kg", DeletePolicy::Restrict,
+            "vf_observation", "economic_resource", "get_unit_resource_ids",
+        )
+    }
+
+    #[test]
+    fn test_unbridged_reference_sources_are_skipped() {
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            let report = delete_record_with_policies(&Address::from("kg"), &[unit_policy()], || Ok(true)).unwrap();
+            assert!(report.deleted);
+        });
+    }
+
+    #[test]
+    fn test_unreadable_reference_sources_prevent_deletion() {
+        let storage = Rc::new(MockStorage::new());
+        storage.register_zome_fn("vf_observation", "economic_resource", CAPABILITY_REQUEST_METHOD, |_| {
+            Err(ZomeApiError::Internal("Zome temporarily unavailable".to_string()))
+        });
+        let deleted = Cell::new(false);
+
+        with_storage_backend(storage, || {
+            let result = delete_record_with_policies(&Address::from("kg"), &[unit_policy()], || {
+                deleted.set(true);
+                Ok(true)
+            });
+            assert!(result.is_err());
+            assert!(!deleted.get(), "record retained whilst references cannot be checked");
+        });
+    }
+}
@@ -7,102 +7,264 @@
  */
 use std::convert::{ TryFrom };
 use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
     holochain_persistence_api::cas::content::Address,
     holochain_core_types::{
         entry::Entry,
         entry::Entry::App as AppEntry,
         entry::entry_type::AppEntryType,
         entry::AppEntryValue,
+        crud_status::CrudStatus,
     },
-    error::{ ZomeApiError, ZomeApiResult },
-    entry_address,
-    get_entry,
-    commit_entry,
-    update_entry as hdk_update_entry,
-    remove_entry,
-    utils:: {
-        get_as_type,    // :TODO: switch this method to one which doesn't consume the input
+    holochain_wasm_utils::api_serialization::get_entry::{
+        GetEntryOptions, GetEntryResultType, GetEntryResultItem, StatusRequestKind,
     },
+    error::{ ZomeApiError, ZomeApiResult },
 };
 
 use super::{
-    identifiers::{ ERR_MSG_ENTRY_NOT_FOUND, ERR_MSG_ENTRY_WRONG_TYPE },
+    read_cache::{ get_entry, commit_entry, update_entry as hdk_update_entry, remove_entry },
+    read_cache::get_as_type,    // :TODO: switch this method to one which doesn't consume the input
+    storage::{ entry_address, get_entry_result },
+    schema::stored_entry_data,
     record_interface::Updateable,
+    error::{ GraphAPIResult, GraphHelperError },
 };
+use vf_core::measurement::QuantityAggregate;
 
 //--------------------------------[ READ ]--------------------------------------
 
 /// Loads up all entry data for the input list of `Addresses` and returns a vector
-/// of tuples corresponding to the entry address and deserialized entry data.
+/// of tuples corresponding to the entry address and the result of reading its data.
 ///
-pub (crate) fn get_entries_by_address<R, A>(addresses: Vec<Address>) -> ZomeApiResult<Vec<(A, Option<R>)>>
+/// Results are returned in the same order as the input `addresses`. Entries which cannot be read
+/// report `NotFound`, `WrongType` or `Deleted` in place, rather than being omitted.
+///
+pub (crate) fn get_entries_by_address<R, A>(addresses: Vec<Address>) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
 {
-    let entries: Vec<Option<R>> = addresses.iter()
+    Ok(addresses.into_iter()
         .map(|address| {
-            let entry = get_entry(&address);
-            try_decode_entry(entry)
+            let entry = read_entry(&address);
+            (address.into(), entry)
         })
-        .filter_map(Result::ok)
-        .collect();
-
-    Ok(addresses.iter()
-        .map(|address| {
-            address.to_owned().into()
-        })
-        .zip(entries)
         .collect()
     )
 }
 
 /// Loads up all entry data for the input list of `key indexes` and returns a vector
-/// of tuples corresponding to the entry key's address and deserialized entry data.
+/// of tuples corresponding to the entry key's address and the result of reading its data.
+///
+/// Results are returned in the same order as the input `addresses`. Entries which cannot be read
+/// report `IndexMissing`, `NotFound`, `WrongType` or `Deleted` in place, rather than being omitted.
 ///
-pub (crate) fn get_entries_by_key_index<R, A>(addresses: Vec<Address>) -> ZomeApiResult<Vec<(A, Option<R>)>>
+pub (crate) fn get_entries_by_key_index<R, A>(addresses: Vec<Address>) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
 {
-    let entries: Vec<Option<R>> = addresses.iter()
+    Ok(addresses.into_iter()
         .map(|address| {
-            let entry_address = get_entry(&address)?;
-            let entry = match entry_address {
-                Some(AppEntry(_, entry_address_value)) => {
-                    get_entry(&Address::try_from(entry_address_value)?)
-                },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
-            };
-
-            try_decode_entry(entry)
+            let entry = read_key_indexed_entry(&address);
+            (address.into(), entry)
         })
-        .filter_map(Result::ok)
-        .collect();
-
-    Ok(addresses.iter()
-        .map(|address| {
-            address.to_owned().into()
-        })
-        .zip(entries)
         .collect()
     )
 }
 
+/// A record which could not be read as part of a batch, and the reason why.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordReadError<A> {
+    pub id: A,
+    pub error: GraphHelperError,
+}
+
+/// Output of a batch read for returning to the client. `results` holds all records which were read
+/// successfully; `errors` holds one `RecordReadError` for each of the remainder, so that a single
+/// unreadable record does not prevent the rest from being returned.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordsResponse<A, R> {
+    pub results: Vec<R>,
+    pub errors: Vec<RecordReadError<A>>,
+}
+
+impl<A: serde::Serialize, R: serde::Serialize> From<RecordsResponse<A, R>> for JsonString {
+    fn from(response: RecordsResponse<A, R>) -> JsonString {
+        JsonString::from_json(&serde_json::to_string(&response).unwrap_or_else(|_| "null".to_string()))
+    }
+}
+
+impl<A: serde::de::DeserializeOwned, R: serde::de::DeserializeOwned> TryFrom<JsonString> for RecordsResponse<A, R> {
+    type Error = JsonError;
+    fn try_from(j: JsonString) -> Result<Self, Self::Error> {
+        serde_json::from_str(&String::from(j)).map_err(|e| JsonError::SerializationError(e.to_string()))
+    }
+}
+
+/// Number of records matching a query, as returned by `count_*` endpoints
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CountResponse {
+    pub count: usize,
+}
+
+impl From<CountResponse> for JsonString {
+    fn from(response: CountResponse) -> JsonString {
+        JsonString::from_json(&serde_json::to_string(&response).unwrap_or_else(|_| "null".to_string()))
+    }
+}
+
+impl TryFrom<JsonString> for CountResponse {
+    type Error = JsonError;
+    fn try_from(j: JsonString) -> Result<Self, Self::Error> {
+        serde_json::from_str(&String::from(j)).map_err(|e| JsonError::SerializationError(e.to_string()))
+    }
+}
+
+/// Totals over all records matching a query, as returned by `aggregate_*` endpoints. Quantities
+/// are aggregated separately for each unit. `errors` holds a `RecordReadError` for each matching
+/// record which could not be read, and was therefore excluded from the totals.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateResponse<A> {
+    pub count: usize,
+    pub resource_quantity: Vec<QuantityAggregate>,
+    pub effort_quantity: Vec<QuantityAggregate>,
+    pub errors: Vec<RecordReadError<A>>,
+}
+
+impl<A: serde::Serialize> From<AggregateResponse<A>> for JsonString {
+    fn from(response: AggregateResponse<A>) -> JsonString {
+        JsonString::from_json(&serde_json::to_string(&response).unwrap_or_else(|_| "null".to_string()))
+    }
+}
+
+impl<A: serde::de::DeserializeOwned> TryFrom<JsonString> for AggregateResponse<A> {
+    type Error = JsonError;
+    fn try_from(j: JsonString) -> Result<Self, Self::Error> {
+        serde_json::from_str(&String::from(j)).map_err(|e| JsonError::SerializationError(e.to_string()))
+    }
+}
+
+/// Splits the output of a batch read into the records which were read successfully and the errors
+/// encountered for the remainder, preserving the order of each. Records which have been deleted
+/// but are still linked are reported with `GraphHelperError::Deleted`, so that stale indexes are
+/// visible to callers. Filter out tombstoned records beforehand with `records::exclude_deleted_records`.
+///
+pub fn partition_batch_results<A, R>(results: Vec<(A, GraphAPIResult<R>)>) -> (Vec<(A, R)>, Vec<RecordReadError<A>>) {
+    let mut entries = vec![];
+    let mut errors = vec![];
+
+    for (address, result) in results {
+        match result {
+            Ok(entry) => entries.push((address, entry)),
+            Err(error) => errors.push(RecordReadError { id: address, error }),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// Builds a `RecordsResponse` from the output of a batch read, using `construct` to convert each
+/// readable entry into its response format.
+///
+pub fn build_records_response<A, E, R, F>(results: Vec<(A, GraphAPIResult<E>)>, construct: F) -> RecordsResponse<A, R>
+    where F: Fn(&A, &E) -> R,
+{
+    let (entries, errors) = partition_batch_results(results);
+
+    RecordsResponse {
+        results: entries.iter().map(|(address, entry)| construct(address, entry)).collect(),
+        errors,
+    }
+}
+
+/// Reads and decodes the entry at `address`, distinguishing deleted entries from missing ones.
+///
+fn read_entry<R>(address: &Address) -> GraphAPIResult<R>
+    where R: TryFrom<AppEntryValue>,
+{
+    match try_decode_entry(address, get_entry(address)) {
+        Ok(Some(entry)) => Ok(entry),
+        Ok(None) | Err(GraphHelperError::NotFound(_)) => Err(not_found_or_deleted(address, GraphHelperError::NotFound(address.to_owned()))),
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads and decodes the entry referenced by the `key index` at `key_address`.
+///
+fn read_key_indexed_entry<R>(key_address: &Address) -> GraphAPIResult<R>
+    where R: TryFrom<AppEntryValue>,
+{
+    let index_entry = get_entry(key_address)
+        .map_err(|e| GraphHelperError::from_read_error(e, key_address))?;
+
+    match index_entry {
+        Some(AppEntry(_, entry_address_value)) => {
+            let entry_address = Address::try_from(entry_address_value)?;
+            read_entry(&entry_address)
+        },
+        Some(_) => Err(GraphHelperError::IndexMissing(key_address.to_owned())),
+        None => Err(not_found_or_deleted(key_address, GraphHelperError::IndexMissing(key_address.to_owned()))),
+    }
+}
+
+/// Returns `Deleted` if the entry at `address` once existed and has since been deleted, otherwise `missing_err`.
+///
+fn not_found_or_deleted(address: &Address, missing_err: GraphHelperError) -> GraphHelperError {
+    let result = get_entry_result(address, GetEntryOptions {
+        status_request: StatusRequestKind::All,
+        entry: false,
+        headers: false,
+        timeout: Default::default(),
+    });
+
+    let is_deleted = |item: &GetEntryResultItem| {
+        item.meta.as_ref().map(|meta| meta.crud_status == CrudStatus::Deleted).unwrap_or(false)
+    };
+    let deleted = match result.map(|r| r.result) {
+        Ok(GetEntryResultType::Single(item)) => is_deleted(&item),
+        Ok(GetEntryResultType::All(history)) => history.items.iter().any(is_deleted),
+        Err(_) => false,
+    };
+
+    match deleted {
+        true => GraphHelperError::Deleted(address.to_owned()),
+        false => missing_err,
+    }
+}
+
 /// Helper for handling decoding of entry data to requested entry struct type
 ///
-pub (crate) fn try_decode_entry<R>(entry: ZomeApiResult<Option<Entry>>) -> ZomeApiResult<Option<R>>
+pub (crate) fn try_decode_entry<R>(address: &Address, entry: ZomeApiResult<Option<Entry>>) -> GraphAPIResult<Option<R>>
     where R: TryFrom<AppEntryValue>,
 {
     match entry {
         Ok(Some(AppEntry(_, entry_value))) => {
             match R::try_from(entry_value.to_owned()) {
                 Ok(val) => Ok(Some(val)),
-                Err(_) => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_WRONG_TYPE.to_string())),
+                Err(_) => Err(GraphHelperError::WrongType(address.to_owned())),
             }
         },
-        _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        Ok(Some(_)) => Err(GraphHelperError::WrongType(address.to_owned())),
+        Ok(None) => Err(GraphHelperError::NotFound(address.to_owned())),
+        Err(e) => Err(GraphHelperError::from_read_error(e, address)),
     }
 }
 
+/// Determines the revision ID of some record `entry` data, as returned in record responses
+/// and accepted by `update_entry_from_revision` as the base revision of an update.
+///
+pub fn get_entry_revision<E, S>(entry_type: S, entry: &E) -> Option<Address>
+    where E: Clone + Into<AppEntryValue>,
+        S: Into<AppEntryType>,
+{
+    entry_address(&AppEntry(entry_type.into(), entry.clone().into())).ok()
+}
+
 //-------------------------------[ CREATE ]-------------------------------------
 
 /// Creates a new entry in the DHT and returns a tuple of
@@ -114,7 +276,7 @@ pub (crate) fn try_decode_entry<R>(entry: ZomeApiResult<Option<Entry>>) -> ZomeA
 pub fn create_entry<E, C, S>(
     entry_type: S,
     create_payload: C,
-) -> ZomeApiResult<(Address, E)>
+) -> GraphAPIResult<(Address, E)>
     where E: Clone + Into<AppEntryValue>,
         C: Into<E>,
         S: Into<AppEntryType>,
@@ -144,16 +306,54 @@ pub fn update_entry<E, U, A, S>(
     entry_type: S,
     address: &A,
     update_payload: &U,
-) -> ZomeApiResult<(Address, E)>
+) -> GraphAPIResult<(Address, E)>
+    where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
+        S: Into<AppEntryType> + Clone,
+        A: AsRef<Address>,
+{
+    update_entry_from_revision(entry_type, address, None, update_payload)
+}
+
+/// Same as `update_entry`, but if `base_revision` is provided the update is only applied
+/// if it is still the most recent revision of the entry. Otherwise, the update fails with a
+/// `GraphHelperError::RevisionConflict` containing the current entry data.
+///
+pub fn update_entry_from_revision<E, U, A, S>(
+    entry_type: S,
+    address: &A,
+    base_revision: Option<&Address>,
+    update_payload: &U,
+) -> GraphAPIResult<(Address, E)>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
         A: AsRef<Address>,
 {
-    let prev_entry: E = get_as_type((*(address.as_ref())).clone())?;
-    // :NOTE: to handle update checks we need the *exact* most recent entry address, not that of the head of the entry chain
-    let data_address = entry_address(&(AppEntry(entry_type.clone().into(), prev_entry.to_owned().into())))?;
+    let stored_entry = get_entry(address.as_ref())
+        .map_err(|e| { GraphHelperError::from_read_error(e, address.as_ref()) })?
+        .ok_or(GraphHelperError::NotFound(address.as_ref().clone()))?;
+    // :NOTE: to handle update checks we need the *exact* most recent entry address, not that of the head of the entry chain.
+    // This must be taken from the stored entry rather than re-encoded, as it may be in an earlier schema version.
+    let data_address = entry_address(&stored_entry)?;
+    let prev_entry: E = try_decode_entry(address.as_ref(), Ok(Some(stored_entry)))?
+        .ok_or(GraphHelperError::NotFound(address.as_ref().clone()))?;
+
+    // reject updates made against stale data. Responses report the revision of the entry as
+    // re-encoded in the current schema, which differs for entries stored in earlier versions.
+    if let Some(base_revision) = base_revision {
+        let is_current = *base_revision == data_address
+            || get_entry_revision(entry_type.clone(), &prev_entry).as_ref() == Some(base_revision);
+        if !is_current {
+            let (_, current_entry) = stored_entry_data(&prev_entry.into());
+            return Err(GraphHelperError::RevisionConflict {
+                base_revision: base_revision.clone(),
+                current_revision: data_address,
+                current_entry,
+            });
+        }
+    }
 
     // perform update logic
+    prev_entry.validate_update(update_payload).map_err(GraphHelperError::ValidationFailed)?;
     let new_entry = prev_entry.update_with(update_payload);
 
     // clone entry for returning to caller
@@ -179,7 +379,7 @@ pub fn update_entry<E, U, A, S>(
 ///
 pub fn delete_entry<T>(
     addr: &Address,
-) -> ZomeApiResult<bool>
+) -> GraphAPIResult<bool>
     where T: TryFrom<AppEntryValue>
 {
     let entry_data: ZomeApiResult<T> = get_as_type(addr.to_owned());
@@ -188,6 +388,44 @@ pub fn delete_entry<T>(
             remove_entry(&addr)?;
             Ok(true)
         },
-        Err(_) => Err(ZomeApiError::ValidationFailed("incorrect record type specified for deletion".to_string())),
+        Err(ZomeApiError::HashNotFound) => Err(GraphHelperError::NotFound(addr.to_owned())),
+        Err(_) => Err(GraphHelperError::WrongType(addr.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_results_preserve_order_and_report_deleted() {
+        let (a, b, c, d) = (Address::from("QmA"), Address::from("QmB"), Address::from("QmC"), Address::from("QmD"));
+
+        let (entries, errors) = partition_batch_results(vec![
+            (a.clone(), Err(GraphHelperError::Deleted(a.clone()))),
+            (b.clone(), Ok("b")),
+            (c.clone(), Err(GraphHelperError::WrongType(c.clone()))),
+            (d.clone(), Ok("d")),
+        ]);
+
+        assert_eq!(entries, vec![(b, "b"), (d, "d")]);
+        assert_eq!(errors, vec![
+            RecordReadError { id: a.clone(), error: GraphHelperError::Deleted(a) },
+            RecordReadError { id: c.clone(), error: GraphHelperError::WrongType(c) },
+        ]);
+    }
+
+    #[test]
+    fn test_records_response_returns_readable_records_alongside_errors() {
+        let (a, b, c) = (Address::from("QmA"), Address::from("QmB"), Address::from("QmC"));
+
+        let response = build_records_response(vec![
+            (a.clone(), Ok(1)),
+            (b.clone(), Err(GraphHelperError::NotFound(b.clone()))),
+            (c.clone(), Ok(3)),
+        ], |_, entry| entry * 10);
+
+        assert_eq!(response.results, vec![10, 30]);
+        assert_eq!(response.errors, vec![RecordReadError { id: b.clone(), error: GraphHelperError::NotFound(b) }]);
     }
 }
@@ -0,0 +1,225 @@
+/**
+ * Error types for graph helper operations
+ *
+ * All helpers in this library report failures via `GraphHelperError`, which retains
+ * enough structure for callers to distinguish missing data from type mismatches,
+ * broken indexes and failures in other zomes or DNAs.
+ *
+ * Errors returned from other zomes or DNAs are decoded by the caller and preserved
+ * as the `cause` of a `RemoteCallFailed` error. Where errors must pass through code
+ * which only understands `ZomeApiError`, they are encoded as JSON within
+ * `ZomeApiError::Internal` and restored upon conversion back.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-02
+ */
+use std::fmt;
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    error::{ ZomeApiError },
+};
+use holochain_json_derive::{ DefaultJson };
+
+/// Result type returned by all graph helper methods
+///
+pub type GraphAPIResult<T> = Result<T, GraphHelperError>;
+
+/// Failure states which may be encountered when reading or writing graph data.
+///
+/// Zome API functions may return `GraphAPIResult` directly, in which case errors are
+/// serialized in their structured form within the `Err` payload of the response.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum GraphHelperError {
+    /// No entry could be retrieved from the given address
+    NotFound(Address),
+    /// An entry exists at the given address, but could not be decoded as the requested type
+    WrongType(Address),
+    /// The given index address does not exist, or does not reference any entry
+    IndexMissing(Address),
+    /// An entry existed at the given address, but has since been deleted
+    Deleted(Address),
+    /// Another record is already registered under the given `anchor index` ID
+    AlreadyExists(String),
+    /// A record could not be deleted, since the records listed in `referenced_by` refer to it
+    /// via `link_type` and the relationship's `DeletePolicy` is `Restrict`
+    DeleteRestricted {
+        link_type: String,
+        referenced_by: Vec<Address>,
+    },
+    /// Entry data was rejected by validation rules
+    ValidationFailed(String),
+    /// An update was made against `base_revision` of a record, but the record has since been
+    /// modified. `current_revision` and `current_entry` describe the record's present state.
+    RevisionConflict {
+        base_revision: Address,
+        current_revision: Address,
+        current_entry: serde_json::Value,
+    },
+    /// A call to another zome or DNA failed; `cause` contains the error reported by the callee
+    RemoteCallFailed {
+        dna: String,
+        zome: String,
+        #[serde(rename = "fn")]
+        fn_name: String,
+        cause: Box<GraphHelperError>,
+    },
+    /// A `remote index` could not be synchronised with another DNA, and the request has been stored
+    /// in the outbox entry at `operation` for retrying later. `cause` contains the error encountered
+    /// in sending the request, if it was sent.
+    RemoteSyncDeferred {
+        operation: Address,
+        cause: Option<Box<GraphHelperError>>,
+    },
+    /// A capability was requested for some zome API method which the zome does not grant to other DNAs
+    CapabilityDenied {
+        zome: String,
+        #[serde(rename = "fn")]
+        fn_name: String,
+    },
+    /// A call was made to the DNA with the given bridge handle, but no such bridge is configured
+    BridgeMissing(String),
+    /// A call to another zome or DNA returned data which could not be decoded
+    RemoteResponseMalformed {
+        dna: String,
+        zome: String,
+        #[serde(rename = "fn")]
+        fn_name: String,
+    },
+    /// An operation failed with `cause`, and some of the writes it had already made could not be
+    /// reverted. `rollback_errors` contains each failure encountered whilst reverting.
+    RollbackFailed {
+        cause: Box<GraphHelperError>,
+        rollback_errors: Vec<GraphHelperError>,
+    },
+    /// Any other failure reported by the HDK
+    Internal(String),
+}
+
+impl GraphHelperError {
+    /// Converts an HDK error encountered when reading from `address` into a `GraphHelperError`,
+    /// such that missing entries report the address that was requested.
+    ///
+    pub fn from_read_error(err: ZomeApiError, address: &Address) -> Self {
+        match err {
+            ZomeApiError::HashNotFound => GraphHelperError::NotFound(address.clone()),
+            e => e.into(),
+        }
+    }
+
+    /// Wraps this error as having been returned from a call to some other zome or DNA.
+    ///
+    pub fn into_remote(self, dna: &str, zome: &str, fn_name: &str) -> Self {
+        GraphHelperError::RemoteCallFailed {
+            dna: dna.to_string(),
+            zome: zome.to_string(),
+            fn_name: fn_name.to_string(),
+            cause: Box::new(self),
+        }
+    }
+
+    /// Determines whether the operation which failed with this error might succeed if retried later,
+    /// as when another DNA is not bridged or could not be reached. Errors decoded from the response
+    /// of a remote zome, such as failed validation or missing capabilities, are permanent.
+    ///
+    pub fn is_transient(&self) -> bool {
+        match self {
+            GraphHelperError::RemoteCallFailed { cause, .. } => cause.is_transient(),
+            GraphHelperError::BridgeMissing(_) | GraphHelperError::Internal(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for GraphHelperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphHelperError::NotFound(addr) => write!(f, "No entry at address {}", addr),
+            GraphHelperError::WrongType(addr) => write!(f, "Could not convert entry at {} to requested type", addr),
+            GraphHelperError::IndexMissing(addr) => write!(f, "Index {} does not exist", addr),
+            GraphHelperError::Deleted(addr) => write!(f, "Entry at address {} has been deleted", addr),
+            GraphHelperError::AlreadyExists(id) => write!(f, "A record with ID {} already exists", id),
+            GraphHelperError::DeleteRestricted { link_type, referenced_by } => write!(f, "Cannot delete record referenced by {} other records via {}", referenced_by.len(), link_type),
+            GraphHelperError::ValidationFailed(msg) => write!(f, "Validation failed: {}", msg),
+            GraphHelperError::RevisionConflict { base_revision, current_revision, .. } => write!(f, "Update conflict: revision {} has been superseded by {}", base_revision, current_revision),
+            GraphHelperError::RemoteCallFailed { dna, zome, fn_name, cause } => write!(f, "Error in zome RPC call {}/{}/{}: {}", dna, zome, fn_name, cause),
+            GraphHelperError::RemoteSyncDeferred { operation, cause: Some(cause) } => write!(f, "Remote index sync deferred as {}: {}", operation, cause),
+            GraphHelperError::RemoteSyncDeferred { operation, cause: None } => write!(f, "Remote index sync deferred as {} behind earlier pending requests", operation),
+            GraphHelperError::CapabilityDenied { zome, fn_name } => write!(f, "Zome {} does not grant access to {}", zome, fn_name),
+            GraphHelperError::BridgeMissing(dna) => write!(f, "No bridge to DNA {} is configured", dna),
+            GraphHelperError::RemoteResponseMalformed { dna, zome, fn_name } => write!(f, "Bad zome RPC response format from {}/{}/{}", dna, zome, fn_name),
+            GraphHelperError::RollbackFailed { cause, rollback_errors } => write!(f, "{} (and {} writes could not be reverted)", cause, rollback_errors.len()),
+            GraphHelperError::Internal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Decode errors received from the HDK. Errors encoded by `GraphHelperError` in other zomes
+/// are restored to their original structure.
+///
+impl From<ZomeApiError> for GraphHelperError {
+    fn from(err: ZomeApiError) -> Self {
+        match err {
+            ZomeApiError::Internal(msg) => {
+                match serde_json::from_str(&msg) {
+                    Ok(decoded) => decoded,
+                    Err(_) => GraphHelperError::Internal(msg),
+                }
+            },
+            ZomeApiError::ValidationFailed(msg) => GraphHelperError::ValidationFailed(msg),
+            e => GraphHelperError::Internal(format!("{:?}", e)),
+        }
+    }
+}
+
+impl From<JsonError> for GraphHelperError {
+    fn from(err: JsonError) -> Self {
+        GraphHelperError::Internal(format!("{:?}", err))
+    }
+}
+
+/// Encode errors for returning from zome API functions. Structured errors are serialized
+/// as JSON so that they can be decoded by calling zomes & UI code.
+///
+impl From<GraphHelperError> for ZomeApiError {
+    fn from(err: GraphHelperError) -> Self {
+        match err {
+            GraphHelperError::ValidationFailed(msg) => ZomeApiError::ValidationFailed(msg),
+            GraphHelperError::Internal(msg) => ZomeApiError::Internal(msg),
+            e => ZomeApiError::Internal(serde_json::to_string(&e).unwrap_or_else(|_| e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_errors_survive_zome_boundary() {
+        let err = GraphHelperError::NotFound(Address::from("QmTest"))
+            .into_remote("planning", "commitment", "get_commitment");
+        let encoded: ZomeApiError = err.clone().into();
+        let decoded: GraphHelperError = encoded.into();
+
+        assert_eq!(decoded, err);
+    }
+
+    #[test]
+    fn test_only_unreachable_remotes_are_transient() {
+        assert!(GraphHelperError::BridgeMissing("vf_observation".to_string()).is_transient());
+        assert!(GraphHelperError::Internal("timeout".to_string()).into_remote("vf_observation", "process", "index_committed_inputs").is_transient());
+
+        assert!(!GraphHelperError::ValidationFailed("bad".to_string()).into_remote("vf_observation", "process", "index_committed_inputs").is_transient());
+        assert!(!GraphHelperError::WrongType(Address::from("QmTest")).into_remote("vf_observation", "process", "index_committed_inputs").is_transient());
+        assert!(!GraphHelperError::CapabilityDenied { zome: "process".to_string(), fn_name: "index_committed_inputs".to_string() }.is_transient());
+    }
+
+    #[test]
+    fn test_plain_errors_pass_through() {
+        let decoded: GraphHelperError = ZomeApiError::Internal("something broke".to_string()).into();
+        assert_eq!(decoded, GraphHelperError::Internal("something broke".to_string()));
+    }
+}
@@ -0,0 +1,314 @@
+/**
+ * Integrity checking & repair for record indexes
+ *
+ * Holochain provides no transactionality across multiple DHT writes, so a failed or
+ * interrupted operation can leave the links which make up `direct indexes` and
+ * `remote indexes` in an inconsistent state. These helpers walk the `key indexes` of
+ * some record type and report:
+ *
+ * - links whose reciprocal link (from the target back to the record) is missing
+ * - links to records which have since been deleted
+ * - `key indexes` whose underlying entry has been deleted
+ * - `remote indexes` whose destination record no longer exists in the remote DNA
+ *
+ * Optionally, each problem found can be repaired by re-creating the missing link or
+ * pruning the broken links & entries.
+ *
+ * :NOTE: only records authored by the calling agent are checked, since `key indexes`
+ * are discovered by querying the local source chain.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-09
+ */
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    read_cache::{ get_entry, link_entries, remove_link, remove_entry },
+    storage::query,
+    error::{ GraphAPIResult, GraphHelperError },
+    keys::get_key_index_address,
+    links::get_linked_addresses,
+    rpc::read_with_capability,
+    records::is_record_deleted,
+};
+
+/// Describes one side of a bidirectional index between two records, as seen from the
+/// record type being checked.
+///
+/// For `remote indexes`, `remote` identifies the zome API method which should be used to
+/// confirm that the referenced record still exists in the foreign DNA.
+///
+#[derive(Debug, Clone)]
+pub struct IndexDefinition<'a> {
+    pub link_type: &'a str,
+    pub link_tag: &'a str,
+    pub reciprocal_link_type: &'a str,
+    pub reciprocal_link_tag: &'a str,
+    pub remote: Option<RemoteIndexTarget<'a>>,
+}
+
+/// Location of the zome API method used to read records referenced by a `remote index`.
+/// The method must accept an `address` parameter, and is called with the capability token
+/// determined by `rpc::capability_token`.
+///
+#[derive(Debug, Clone)]
+pub struct RemoteIndexTarget<'a> {
+    pub dna: &'a str,
+    pub zome: &'a str,
+    pub read_fn: &'a str,
+}
+
+impl<'a> IndexDefinition<'a> {
+    /// Define a `direct index` between two records in the same DNA
+    ///
+    pub fn local(
+        link_type: &'a str, link_tag: &'a str,
+        reciprocal_link_type: &'a str, reciprocal_link_tag: &'a str,
+    ) -> Self {
+        IndexDefinition { link_type, link_tag, reciprocal_link_type, reciprocal_link_tag, remote: None }
+    }
+
+    /// Define the origin side of a `direct remote index` to records in another DNA
+    ///
+    pub fn remote(
+        link_type: &'a str, link_tag: &'a str,
+        reciprocal_link_type: &'a str, reciprocal_link_tag: &'a str,
+        dna: &'a str, zome: &'a str, read_fn: &'a str,
+    ) -> Self {
+        IndexDefinition {
+            link_type, link_tag, reciprocal_link_type, reciprocal_link_tag,
+            remote: Some(RemoteIndexTarget { dna, zome, read_fn }),
+        }
+    }
+}
+
+/// Inconsistencies which may be detected by `check_record_indexes`
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexProblem {
+    /// The reciprocal link from `base` to `target` via `link_type` & `link_tag` is missing,
+    /// although `target` links to `base`
+    #[serde(rename_all = "camelCase")]
+    MissingReciprocal { base: Address, target: Address, link_type: String, link_tag: String },
+    /// `base` links to `target` via `link_type` & `link_tag`, but the record at `target` has been deleted
+    #[serde(rename_all = "camelCase")]
+    TargetDeleted { base: Address, target: Address, link_type: String, link_tag: String },
+    /// The `key index` remains, but the record entry it references has been deleted
+    #[serde(rename_all = "camelCase")]
+    OrphanedKeyIndex { key_index: Address, entry: Address },
+    /// `base` links to `target` via `link_type` & `link_tag` (and back via `reciprocal_link_type` &
+    /// `reciprocal_link_tag`), but the foreign record at `remote_address` could not be found in the remote DNA
+    #[serde(rename_all = "camelCase")]
+    RemoteTargetMissing {
+        base: Address,
+        target: Address,
+        link_type: String,
+        link_tag: String,
+        reciprocal_link_type: String,
+        reciprocal_link_tag: String,
+        remote_address: Address,
+    },
+    /// The foreign record referenced by `target` could not be checked, due to some other error
+    #[serde(rename_all = "camelCase")]
+    RemoteCheckFailed { base: Address, target: Address, cause: GraphHelperError },
+}
+
+/// Results of an index integrity check.
+///
+/// `repairs` contains the result of repairing each of `problems`, in the same order.
+/// It is empty when repair was not requested.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub records_checked: usize,
+    pub problems: Vec<IndexProblem>,
+    pub repairs: Vec<GraphAPIResult<()>>,
+}
+
+// Request & response formats for confirming the existence of remote records.
+// Responses are not decoded, since only the success of the call is of interest.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct RemoteRecordRequest {
+    address: Address,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+struct RemoteRecordResponse(serde_json::Value);
+
+//--------------------------------[ CHECK ]-------------------------------------
+
+/// Check all `key indexes` of `base_entry_type` authored by the current agent against the given
+/// set of `indexes`. If `repair` is set, an attempt is made to correct each problem found.
+///
+pub fn check_record_indexes(
+    base_entry_type: &str,
+    indexes: &[IndexDefinition],
+    repair: bool,
+) -> GraphAPIResult<IntegrityReport> {
+    check_indexes_of(base_entry_type, None, indexes, repair)
+}
+
+/// As with `check_record_indexes`, but records which have been tombstoned via their
+/// `initial_entry_link_type` are skipped.
+///
+/// Use this for record types which detach their indexes upon deletion, so that the
+/// intentionally removed links are not reported (and re-created) as missing.
+///
+pub fn check_active_record_indexes(
+    base_entry_type: &str,
+    initial_entry_link_type: &str,
+    indexes: &[IndexDefinition],
+    repair: bool,
+) -> GraphAPIResult<IntegrityReport> {
+    check_indexes_of(base_entry_type, Some(initial_entry_link_type), indexes, repair)
+}
+
+fn check_indexes_of(
+    base_entry_type: &str,
+    initial_entry_link_type: Option<&str>,
+    indexes: &[IndexDefinition],
+    repair: bool,
+) -> GraphAPIResult<IntegrityReport> {
+    let key_indexes = query(base_entry_type)?;
+    let mut records_checked = 0;
+    let mut problems = vec![];
+
+    for key_index in key_indexes.iter() {
+        // skip records which have been cleanly deleted
+        if let None = get_entry(key_index)? { continue; }
+        // skip tombstoned records, if requested
+        if let Some(link_type) = initial_entry_link_type {
+            if is_record_deleted(key_index, link_type)? { continue; }
+        }
+
+        records_checked += 1;
+
+        let entry_address = get_key_index_address(key_index)?;
+        if let None = get_entry(&entry_address)? {
+            problems.push(IndexProblem::OrphanedKeyIndex { key_index: key_index.clone(), entry: entry_address });
+            continue;
+        }
+
+        for index in indexes.iter() {
+            problems.append(&mut check_index(key_index, index)?);
+        }
+    }
+
+    let repairs = match repair {
+        true => problems.iter().map(repair_problem).collect(),
+        false => vec![],
+    };
+
+    Ok(IntegrityReport { records_checked, problems, repairs })
+}
+
+/// Check all links from `base` which form part of the given `index`
+///
+fn check_index(base: &Address, index: &IndexDefinition) -> GraphAPIResult<Vec<IndexProblem>> {
+    let targets = get_linked_addresses(base, index.link_type, index.link_tag)?;
+    let mut problems = vec![];
+
+    for target in targets.iter() {
+        // targets are `key indexes`; for remote indexes, these contain the foreign record ID
+        let target_entry = match get_key_index_address(target) {
+            Ok(addr) => addr,
+            Err(_) => {
+                problems.push(IndexProblem::TargetDeleted {
+                    base: base.clone(),
+                    target: target.clone(),
+                    link_type: index.link_type.to_string(),
+                    link_tag: index.link_tag.to_string(),
+                });
+                continue;
+            },
+        };
+
+        if let Some(remote) = &index.remote {
+            match check_remote_record(remote, &target_entry) {
+                Ok(true) => (),
+                Ok(false) => {
+                    problems.push(IndexProblem::RemoteTargetMissing {
+                        base: base.clone(),
+                        target: target.clone(),
+                        link_type: index.link_type.to_string(),
+                        link_tag: index.link_tag.to_string(),
+                        reciprocal_link_type: index.reciprocal_link_type.to_string(),
+                        reciprocal_link_tag: index.reciprocal_link_tag.to_string(),
+                        remote_address: target_entry,
+                    });
+                    continue;
+                },
+                Err(cause) => {
+                    problems.push(IndexProblem::RemoteCheckFailed { base: base.clone(), target: target.clone(), cause });
+                },
+            }
+        }
+
+        let reciprocal = get_linked_addresses(target, index.reciprocal_link_type, index.reciprocal_link_tag)?;
+        if !reciprocal.contains(base) {
+            problems.push(IndexProblem::MissingReciprocal {
+                base: target.clone(),
+                target: base.clone(),
+                link_type: index.reciprocal_link_type.to_string(),
+                link_tag: index.reciprocal_link_tag.to_string(),
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Determine whether the record at `address` can be read from the remote DNA.
+/// Missing records are reported as `Ok(false)`; any other failure is returned as an error.
+///
+fn check_remote_record(remote: &RemoteIndexTarget, address: &Address) -> GraphAPIResult<bool> {
+    let result: GraphAPIResult<RemoteRecordResponse> = read_with_capability(
+        remote.dna, remote.zome, remote.read_fn,
+        RemoteRecordRequest { address: address.clone() }.into(),
+    );
+
+    match result {
+        Ok(_) => Ok(true),
+        Err(GraphHelperError::RemoteCallFailed { cause, .. }) => match *cause {
+            GraphHelperError::NotFound(_) | GraphHelperError::IndexMissing(_) | GraphHelperError::Deleted(_) => Ok(false),
+            e => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+//--------------------------------[ REPAIR ]------------------------------------
+
+/// Attempt to correct a single problem reported by `check_record_indexes`:
+///
+/// - missing reciprocal links are re-created
+/// - links to deleted records are removed
+/// - orphaned `key indexes` are removed
+/// - links in both directions between a record and a missing remote record are removed
+///
+fn repair_problem(problem: &IndexProblem) -> GraphAPIResult<()> {
+    match problem {
+        IndexProblem::MissingReciprocal { base, target, link_type, link_tag } => {
+            link_entries(base, target, link_type.as_str(), link_tag.as_str())?;
+            Ok(())
+        },
+        IndexProblem::TargetDeleted { base, target, link_type, link_tag } => {
+            Ok(remove_link(base, target, link_type.as_str(), link_tag.as_str())?)
+        },
+        IndexProblem::OrphanedKeyIndex { key_index, .. } => {
+            Ok(remove_entry(key_index)?)
+        },
+        IndexProblem::RemoteTargetMissing { base, target, link_type, link_tag, reciprocal_link_type, reciprocal_link_tag, .. } => {
+            remove_link(base, target, link_type.as_str(), link_tag.as_str())?;
+            Ok(remove_link(target, base, reciprocal_link_type.as_str(), reciprocal_link_tag.as_str())?)
+        },
+        IndexProblem::RemoteCheckFailed { cause, .. } => Err(cause.clone()),
+    }
+}
@@ -7,11 +7,11 @@
  */
 use hdk::{
     holochain_persistence_api::cas::content::Address,
-    error::{ ZomeApiResult },
 };
 
 use crate::{
     MaybeUndefined,
+    error::{ GraphAPIResult },
     keys::{
         get_key_index_address,
     },
@@ -85,7 +85,7 @@ pub (crate) fn wipe_links_from_origin<'a, A, B>(
     link_type_reciprocal: &'a str,
     link_name_reciprocal: &'a str,
     source: &'a A,
-) -> Box<dyn Fn(&'a B) -> Vec<ZomeApiResult<()>> + 'a>
+) -> Box<dyn Fn(&'a B) -> Vec<GraphAPIResult<()>> + 'a>
     where A: AsRef<Address>,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
@@ -20,12 +20,13 @@ use hdk::{
         entry::Entry::App as AppEntry,
         entry::entry_type::AppEntryType,
     },
-    error::{ ZomeApiResult },
-    entry_address,
-    commit_entry,
-    utils:: {
-        get_as_type,    // :TODO: switch this method to one which doesn't consume the input
-    },
+};
+
+use super::{
+    read_cache::{ commit_entry },
+    read_cache::get_as_type,    // :TODO: switch this method to one which doesn't consume the input
+    storage::entry_address,
+    error::{ GraphAPIResult, GraphHelperError },
 };
 
 //--------------------------------[ READ ]--------------------------------------
@@ -33,32 +34,27 @@ use hdk::{
 /// Query the `entry` address for a given `key index` address and return the result in an Address
 /// NewType wrapper of the expected type.
 ///
-pub fn get_key_index_address_as_type<A>(key_address: &Address) -> ZomeApiResult<A>
+pub fn get_key_index_address_as_type<A>(key_address: &Address) -> GraphAPIResult<A>
     where A: AsRef<Address> + From<Address>,
 {
-    let result: ZomeApiResult<Address> = get_as_type(key_address.clone());
-
-    match result {
-        Ok(res) => Ok(res.into()),
-        Err(e) => Err(e),
-    }
+    get_key_index_address(key_address).map(|res| { res.into() })
 }
 
 /// Query the underlying `entry` address for a given `key index` address and return as a raw Address
 ///
-pub (crate) fn get_key_index_address(key_address: &Address) -> ZomeApiResult<Address> {
+pub (crate) fn get_key_index_address(key_address: &Address) -> GraphAPIResult<Address> {
     get_as_type(key_address.clone())
+        .map_err(|e| { GraphHelperError::from_read_error(e, key_address) })
 }
 
 /// Determine the underlying `entry` address for a given external `base_address`, without querying the DHT.
 /// The `base_entry_type` must be provided in order to calculate the entry hash.
 ///
-pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_address: &Address) -> ZomeApiResult<A>
+pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_address: &Address) -> GraphAPIResult<A>
     where S: Into<AppEntryType>,
         A: From<Address>,
 {
-    entry_address(&AppEntry(base_entry_type.into(), (*base_address).clone().into()))
-        .map(|addr| { addr.into() })
+    Ok(entry_address(&AppEntry(base_entry_type.into(), (*base_address).clone().into()))?.into())
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -69,7 +65,7 @@ pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_addres
 pub (crate) fn create_key_index(
     base_entry_type: &AppEntryType,
     referenced_address: &Address,
-) -> ZomeApiResult<Address> {
+) -> GraphAPIResult<Address> {
     let base_entry = AppEntry(base_entry_type.clone().into(), referenced_address.into());
-    commit_entry(&base_entry)
+    Ok(commit_entry(&base_entry)?)
 }
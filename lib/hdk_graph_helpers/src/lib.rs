@@ -11,14 +11,27 @@ extern crate hdk;
 
 mod internals;
 
+mod error_helpers;
 mod entry_helpers;
 mod anchor_helpers;
+mod time_index_helpers;
+mod category_index_helpers;
 mod key_helpers;
 mod local_index_helpers;
 mod remote_index_helpers;
 mod record_helpers;
+mod unit_of_work_helpers;
+mod index_integrity_helpers;
+mod delete_policy_helpers;
+mod outbox_helpers;
 mod link_helpers;
 mod rpc_helpers;
+mod capability_helpers;
+mod zome_helpers;
+mod read_cache_helpers;
+mod storage_helpers;
+mod schema_helpers;
+mod search_helpers;
 
 // API interfaces
 
@@ -26,28 +39,47 @@ pub mod type_wrappers;  // :TODO: make private once unit_requests.rs dependency
 pub mod maybe_undefined;
 pub use maybe_undefined::MaybeUndefined as MaybeUndefined;
 pub mod record_interface;
+pub use error_helpers::{ GraphHelperError, GraphAPIResult };
+
+// dependencies of `record_zome!`
+#[doc(hidden)]
+pub use paste;
+#[doc(hidden)]
+pub use hdk_proc_macros::zome;
 
 // helper functions API
 
+pub mod error { pub use crate::error_helpers::*; }
 pub mod entries { pub use crate::entry_helpers::*; }
-pub mod anchors { pub use crate::anchor_helpers::*; }
+pub mod anchors {
+    pub use crate::anchor_helpers::*;
+    pub use crate::time_index_helpers::*;
+    pub use crate::category_index_helpers::*;
+}
 pub mod links { pub use crate::link_helpers::*; }
 pub mod keys { pub use crate::key_helpers::*; }
 pub mod local_indexes { pub use crate::local_index_helpers::*; }
 pub mod remote_indexes { pub use crate::remote_index_helpers::*; }
-pub mod rpc { pub use crate::rpc_helpers::*; }
-pub mod records { pub use crate::record_helpers::*; }
+pub mod rpc {
+    pub use crate::rpc_helpers::*;
+    pub use crate::capability_helpers::*;
+}
+pub mod integrity { pub use crate::index_integrity_helpers::*; }
+pub mod delete_policies { pub use crate::delete_policy_helpers::*; }
+pub mod outbox { pub use crate::outbox_helpers::*; }
+pub mod read_cache { pub use crate::read_cache_helpers::*; }
+pub mod storage { pub use crate::storage_helpers::*; }
+pub mod schema { pub use crate::schema_helpers::*; }
+pub mod search { pub use crate::search_helpers::*; }
+pub mod records {
+    pub use crate::record_helpers::*;
+    pub use crate::unit_of_work_helpers::*;
+}
 
 pub mod identifiers {
     // Holochain DHT storage type IDs
     pub const RECORD_INITIAL_ENTRY_LINK_TAG: &str = "initial_entry";
     pub const ANCHOR_POINTER_LINK_TAG: &str = "referenced_entry";
-
-    // Error message strings
-    pub const ERR_MSG_ENTRY_NOT_FOUND: &str = "No entry at this address";
-    pub const ERR_MSG_ENTRY_WRONG_TYPE: &str = "Could not convert entry to requested type";
-    pub const ERR_MSG_REMOTE_INDEXING_ERR: &str = "Indexing error in remote DNA call ";
-    pub const ERR_MSG_REMOTE_REQUEST_ERR: &str = "Error in zome RPC call ";
-    pub const ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR: &str = "Bad zome RPC response format from ";
-    pub const ERR_MSG_INDEX_NOT_FOUND: &str = "Given index does not exist";
+    pub const RECORD_TOMBSTONE_LINK_TAG: &str = "deleted";
+    pub const REMOTE_INDEX_OUTBOX_ENTRY_TYPE: &str = "vf_remote_index_outbox";
 }
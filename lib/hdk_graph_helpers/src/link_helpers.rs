@@ -4,29 +4,122 @@
  * Handles common behaviours for linking between data in different hApps,
  * in a way that is predictable, semantically meaningful and easy to reason about.
  *
+ * Links may carry structured attributes describing the relationship, which are
+ * encoded as JSON within the link tag following `LINK_ATTRIBUTES_SEPARATOR`. Such
+ * links can be read and filtered upon without loading the linked entries.
+ * Plain link tags must not contain the separator.
+ *
  * @package HoloREA
  * @since   2019-07-03
  */
 
 use std::borrow::Cow;
+use serde::{ Serialize, de::DeserializeOwned };
 use hdk::{
     holochain_persistence_api::cas::content::Address,
-    holochain_core_types::{
-        link::LinkMatch,
-    },
-    holochain_wasm_utils::api_serialization::get_links::GetLinksOptions,
-    error::{ ZomeApiResult },
-    get_links_with_options,
 };
 
 use super::{
+    read_cache::get_links,
+    error::{ GraphAPIResult, GraphHelperError },
     keys::{
         get_key_index_address,
     },
 };
 
 // HDK re-exports
-pub use hdk::link_entries;
+pub use crate::read_cache_helpers::link_entries;
+
+/// Separates the tag of a link from its encoded attributes
+///
+pub const LINK_ATTRIBUTES_SEPARATOR: char = '|';
+
+/// Attribute values which links must hold in order to match a query.
+/// An empty filter matches all links, including those without attributes.
+///
+pub type LinkAttributeFilter = serde_json::Map<String, serde_json::Value>;
+
+/// A link target, along with the complete tag and any decoded attributes of the link
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributedLink {
+    pub address: Address,
+    pub tag: String,
+    pub attributes: Option<serde_json::Value>,
+}
+
+/// Build the tag for a link of `link_tag` carrying the given `attributes`
+///
+pub fn encode_link_tag<T: Serialize>(link_tag: &str, attributes: &T) -> GraphAPIResult<String> {
+    let encoded = serde_json::to_string(attributes)
+        .map_err(|e| GraphHelperError::Internal(format!("Could not encode link attributes: {}", e)))?;
+    Ok(format!("{}{}{}", link_tag, LINK_ATTRIBUTES_SEPARATOR, encoded))
+}
+
+/// Split a link tag into its plain tag and any encoded attributes
+///
+pub fn decode_link_tag(tag: &str) -> (&str, Option<&str>) {
+    match tag.find(LINK_ATTRIBUTES_SEPARATOR) {
+        Some(pos) => (&tag[..pos], Some(&tag[pos + 1..])),
+        None => (tag, None),
+    }
+}
+
+/// Determines whether link `attributes` contain every field of `filter` with an equal value
+///
+pub fn link_attributes_match(attributes: &Option<serde_json::Value>, filter: &LinkAttributeFilter) -> bool {
+    filter.iter().all(|(key, value)| match attributes {
+        Some(serde_json::Value::Object(attrs)) => attrs.get(key) == Some(value),
+        _ => false,
+    })
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Link from `base_address` to `target_address` via `link_type` and `link_tag`, with
+/// `attributes` describing the relationship encoded in the link tag.
+///
+pub fn link_entries_with_attributes<T: Serialize>(
+    base_address: &Address,
+    target_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+    attributes: &T,
+) -> GraphAPIResult<Address> {
+    let tag = encode_link_tag(link_tag, attributes)?;
+    Ok(link_entries(base_address, target_address, link_type, tag.as_str())?)
+}
+
+/// Link from `base_address` to `target_address` via `link_type` and `link_tag`, unless a live
+/// link between them of the same type & tag already exists.
+///
+/// Returns the address of the new link, or `None` if the link was already present.
+///
+pub fn link_entries_if_absent(
+    base_address: &Address,
+    target_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+) -> GraphAPIResult<Option<Address>> {
+    if get_linked_addresses(base_address, link_type, link_tag)?.contains(target_address) {
+        return Ok(None);
+    }
+    Ok(Some(link_entries(base_address, target_address, link_type, link_tag)?))
+}
+
+/// Same as `link_entries_if_absent`, for links carrying `attributes`. Links holding different
+/// attributes are considered distinct.
+///
+pub fn link_entries_with_attributes_if_absent<T: Serialize>(
+    base_address: &Address,
+    target_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+    attributes: &T,
+) -> GraphAPIResult<Option<Address>> {
+    let tag = encode_link_tag(link_tag, attributes)?;
+    link_entries_if_absent(base_address, target_address, link_type, tag.as_str())
+}
 
 //--------------------------------[ READ ]--------------------------------------
 
@@ -90,20 +183,134 @@ pub fn get_linked_addresses_with_foreign_key_as_type<'a, T, I>(
 /// Load any set of addresses that are linked from the
 /// `base_address` entry via `link_type` and `link_name`.
 ///
+/// Duplicate links (eg. from retried writes) are only reported once.
+///
 pub (crate) fn get_linked_addresses(
     base_address: &Address,
     link_type: &str,
     link_tag: &str,
-) -> ZomeApiResult<Vec<Address>> {
-    let get_links_result = get_links_with_options(
-        base_address,
-        LinkMatch::Exactly(link_type),
-        LinkMatch::Exactly(link_tag),
-        GetLinksOptions::default(),
-    );
-    if let Err(get_links_err) = get_links_result {
-        return Err(get_links_err);
+) -> GraphAPIResult<Vec<Address>> {
+    Ok(dedupe_links(get_links(base_address, link_type, Some(link_tag))?).into_iter()
+        .map(|(_tag, address)| address)
+        .collect())
+}
+
+/// Load the addresses of all entries linked from the `base_address` entry via
+/// `link_type`, along with the tag of each link.
+///
+/// Duplicate links (eg. from retried writes) are only reported once.
+///
+pub (crate) fn get_linked_addresses_with_tags(
+    base_address: &Address,
+    link_type: &str,
+) -> GraphAPIResult<Vec<(String, Address)>> {
+    Ok(dedupe_links(get_links(base_address, link_type, None)?))
+}
+
+/// Drop all but the first of any links with the same tag & target, preserving order
+///
+fn dedupe_links(links: Vec<(String, Address)>) -> Vec<(String, Address)> {
+    let mut unique: Vec<(String, Address)> = Vec::with_capacity(links.len());
+    for link in links {
+        if !unique.contains(&link) {
+            unique.push(link);
+        }
     }
+    unique
+}
 
-    Ok(get_links_result.unwrap().addresses())
+/// Load all links from the `base_address` entry via `link_type` and `link_tag`, whether or not
+/// they have attributes, along with the decoded attributes of each.
+///
+pub fn get_attributed_links(
+    base_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+) -> GraphAPIResult<Vec<AttributedLink>> {
+    get_linked_addresses_with_tags(base_address, link_type)?.into_iter()
+        .filter(|(tag, _address)| decode_link_tag(tag).0 == link_tag)
+        .map(|(tag, address)| {
+            let attributes = match decode_link_tag(&tag).1 {
+                Some(encoded) => Some(serde_json::from_str(encoded)
+                    .map_err(|_| GraphHelperError::WrongType(address.clone()))?),
+                None => None,
+            };
+            Ok(AttributedLink { address, tag, attributes })
+        })
+        .collect()
+}
+
+/// Load the addresses of all entries linked from the `base_address` entry via `link_type`
+/// and `link_tag` whose link attributes match `filter`.
+///
+/// Entries linked more than once (eg. via both a plain link and a variant carrying attributes)
+/// are only reported once.
+///
+pub fn get_linked_addresses_where(
+    base_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+    filter: &LinkAttributeFilter,
+) -> GraphAPIResult<Vec<Address>> {
+    let mut addresses: Vec<Address> = vec![];
+    for link in get_attributed_links(base_address, link_type, link_tag)? {
+        if link_attributes_match(&link.attributes, filter) && !addresses.contains(&link.address) {
+            addresses.push(link.address);
+        }
+    }
+    Ok(addresses)
+}
+
+/// Load the attributes of all links from the `base_address` entry via `link_type` and `link_tag`,
+/// decoded as `T`, without loading the linked entries. Links without attributes yield `None`.
+///
+/// Attributes which cannot be decoded are reported as `WrongType` errors against the link target.
+///
+pub fn get_linked_attributes<T: DeserializeOwned>(
+    base_address: &Address,
+    link_type: &str,
+    link_tag: &str,
+) -> GraphAPIResult<Vec<(Address, Option<T>)>> {
+    get_attributed_links(base_address, link_type, link_tag)?.into_iter()
+        .map(|link| {
+            let attributes = match link.attributes {
+                Some(value) => Some(serde_json::from_value(value)
+                    .map_err(|_| GraphHelperError::WrongType(link.address.clone()))?),
+                None => None,
+            };
+            Ok((link.address, attributes))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_tag_attributes_roundtrip() {
+        let mut attributes = LinkAttributeFilter::new();
+        attributes.insert("action".to_string(), serde_json::Value::String("consume".to_string()));
+
+        let tag = encode_link_tag("inputs", &attributes).unwrap();
+        assert_eq!(tag, "inputs|{\"action\":\"consume\"}");
+        assert_eq!(decode_link_tag(&tag), ("inputs", Some("{\"action\":\"consume\"}")));
+        assert_eq!(decode_link_tag("inputs"), ("inputs", None));
+    }
+
+    #[test]
+    fn test_link_attribute_filters() {
+        let attributes = Some(serde_json::json!({ "action": "consume", "reciprocal": true }));
+        let mut filter = LinkAttributeFilter::new();
+
+        assert!(link_attributes_match(&None, &filter));
+        assert!(link_attributes_match(&attributes, &filter));
+
+        filter.insert("action".to_string(), serde_json::json!("consume"));
+        assert!(link_attributes_match(&attributes, &filter));
+        assert!(!link_attributes_match(&None, &filter));
+
+        filter.insert("reciprocal".to_string(), serde_json::json!(false));
+        assert!(!link_attributes_match(&attributes, &filter));
+    }
 }
@@ -4,35 +4,48 @@
  * A `local index` is a simple set of links between Holochain entries. These are
  * appropriate for linking directly between entries within the same DNA.
  *
+ * Links in either direction may carry attributes describing the relationship,
+ * which can be queried without loading the linked entries. @see `links`
+ *
  * @see     ../README.md
  * @package HDK Graph Helpers
  * @since   2019-05-16
  */
 use std::convert::{ TryFrom };
+use serde::{ Serialize, de::DeserializeOwned };
 use hdk::{
     holochain_json_api::{ json::JsonString },
     holochain_persistence_api::cas::content::Address,
     holochain_core_types::{
         entry::AppEntryValue,
     },
-    error::{ ZomeApiResult },
-    link_entries,
-    remove_link,
 };
 
 use super::{
+    read_cache::remove_link,
     MaybeUndefined,
+    error::{ GraphAPIResult, GraphHelperError },
     entries::{
         get_entries_by_address,
         get_entries_by_key_index,
     },
     links::{
+        LinkAttributeFilter,
         get_linked_addresses,
         get_linked_addresses_as_type,
+        get_linked_addresses_where,
+        get_linked_attributes,
+        get_linked_addresses_with_tags,
+        decode_link_tag,
+        link_entries_if_absent,
+        link_entries_with_attributes_if_absent,
     },
     keys::{
         determine_key_index_address,
     },
+    search::read_search_index_addresses,
+    anchors::{ read_category_index_addresses, read_time_index_addresses },
+    records::is_record_deleted,
     internals::{
         wipe_links_from_origin,
         link_matches,
@@ -47,22 +60,19 @@ use super::{
 /// - linked directly to the `base_address`, without any indirection
 /// - linked via `link_type` and `link_name`
 ///
-/// :TODO: return errors, improve error handling
+/// Entries which fail to load or cannot be converted to the type are reported in place.
 ///
 pub fn query_direct_index<R, F, A>(
     base_address: &F,
     link_type: &str,
     link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address>,
 {
-    let addrs_result = get_linked_addresses(base_address.as_ref(), link_type, link_name);
-    if let Err(get_links_err) = addrs_result {
-        return Err(get_links_err);
-    }
-    get_entries_by_address(addrs_result.unwrap())
+    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_name)?;
+    get_entries_by_address(addrs)
 }
 
 /// Load any set of records of type `R` that are:
@@ -71,24 +81,19 @@ pub fn query_direct_index<R, F, A>(
 /// - linked via `link_type` and `link_name`
 ///
 /// Results are automatically deserialized into `R` as they are retrieved from the DHT.
-/// Any entries that either fail to load or cannot be converted to the type will be dropped.
-///
-/// :TODO: return errors, improve error handling
+/// Entries which fail to load or cannot be converted to the type are reported in place.
 ///
 pub fn query_direct_index_with_foreign_key<R, F, A>(
     base_address: &F,
     link_type: &str,
     link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address>,
 {
-    let addrs_result = get_linked_addresses(base_address.as_ref(), link_type, link_name);
-    if let Err(get_links_err) = addrs_result {
-        return Err(get_links_err);
-    }
-    get_entries_by_key_index(addrs_result.unwrap())
+    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_name)?;
+    get_entries_by_key_index(addrs)
 }
 
 /// Load any set of records of type `R` that are:
@@ -97,36 +102,195 @@ pub fn query_direct_index_with_foreign_key<R, F, A>(
 /// - linked via `link_type` and `link_name`
 ///
 /// Results are automatically deserialized into `R` as they are retrieved from the DHT.
-/// Any entries that either fail to load or cannot be converted to the type will be dropped.
-///
-/// :TODO: return errors, improve error handling
+/// Entries which fail to load or cannot be converted to the type are reported in place.
 ///
 pub fn query_direct_remote_index_with_foreign_key<'a, R, F, A>(
     base_address: &F,
     base_entry_type: &'a str,
     link_type: &str,
     link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address> + Into<JsonString> + Clone,
 {
-    let query_address = determine_key_index_address(base_entry_type.to_string(), base_address.as_ref());
-    if let Err(resolve_remote_err) = query_address {
-        return Err(resolve_remote_err);
+    let query_address = determine_key_index_address(base_entry_type.to_string(), base_address.as_ref())?;
+    let addrs = get_linked_addresses(&query_address, link_type, link_name)?;
+    get_entries_by_key_index(addrs)
+}
+
+/// Read the attributes of all links in a `direct index` from `base_address`, without loading
+/// the linked records. Links without attributes yield `None`.
+///
+pub fn query_direct_index_attributes<T, F, A>(
+    base_address: &F,
+    link_type: &str,
+    link_name: &str,
+) -> GraphAPIResult<Vec<(A, Option<T>)>>
+    where T: DeserializeOwned,
+        A: From<Address>,
+        F: AsRef<Address>,
+{
+    Ok(get_linked_attributes(base_address.as_ref(), link_type, link_name)?.into_iter()
+        .map(|(address, attributes)| (A::from(address), attributes))
+        .collect())
+}
+
+/// A composable filter over `direct indexes`, which resolves to a set of record `key index`
+/// addresses. Used to apply several query parameters at once.
+///
+/// `And` and `Or` results are ordered by the position of each record in the first sub-query
+/// which matched it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexQuery<'a> {
+    /// Records linked from the local `base_address`. @see `query_direct_index_with_foreign_key`
+    Direct { base_address: Address, link_type: &'a str, link_tag: &'a str },
+    /// Records linked from the remote `base_address`. @see `query_direct_remote_index_with_foreign_key`
+    DirectRemote { base_address: Address, base_entry_type: &'a str, link_type: &'a str, link_tag: &'a str },
+    /// Records linked from the local `base_address` via links with attributes matching `filter`
+    Attributed { base_address: Address, link_type: &'a str, link_tag: &'a str, filter: LinkAttributeFilter },
+    /// Records found in a `search index` by the terms of `query`. @see `search`
+    Search { index_entry_type: &'a str, index_id: &'a str, link_type: &'a str, query: String },
+    /// Records tagged with `value` in a `category index`
+    Category { index_entry_type: &'a str, index_id: &'a str, link_type: &'a str, value: String },
+    /// Records in a `time index` from `start` until `end`, inclusive. @see `anchors::read_time_index_addresses`
+    TimeRange { index_entry_type: &'a str, index_id: &'a str, bucket_link_type: &'a str, link_type: &'a str, start: Option<String>, end: Option<String> },
+    /// Records matched by every sub-query. Must contain at least one sub-query.
+    And(Vec<IndexQuery<'a>>),
+    /// Records matched by any sub-query
+    Or(Vec<IndexQuery<'a>>),
+}
+
+impl<'a> IndexQuery<'a> {
+    pub fn direct<F: AsRef<Address>>(base_address: &F, link_type: &'a str, link_tag: &'a str) -> Self {
+        IndexQuery::Direct { base_address: base_address.as_ref().clone(), link_type, link_tag }
     }
 
-    let addrs_result = get_linked_addresses(&query_address.unwrap(), link_type, link_name);
-    if let Err(get_links_err) = addrs_result {
-        return Err(get_links_err);
+    pub fn direct_remote<F: AsRef<Address>>(base_address: &F, base_entry_type: &'a str, link_type: &'a str, link_tag: &'a str) -> Self {
+        IndexQuery::DirectRemote { base_address: base_address.as_ref().clone(), base_entry_type, link_type, link_tag }
     }
-    get_entries_by_key_index(addrs_result.unwrap())
+
+    pub fn attributed<F: AsRef<Address>>(base_address: &F, link_type: &'a str, link_tag: &'a str, filter: LinkAttributeFilter) -> Self {
+        IndexQuery::Attributed { base_address: base_address.as_ref().clone(), link_type, link_tag, filter }
+    }
+
+    pub fn search(index_entry_type: &'a str, index_id: &'a str, link_type: &'a str, query: &str) -> Self {
+        IndexQuery::Search { index_entry_type, index_id, link_type, query: query.to_string() }
+    }
+
+    pub fn category(index_entry_type: &'a str, index_id: &'a str, link_type: &'a str, value: &str) -> Self {
+        IndexQuery::Category { index_entry_type, index_id, link_type, value: value.to_string() }
+    }
+
+    pub fn time_range(index_entry_type: &'a str, index_id: &'a str, bucket_link_type: &'a str, link_type: &'a str, start: Option<String>, end: Option<String>) -> Self {
+        IndexQuery::TimeRange { index_entry_type, index_id, bucket_link_type, link_type, start, end }
+    }
+
+    /// Determine the `key index` addresses of all records matched by this query
+    ///
+    fn resolve(&self) -> GraphAPIResult<Vec<Address>> {
+        match self {
+            IndexQuery::Direct { base_address, link_type, link_tag } => {
+                get_linked_addresses(base_address, link_type, link_tag)
+            },
+            IndexQuery::DirectRemote { base_address, base_entry_type, link_type, link_tag } => {
+                let query_address: Address = determine_key_index_address(base_entry_type.to_string(), base_address)?;
+                get_linked_addresses(&query_address, link_type, link_tag)
+            },
+            IndexQuery::Attributed { base_address, link_type, link_tag, filter } => {
+                get_linked_addresses_where(base_address, link_type, link_tag, filter)
+            },
+            IndexQuery::Search { index_entry_type, index_id, link_type, query } => {
+                read_search_index_addresses(&index_entry_type.to_string(), index_id, link_type, query)
+            },
+            IndexQuery::Category { index_entry_type, index_id, link_type, value } => {
+                read_category_index_addresses(&index_entry_type.to_string(), index_id, link_type, value)
+            },
+            IndexQuery::TimeRange { index_entry_type, index_id, bucket_link_type, link_type, start, end } => {
+                read_time_index_addresses(
+                    &index_entry_type.to_string(), index_id, bucket_link_type, link_type,
+                    start.as_ref().map(String::as_str), end.as_ref().map(String::as_str),
+                )
+            },
+            IndexQuery::And(queries) => {
+                let mut terms = queries.iter();
+                let mut results = match terms.next() {
+                    Some(query) => query.resolve()?,
+                    // there is no way of listing "all records", so an empty intersection is meaningless
+                    None => return Err(GraphHelperError::Internal("No query filters given".to_string())),
+                };
+                for query in terms {
+                    if results.is_empty() {
+                        break;
+                    }
+                    results = intersect_addresses(results, &query.resolve()?);
+                }
+                Ok(results)
+            },
+            IndexQuery::Or(queries) => {
+                let mut results = vec![];
+                for query in queries {
+                    results = union_addresses(results, query.resolve()?);
+                }
+                Ok(results)
+            },
+        }
+    }
+}
+
+/// Load the set of records of type `R` matched by an `IndexQuery`.
+///
+/// Results are automatically deserialized into `R` as they are retrieved from the DHT.
+/// Entries which fail to load or cannot be converted to the type are reported in place.
+///
+pub fn query_index<R, A>(
+    query: &IndexQuery,
+) -> GraphAPIResult<Vec<(A, GraphAPIResult<R>)>>
+    where R: Clone + TryFrom<AppEntryValue>,
+        A: From<Address>,
+{
+    get_entries_by_key_index(query.resolve()?)
+}
+
+/// Count the records matched by `query`, without loading any of them.
+///
+/// As with `records::exclude_deleted_records`, records which have been marked as deleted are not
+/// counted. `initial_entry_link_type` must be the one the records were created with.
+///
+pub fn count_index(query: &IndexQuery, initial_entry_link_type: &str) -> GraphAPIResult<usize> {
+    let mut count = 0;
+    for address in query.resolve()? {
+        if !is_record_deleted(&address, initial_entry_link_type)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn intersect_addresses(left: Vec<Address>, right: &Vec<Address>) -> Vec<Address> {
+    left.into_iter()
+        .filter(|address| right.contains(address))
+        .collect()
+}
+
+fn union_addresses(mut left: Vec<Address>, right: Vec<Address>) -> Vec<Address> {
+    for address in right {
+        if !left.contains(&address) {
+            left.push(address);
+        }
+    }
+    left
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
 
 /// Creates a bidirectional link between two entry addresses, and returns a vector
 /// of the addresses of the (respectively) forward & reciprocal links created.
+///
+/// Either link is skipped if already present, so that retried calls do not write duplicate
+/// links. Skipped links are omitted from the returned addresses.
+///
 pub fn create_direct_index<S: Into<String>>(
     source: &Address,
     dest: &Address,
@@ -134,11 +298,38 @@ pub fn create_direct_index<S: Into<String>>(
     link_name: S,
     link_type_reciprocal: S,
     link_name_reciprocal: S,
-) -> Vec<ZomeApiResult<Address>> {
+) -> Vec<GraphAPIResult<Address>> {
+    let (link_type, link_name): (String, String) = (link_type.into(), link_name.into());
+    let (link_type_reciprocal, link_name_reciprocal): (String, String) = (link_type_reciprocal.into(), link_name_reciprocal.into());
+
+    vec! [
+        link_entries_if_absent(source, dest, &link_type, &link_name),
+        link_entries_if_absent(dest, source, &link_type_reciprocal, &link_name_reciprocal),
+    ].into_iter()
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// Creates a bidirectional link between two entry addresses, with `attributes` describing the
+/// relationship encoded in both links. Returns the addresses of the forward & reciprocal links created.
+///
+/// As with `create_direct_index`, links already present with the same attributes are skipped.
+///
+pub fn create_direct_index_with_attributes<T: Serialize>(
+    source: &Address,
+    dest: &Address,
+    link_type: &str,
+    link_name: &str,
+    link_type_reciprocal: &str,
+    link_name_reciprocal: &str,
+    attributes: &T,
+) -> Vec<GraphAPIResult<Address>> {
     vec! [
-        link_entries(source, dest, link_type, link_name),
-        link_entries(dest, source, link_type_reciprocal, link_name_reciprocal),
-    ]
+        link_entries_with_attributes_if_absent(source, dest, link_type, link_name, attributes),
+        link_entries_with_attributes_if_absent(dest, source, link_type_reciprocal, link_name_reciprocal, attributes),
+    ].into_iter()
+        .filter_map(Result::transpose)
+        .collect()
 }
 
 //-------------------------------[ UPDATE ]-------------------------------------
@@ -163,7 +354,7 @@ pub fn replace_direct_index<A, B>(
     link_name: &str,
     link_type_reciprocal: &str,
     link_name_reciprocal: &str,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
@@ -181,14 +372,14 @@ pub fn replace_direct_index<A, B>(
 
     // wipe stale links
     // :TODO: propagate errors
-    let _erased: Vec<ZomeApiResult<()>> = to_erase.iter().flat_map(wipe_links_from_origin(
+    let _erased: Vec<GraphAPIResult<()>> = to_erase.iter().flat_map(wipe_links_from_origin(
         link_type, link_name,
         link_type_reciprocal, link_name_reciprocal,
         source,
     )).collect();
 
     // get base addresses of erased items
-    let erased: Vec<ZomeApiResult<Address>> = to_erase.iter().map(|addr| { Ok((*addr).as_ref().clone()) }).collect();
+    let erased: Vec<GraphAPIResult<Address>> = to_erase.iter().map(|addr| { Ok((*addr).as_ref().clone()) }).collect();
 
     // run insert if needed
     match new_dest {
@@ -210,10 +401,43 @@ pub fn replace_direct_index<A, B>(
     }
 }
 
+/// Removes the reciprocal links of a `direct index` from every entry linked from `source`, so that
+/// those entries no longer resolve to `source`. Links from `source` are left intact, such that the
+/// index can later be rebuilt with `reattach_direct_index`. Used to hide records marked as deleted
+/// from the link fields of records which reference them. @see `records::tombstone_record`
+///
+pub fn detach_direct_index(
+    source: &Address,
+    link_type: &str,
+    link_name: &str,
+    link_type_reciprocal: &str,
+    link_name_reciprocal: &str,
+) -> GraphAPIResult<()> {
+    for dest in get_linked_addresses(source, link_type, link_name)?.iter() {
+        remove_link(dest, source, link_type_reciprocal, link_name_reciprocal)?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the reciprocal links of a `direct index` removed by `detach_direct_index`.
+///
+pub fn reattach_direct_index(
+    source: &Address,
+    link_type: &str,
+    link_name: &str,
+    link_type_reciprocal: &str,
+    link_name_reciprocal: &str,
+) -> GraphAPIResult<()> {
+    for dest in get_linked_addresses(source, link_type, link_name)?.iter() {
+        link_entries_if_absent(dest, source, link_type_reciprocal, link_name_reciprocal)?;
+    }
+    Ok(())
+}
+
 //-------------------------------[ DELETE ]-------------------------------------
 
-/// Deletes a bidirectional link between two entry addresses, and returns any errors encountered
-/// to the caller.
+/// Deletes a bidirectional link between two entry addresses, along with any variants of either
+/// link carrying attributes, and returns any errors encountered to the caller.
 ///
 /// :TODO: filter empty success tuples from results and return as flattened error array
 ///
@@ -224,9 +448,126 @@ pub fn delete_direct_index<S: Into<String>>(
     link_name: S,
     link_type_reciprocal: S,
     link_name_reciprocal: S,
-) -> Vec<ZomeApiResult<()>> {
-    vec! [
-        remove_link(source, dest, link_type, link_name),
-        remove_link(dest, source, link_type_reciprocal, link_name_reciprocal),
-    ]
+) -> Vec<GraphAPIResult<()>> {
+    let (link_type, link_name): (String, String) = (link_type.into(), link_name.into());
+    let (link_type_reciprocal, link_name_reciprocal): (String, String) = (link_type_reciprocal.into(), link_name_reciprocal.into());
+
+    let mut results = vec![];
+    for (from, to, link_type, link_name) in [
+        (source, dest, &link_type, &link_name),
+        (dest, source, &link_type_reciprocal, &link_name_reciprocal),
+    ].iter() {
+        results.push(remove_link(from, to, link_type.as_str(), link_name.as_str()).map_err(GraphHelperError::from));
+
+        match get_linked_addresses_with_tags(from, link_type) {
+            Ok(links) => results.extend(links.iter()
+                .filter(|(tag, address)| address == *to && tag != *link_name && decode_link_tag(tag).0 == link_name.as_str())
+                .map(|(tag, _)| remove_link(from, to, link_type.as_str(), tag.as_str()).map_err(GraphHelperError::from))),
+            Err(e) => results.push(Err(e)),
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+    use crate::identifiers::RECORD_TOMBSTONE_LINK_TAG;
+
+    #[test]
+    fn test_address_set_operations_preserve_order() {
+        let (a, b, c, d) = (Address::from("QmA"), Address::from("QmB"), Address::from("QmC"), Address::from("QmD"));
+
+        assert_eq!(
+            intersect_addresses(vec![c.clone(), a.clone(), b.clone()], &vec![b.clone(), c.clone(), d.clone()]),
+            vec![c.clone(), b.clone()],
+        );
+        assert_eq!(
+            union_addresses(vec![c.clone(), a.clone()], vec![b.clone(), a.clone(), d.clone()]),
+            vec![c, a, b, d],
+        );
+    }
+
+    #[test]
+    fn test_count_excludes_deleted_records() {
+        let storage = Rc::new(MockStorage::new());
+        let (process, first, second) = (Address::from("QmProcess"), Address::from("QmFirst"), Address::from("QmSecond"));
+        storage.link_entries(&process, &first, "inputs", "inputs").unwrap();
+        storage.link_entries(&process, &second, "inputs", "inputs").unwrap();
+        storage.link_entries(&second, &Address::from("QmSecondEntry"), "entry", RECORD_TOMBSTONE_LINK_TAG).unwrap();
+
+        with_storage_backend(storage.clone(), || {
+            assert_eq!(count_index(&IndexQuery::direct(&process, "inputs", "inputs"), "entry").unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn test_direct_index_creation_is_idempotent() {
+        let storage = Rc::new(MockStorage::new());
+        let (event, commitment, other) = (Address::from("QmEvent"), Address::from("QmCommitment"), Address::from("QmOther"));
+
+        // simulate a previous attempt which failed after writing only the reciprocal link
+        storage.link_entries(&commitment, &event, "fulfilled_by", "fulfilled_by").unwrap();
+
+        with_storage_backend(storage.clone(), || {
+            assert_eq!(create_direct_index(&event, &commitment, "fulfills", "fulfills", "fulfilled_by", "fulfilled_by").len(), 1);
+            assert_eq!(create_direct_index(&event, &commitment, "fulfills", "fulfills", "fulfilled_by", "fulfilled_by").len(), 0);
+        });
+        assert_eq!(storage.count_links(&event, "fulfills"), 1);
+        assert_eq!(storage.count_links(&commitment, "fulfilled_by"), 1);
+
+        // duplicates written by other means are reported once, in order of first appearance
+        storage.link_entries(&event, &other, "fulfills", "fulfills").unwrap();
+        storage.link_entries(&event, &commitment, "fulfills", "fulfills").unwrap();
+        with_storage_backend(storage.clone(), || {
+            assert_eq!(get_linked_addresses(&event, "fulfills", "fulfills").unwrap(), vec![commitment.clone(), other.clone()]);
+        });
+    }
+
+    #[test]
+    fn test_detached_index_retains_forward_links() {
+        let storage = Rc::new(MockStorage::new());
+        let (proposal, proposed_intent) = (Address::from("QmProposal"), Address::from("QmProposedIntent"));
+
+        with_storage_backend(storage.clone(), || {
+            create_direct_index(&proposal, &proposed_intent, "publishes", "publishes", "published_in", "published_in");
+
+            detach_direct_index(&proposal, "publishes", "publishes", "published_in", "published_in").unwrap();
+            assert_eq!(get_linked_addresses(&proposed_intent, "published_in", "published_in").unwrap(), vec![]);
+            assert_eq!(get_linked_addresses(&proposal, "publishes", "publishes").unwrap(), vec![proposed_intent.clone()]);
+
+            reattach_direct_index(&proposal, "publishes", "publishes", "published_in", "published_in").unwrap();
+            assert_eq!(get_linked_addresses(&proposed_intent, "published_in", "published_in").unwrap(), vec![proposal.clone()]);
+        });
+    }
+
+    #[test]
+    fn test_deleted_index_removes_attributed_links() {
+        let storage = Rc::new(MockStorage::new());
+        let (event, process, other) = (Address::from("QmEvent"), Address::from("QmProcess"), Address::from("QmOther"));
+        let mut consume = LinkAttributeFilter::new();
+        consume.insert("action".to_string(), serde_json::Value::String("consume".to_string()));
+
+        with_storage_backend(storage.clone(), || {
+            create_direct_index(&event, &process, "input_of", "input_of", "inputs", "inputs");
+            create_direct_index_with_attributes(&event, &process, "input_of", "input_of", "inputs", "inputs", &consume);
+            create_direct_index_with_attributes(&other, &process, "input_of", "input_of", "inputs", "inputs", &consume);
+            assert_eq!(
+                IndexQuery::attributed(&process, "inputs", "inputs", consume.clone()).resolve().unwrap(),
+                vec![event.clone(), other.clone()],
+            );
+
+            let results = delete_direct_index(&event, &process, "input_of", "input_of", "inputs", "inputs");
+            assert!(results.iter().all(Result::is_ok));
+            assert_eq!(get_linked_addresses(&process, "inputs", "inputs").unwrap(), vec![]);
+            assert_eq!(
+                IndexQuery::attributed(&process, "inputs", "inputs", consume.clone()).resolve().unwrap(),
+                vec![other.clone()],
+                "attributed links to other records are retained",
+            );
+        });
+        assert_eq!(storage.count_links(&event, "input_of"), 0);
+    }
 }
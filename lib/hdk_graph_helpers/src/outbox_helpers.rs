@@ -0,0 +1,241 @@
+/**
+ * Durable outbox for `remote index` synchronisation
+ *
+ * Building a `remote index` requires a call into the DNA holding the destination side of
+ * the index. If that call fails because the remote DNA is unavailable, the request is stored
+ * as a private entry on the caller's source chain rather than being discarded. Stored requests
+ * can later be replayed in the order they were made via `replay_pending_index_operations`, and
+ * inspected with `read_pending_index_operations`. Requests rejected by the remote DNA would fail
+ * in the same way if retried, so their errors are returned to the caller instead.
+ *
+ * Whilst any request to some remote index is pending, subsequent requests against the same
+ * remote index and origin record are also queued, so that replaying them cannot reorder
+ * additions & removals. Replay is idempotent; indexes which already exist in the remote DNA
+ * are not recreated. Capability tokens are looked up upon each attempt rather than stored with
+ * the request, since a capability may have been granted since the request was made.
+ *
+ * Zomes which create `remote indexes` must include `outbox_entry_def` in their entry definitions.
+ * Otherwise, failed requests cannot be stored and their errors are returned as before.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-13
+ */
+use std::convert::TryFrom;
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        dna::entry_types::Sharing,
+        entry::Entry::App as AppEntry,
+        validation::EntryValidationData,
+    },
+    entry_definition::ValidatingEntryType,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    read_cache::{ get_entry, commit_entry, remove_entry },
+    storage::query,
+    error::{ GraphAPIResult, GraphHelperError },
+    identifiers::REMOTE_INDEX_OUTBOX_ENTRY_TYPE,
+    remote_index_helpers::send_remote_index_operation,
+};
+
+/// A request to synchronise the destination side of a `remote index`, as stored in the outbox.
+///
+/// `sequence` orders requests in the sequence they were made. `attempts` counts failed attempts
+/// to send the request, and `last_error` holds the most recent failure, if any.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingIndexOperation {
+    pub sequence: usize,
+    pub remote_dna_id: String,
+    pub remote_zome_id: String,
+    pub remote_zome_method: String,
+    pub source_base_address: Address,
+    pub target_entries: Vec<Address>,
+    pub removed_entries: Vec<Address>,
+    pub attempts: u32,
+    pub last_error: Option<GraphHelperError>,
+}
+
+/// A pending operation, along with the address of its outbox entry
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+    pub address: Address,
+    pub operation: PendingIndexOperation,
+}
+
+/// Outcome of `replay_pending_index_operations`.
+///
+/// `synced` lists the outbox entries which were sent successfully and removed. `pending`
+/// lists all operations remaining in the outbox, with updated error information. `failed`
+/// lists operations which were rejected by the remote DNA, and so have been removed from the outbox.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxReplayReport {
+    pub synced: Vec<Address>,
+    pub pending: Vec<OutboxItem>,
+    pub failed: Vec<OutboxItem>,
+}
+
+impl PendingIndexOperation {
+    /// Determines whether this operation affects the same `remote index` & origin record as `other`
+    ///
+    fn same_index_as(&self, other: &PendingIndexOperation) -> bool {
+        self.remote_dna_id == other.remote_dna_id
+            && self.remote_zome_id == other.remote_zome_id
+            && self.remote_zome_method == other.remote_zome_method
+            && self.source_base_address == other.source_base_address
+    }
+
+    /// Determines whether this operation could still be delivered, i.e. it has not been rejected
+    /// by the remote DNA. Only such operations hold back later ones against the same index.
+    ///
+    fn is_deliverable(&self) -> bool {
+        match &self.last_error {
+            Some(e) => e.is_transient(),
+            None => true,
+        }
+    }
+}
+
+/// Entry definition for the outbox. Entries are private, so are never published to the DHT.
+///
+pub fn outbox_entry_def() -> ValidatingEntryType {
+    hdk::entry!(
+        name: REMOTE_INDEX_OUTBOX_ENTRY_TYPE,
+        description: "Requests to synchronise remote indexes which have not yet been accepted by the remote DNA",
+        sharing: Sharing::Private,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: EntryValidationData<PendingIndexOperation>| {
+            Ok(())
+        }
+    )
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Read all operations remaining in the outbox of the current agent, in the order they were made
+///
+pub fn read_pending_index_operations() -> GraphAPIResult<Vec<OutboxItem>> {
+    let addresses = query(REMOTE_INDEX_OUTBOX_ENTRY_TYPE)?;
+
+    let mut items = vec![];
+    for address in addresses.into_iter() {
+        // entries which have been removed were synced successfully
+        let operation = match get_entry(&address)? {
+            Some(AppEntry(_, value)) => PendingIndexOperation::try_from(value)
+                .map_err(|_| GraphHelperError::WrongType(address.clone()))?,
+            Some(_) => return Err(GraphHelperError::WrongType(address)),
+            None => continue,
+        };
+        items.push(OutboxItem { address, operation });
+    }
+
+    items.sort_by_key(|item| item.operation.sequence);
+    Ok(items)
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Send `operation` to the remote DNA, unless earlier operations against the same index are still
+/// pending. If the operation is not sent, or fails because the remote DNA is unavailable, it is
+/// stored in the outbox and a `RemoteSyncDeferred` error is returned.
+///
+/// Errors which would recur upon retrying (@see `GraphHelperError::is_transient`) are returned
+/// directly, as are all errors encountered in sending if the outbox is unavailable.
+///
+pub (crate) fn sync_or_defer_index_operation(
+    operation: PendingIndexOperation,
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>> {
+    let blocked = read_pending_index_operations()
+        .map(|pending| pending.iter().any(|item| item.operation.is_deliverable() && item.operation.same_index_as(&operation)))
+        .unwrap_or(false);
+
+    if blocked {
+        let address = store_operation(&operation)?;
+        return Err(GraphHelperError::RemoteSyncDeferred { operation: address, cause: None });
+    }
+
+    match send_remote_index_operation(&operation) {
+        Ok(results) => Ok(results),
+        Err(e) if !e.is_transient() => Err(e),
+        Err(e) => {
+            let failed = PendingIndexOperation { attempts: 1, last_error: Some(e.clone()), ..operation };
+            match store_operation(&failed) {
+                Ok(address) => Err(GraphHelperError::RemoteSyncDeferred { operation: address, cause: Some(Box::new(e)) }),
+                Err(_) => Err(e),
+            }
+        },
+    }
+}
+
+/// Commit an operation to the outbox, assigning it the next `sequence` number
+///
+fn store_operation(operation: &PendingIndexOperation) -> GraphAPIResult<Address> {
+    // removed entries remain in the source chain, so the count of all outbox entries always increases
+    let sequence = query(REMOTE_INDEX_OUTBOX_ENTRY_TYPE)?.len();
+    let stored = PendingIndexOperation { sequence, ..operation.clone() };
+
+    Ok(commit_entry(&AppEntry(REMOTE_INDEX_OUTBOX_ENTRY_TYPE.into(), stored.into()))?)
+}
+
+//-------------------------------[ REPLAY ]-------------------------------------
+
+/// Attempt to send all operations in the outbox of the current agent, in the order they were made.
+///
+/// Successful operations are removed from the outbox. Operations which cannot yet be delivered
+/// remain, with their `attempts` and `last_error` updated, and later operations against the same
+/// index are skipped until the next replay. Operations rejected by the remote DNA are removed
+/// and reported as `failed`, so that they do not hold back later operations indefinitely.
+///
+pub fn replay_pending_index_operations() -> GraphAPIResult<OutboxReplayReport> {
+    let mut report = OutboxReplayReport::default();
+    let mut undelivered: Vec<PendingIndexOperation> = vec![];
+
+    for item in read_pending_index_operations()?.into_iter() {
+        if undelivered.iter().any(|op| op.same_index_as(&item.operation)) {
+            report.pending.push(item);
+            continue;
+        }
+
+        match send_remote_index_operation(&item.operation) {
+            Ok(_) => {
+                remove_entry(&item.address)?;
+                report.synced.push(item.address);
+            },
+            Err(e) if !e.is_transient() => {
+                remove_entry(&item.address)?;
+                let operation = PendingIndexOperation {
+                    attempts: item.operation.attempts + 1,
+                    last_error: Some(e),
+                    ..item.operation
+                };
+                report.failed.push(OutboxItem { address: item.address, operation });
+            },
+            Err(e) => {
+                // replace the entry, retaining its position in the sequence
+                let operation = PendingIndexOperation {
+                    attempts: item.operation.attempts + 1,
+                    last_error: Some(e),
+                    ..item.operation
+                };
+                remove_entry(&item.address)?;
+                let address = commit_entry(&AppEntry(REMOTE_INDEX_OUTBOX_ENTRY_TYPE.into(), operation.clone().into()))?;
+
+                undelivered.push(operation.clone());
+                report.pending.push(OutboxItem { address, operation });
+            },
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,253 @@
+/**
+ * Request-scoped read cache
+ *
+ * Reading a list of records typically loads the same entries & links many times over,
+ * for example when reading the link fields of each record or following links to shared
+ * related records. Within `with_read_cache`, the results of `get_entry` and link reads are
+ * memoised, so that each is only requested from the HDK once per zome call.
+ *
+ * All helpers in this library read & write via the functions in this module, which
+ * otherwise behave identically to their HDK equivalents. Writes made via these functions
+ * invalidate affected cache entries automatically. Calls to other zomes may modify any data,
+ * and so clear the cache entirely. Code which writes via the HDK directly should call
+ * `invalidate_entry`, `invalidate_links` or `clear_read_cache` afterwards.
+ *
+ * Outside of `with_read_cache`, nothing is cached. All reads & writes are made via the
+ * current `storage` backend.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-15
+ */
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::entry::{ Entry, AppEntryValue },
+    error::{ ZomeApiError, ZomeApiResult },
+};
+
+use super::storage_helpers::backend;
+
+// links from some base address, by link type and (if not matching any tag) link tag
+type LinkQueryKey = (Address, String, Option<String>);
+
+#[derive(Debug, Default)]
+struct ReadCache {
+    entries: HashMap<Address, Option<Entry>>,
+    links: HashMap<LinkQueryKey, Vec<(String, Address)>>,
+}
+
+thread_local! {
+    static READ_CACHE: RefCell<Option<ReadCache>> = RefCell::new(None);
+}
+
+/// Run `read_fn` with read caching enabled, discarding the cache upon completion.
+/// Nested calls share the cache of the outermost call.
+///
+pub fn with_read_cache<T, F>(read_fn: F) -> T
+    where F: FnOnce() -> T,
+{
+    let started = READ_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.is_some() {
+            return false;
+        }
+        *cache = Some(ReadCache::default());
+        true
+    });
+
+    let result = read_fn();
+
+    if started {
+        READ_CACHE.with(|cache| *cache.borrow_mut() = None);
+    }
+    result
+}
+
+/// Run `run_fn` without access to any cache of the enclosing scope, for example because
+/// it reads from some other storage backend. The enclosing cache is restored afterwards.
+///
+pub (crate) fn without_read_cache<T, F>(run_fn: F) -> T
+    where F: FnOnce() -> T,
+{
+    let outer = READ_CACHE.with(|cache| cache.borrow_mut().take());
+    let result = run_fn();
+    READ_CACHE.with(|cache| *cache.borrow_mut() = outer);
+    result
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Memoised `hdk::get_entry`
+///
+pub fn get_entry(address: &Address) -> ZomeApiResult<Option<Entry>> {
+    let cached = READ_CACHE.with(|cache| {
+        cache.borrow().as_ref().and_then(|c| c.entries.get(address).cloned())
+    });
+    if let Some(entry) = cached {
+        return Ok(entry);
+    }
+
+    let entry = backend().get_entry(address)?;
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            c.entries.insert(address.clone(), entry.clone());
+        }
+    });
+    Ok(entry)
+}
+
+/// Memoised read of the targets of all links from `base_address` via `link_type`, along with
+/// the tag of each link. If `link_tag` is given, only links with exactly that tag are returned.
+///
+pub fn get_links(base_address: &Address, link_type: &str, link_tag: Option<&str>) -> ZomeApiResult<Vec<(String, Address)>> {
+    let key: LinkQueryKey = (base_address.clone(), link_type.to_string(), link_tag.map(String::from));
+    let cached = READ_CACHE.with(|cache| {
+        cache.borrow().as_ref().and_then(|c| c.links.get(&key).cloned())
+    });
+    if let Some(links) = cached {
+        return Ok(links);
+    }
+
+    let links = backend().get_links(base_address, link_type, link_tag)?;
+
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            c.links.insert(key, links.clone());
+        }
+    });
+    Ok(links)
+}
+
+/// Memoised `hdk::utils::get_as_type`
+///
+pub fn get_as_type<R: TryFrom<AppEntryValue>>(address: Address) -> ZomeApiResult<R> {
+    match get_entry(&address)? {
+        Some(Entry::App(_, entry_value)) => R::try_from(entry_value).map_err(|_| {
+            ZomeApiError::Internal("Could not convert entry to requested type".to_string())
+        }),
+        Some(_) => Err(ZomeApiError::Internal("Entry is not an app entry".to_string())),
+        None => Err(ZomeApiError::Internal("No entry at this address".to_string())),
+    }
+}
+
+//-------------------------------[ WRITE ]--------------------------------------
+
+/// `hdk::commit_entry`, invalidating any cached read of the new entry's address
+///
+pub fn commit_entry(entry: &Entry) -> ZomeApiResult<Address> {
+    let address = backend().commit_entry(entry)?;
+    invalidate_entry(&address);
+    Ok(address)
+}
+
+/// `hdk::update_entry`, invalidating all cached entries (since reads of any earlier
+/// revision resolve to the latest one)
+///
+pub fn update_entry(new_entry: Entry, address: &Address) -> ZomeApiResult<Address> {
+    let result = backend().update_entry(new_entry, address);
+    invalidate_all_entries();
+    result
+}
+
+/// `hdk::remove_entry`, invalidating all cached entries
+///
+pub fn remove_entry(address: &Address) -> ZomeApiResult<Address> {
+    let result = backend().remove_entry(address);
+    invalidate_all_entries();
+    result
+}
+
+/// `hdk::link_entries`, invalidating cached links from `base`
+///
+pub fn link_entries<S: Into<String>>(base: &Address, target: &Address, link_type: S, tag: S) -> ZomeApiResult<Address> {
+    let (link_type, tag): (String, String) = (link_type.into(), tag.into());
+    let result = backend().link_entries(base, target, &link_type, &tag);
+    invalidate_links(base);
+    result
+}
+
+/// `hdk::remove_link`, invalidating cached links from `base`
+///
+pub fn remove_link<S: Into<String>>(base: &Address, target: &Address, link_type: S, tag: S) -> ZomeApiResult<()> {
+    let (link_type, tag): (String, String) = (link_type.into(), tag.into());
+    let result = backend().remove_link(base, target, &link_type, &tag);
+    invalidate_links(base);
+    result
+}
+
+//----------------------------[ INVALIDATION ]----------------------------------
+
+/// Discard any cached read of the entry at `address`
+///
+pub fn invalidate_entry(address: &Address) {
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            c.entries.remove(address);
+        }
+    });
+}
+
+/// Discard all cached reads of links from `base_address`
+///
+pub fn invalidate_links(base_address: &Address) {
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            c.links.retain(|(base, _, _), _| base != base_address);
+        }
+    });
+}
+
+/// Discard everything read so far in the current cache scope
+///
+pub fn clear_read_cache() {
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            *c = ReadCache::default();
+        }
+    });
+}
+
+fn invalidate_all_entries() {
+    READ_CACHE.with(|cache| {
+        if let Some(c) = cache.borrow_mut().as_mut() {
+            c.entries.clear();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_cached(address: &Address) -> bool {
+        READ_CACHE.with(|cache| cache.borrow().as_ref().map_or(false, |c| c.entries.contains_key(address)))
+    }
+
+    fn cache_empty_entry(address: &Address) {
+        READ_CACHE.with(|cache| {
+            if let Some(c) = cache.borrow_mut().as_mut() {
+                c.entries.insert(address.clone(), None);
+            }
+        });
+    }
+
+    #[test]
+    fn test_nested_scopes_share_cache() {
+        let address = Address::from("QmA");
+
+        with_read_cache(|| {
+            cache_empty_entry(&address);
+            with_read_cache(|| assert!(is_cached(&address)));
+            assert!(is_cached(&address), "inner scope does not discard outer cache");
+
+            invalidate_entry(&address);
+            assert!(!is_cached(&address));
+        });
+
+        cache_empty_entry(&address);
+        assert!(!is_cached(&address), "nothing cached outside of scope");
+    }
+}
@@ -11,27 +11,33 @@
 
 use std::convert::TryFrom;
 use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
     holochain_persistence_api::cas::content::Address,
     holochain_core_types::{
         entry::{
+            Entry::App as AppEntry,
             entry_type::AppEntryType,
             AppEntryValue,
         },
+        chain_header::ChainHeader,
+        time::Iso8601,
+    },
+    holochain_wasm_utils::api_serialization::get_entry::{
+        GetEntryOptions, GetEntryResultType, GetEntryResultItem, StatusRequestKind,
     },
-    error::{ ZomeApiResult, ZomeApiError },
-    link_entries,
-    get_entry,
-    remove_entry,
 };
 
 use super::{
-    identifiers::{ RECORD_INITIAL_ENTRY_LINK_TAG, ERR_MSG_ENTRY_NOT_FOUND },
+    read_cache::{ link_entries, remove_link, get_entry, remove_entry },
+    storage::get_entry_result,
+    identifiers::{ RECORD_INITIAL_ENTRY_LINK_TAG, RECORD_TOMBSTONE_LINK_TAG },
+    error::{ GraphAPIResult, GraphHelperError },
     type_wrappers::Addressable,
-    record_interface::{ Updateable, UniquelyIdentifiable, UpdateableIdentifier },
+    record_interface::{ Updateable, UniquelyIdentifiable, UpdateableIdentifier, RevisionedUpdate },
     entries::{
         create_entry,
         try_decode_entry,
-        update_entry,
+        update_entry_from_revision,
         delete_entry,
     },
     keys::{
@@ -39,40 +45,137 @@ use super::{
         get_key_index_address,
         get_key_index_address_as_type,
     },
+    links::get_linked_addresses,
     anchors::{
         create_anchor_index,
         get_anchor_index_entry_address,
+        check_anchor_available,
         update_anchor_index,
         delete_anchor_index,
     },
 };
 
+/// A single revision of some record, as read by `read_record_revisions`.
+///
+/// `revision_id` is the address of the entry holding this version of the record's data;
+/// `timestamp` and `author` are taken from the header of the source chain entry which
+/// committed it.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordRevision<T> {
+    pub revision_id: Address,
+    pub timestamp: Iso8601,
+    pub author: Address,
+    pub record: T,
+}
+
+impl<T> RecordRevision<T> {
+    /// Converts the record data held by this revision, retaining revision metadata.
+    /// Used to transform revisions of raw entry data into API response structs.
+    ///
+    pub fn map<U, F>(self, f: F) -> RecordRevision<U>
+        where F: FnOnce(T) -> U,
+    {
+        RecordRevision {
+            revision_id: self.revision_id,
+            timestamp: self.timestamp,
+            author: self.author,
+            record: f(self.record),
+        }
+    }
+}
+
+// :NOTE: `DefaultJson` cannot be derived for generic types
+impl<T: serde::Serialize> From<RecordRevision<T>> for JsonString {
+    fn from(revision: RecordRevision<T>) -> JsonString {
+        JsonString::from_json(&serde_json::to_string(&revision).unwrap_or_else(|_| "null".to_string()))
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> TryFrom<JsonString> for RecordRevision<T> {
+    type Error = JsonError;
+    fn try_from(j: JsonString) -> Result<Self, Self::Error> {
+        serde_json::from_str(&String::from(j)).map_err(|e| JsonError::SerializationError(e.to_string()))
+    }
+}
+
 //--------------------------------[ READ ]--------------------------------------
 
 /// Read a record's entry data by its `key index` (static id).
 ///
 pub fn read_record_entry<T: TryFrom<AppEntryValue>, A: AsRef<Address>>(
     address: &A,
-) -> ZomeApiResult<T> {
+) -> GraphAPIResult<T> {
     // read base entry to determine dereferenced entry address
-    let data_address = get_key_index_address(address.as_ref());
+    let data_address = get_key_index_address(address.as_ref())?;
 
     // return retrieval error or attempt underlying type fetch
-    match data_address {
-        Ok(addr) => {
-            let entry = get_entry(&addr);
-            let decoded = try_decode_entry(entry);
-            match decoded {
-                Ok(Some(entry)) => {
-                    Ok(entry)
-                },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
-            }
-        },
-        Err(e) => Err(e),
+    let entry = get_entry(&data_address);
+    match try_decode_entry(&data_address, entry)? {
+        Some(entry) => Ok(entry),
+        None => Err(GraphHelperError::NotFound(data_address)),
     }
 }
 
+/// Read a record's entry data by its `key index` (static id), failing with
+/// `GraphHelperError::Deleted` if the record has been marked as deleted.
+///
+/// @see tombstone_record
+///
+pub fn read_active_record_entry<T: TryFrom<AppEntryValue>, A: AsRef<Address>>(
+    address: &A,
+    initial_entry_link_type: &str,
+) -> GraphAPIResult<T> {
+    check_not_deleted(address.as_ref(), initial_entry_link_type)?;
+    read_record_entry(address)
+}
+
+/// Determine whether the record at the given `key index` has been marked as deleted.
+///
+pub fn is_record_deleted(
+    address: &Address,
+    initial_entry_link_type: &str,
+) -> GraphAPIResult<bool> {
+    Ok(!get_linked_addresses(address, initial_entry_link_type, RECORD_TOMBSTONE_LINK_TAG)?.is_empty())
+}
+
+/// Fails with `GraphHelperError::Deleted` if the record at the given `key index` has been marked as deleted.
+/// Used to prevent modification of tombstoned records.
+///
+pub fn check_not_deleted(address: &Address, initial_entry_link_type: &str) -> GraphAPIResult<()> {
+    match is_record_deleted(address, initial_entry_link_type)? {
+        true => Err(GraphHelperError::Deleted(address.clone())),
+        false => Ok(()),
+    }
+}
+
+/// Removes any records marked as deleted from some set of batch read results, such that queries
+/// do not return them. Results are keyed by `key index` address, as returned from `query_index`.
+///
+/// Records whose tombstone status cannot be determined are reported with the error encountered.
+///
+pub fn exclude_deleted_records<A, R>(
+    results: Vec<(A, GraphAPIResult<R>)>,
+    initial_entry_link_type: &str,
+) -> Vec<(A, GraphAPIResult<R>)>
+    where A: AsRef<Address>,
+{
+    results.into_iter()
+        .filter_map(|(address, result)| {
+            let result = match result {
+                Ok(entry) => match check_not_deleted(address.as_ref(), initial_entry_link_type) {
+                    Ok(()) => Ok(entry),
+                    Err(GraphHelperError::Deleted(_)) => return None,
+                    Err(e) => Err(e),
+                },
+                err => err,
+            };
+            Some((address, result))
+        })
+        .collect()
+}
+
 /// Reads an entry via its `anchor index`.
 ///
 /// Follows an anchor identified by `id_entry_type`, `id_link_type` and
@@ -85,7 +188,7 @@ pub fn read_anchored_record_entry<T, E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<T>
+) -> GraphAPIResult<T>
     where E: Into<AppEntryType> + Clone,
         T: TryFrom<AppEntryValue>,
 {
@@ -94,16 +197,116 @@ pub fn read_anchored_record_entry<T, E>(
     match entry_address {
         Some(address) => {
             let entry = get_entry(&address);
-            let decoded = try_decode_entry(entry);
-            match decoded {
-                Ok(Some(entry)) => {
-                    Ok(entry)
-                },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+            match try_decode_entry(&address, entry)? {
+                Some(entry) => Ok(entry),
+                None => Err(GraphHelperError::NotFound(address)),
             }
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        None => Err(GraphHelperError::NotFound(Address::from(id_string.to_owned()))),
+    }
+}
+
+/// Reads all revisions of a record by its `key index` (static id), oldest first.
+///
+/// The update chain is followed from the record's initial entry, which is located via the
+/// `initial_entry_link_type` link written by `create_record`.
+///
+pub fn read_record_revisions<T, A>(
+    address: &A,
+    initial_entry_link_type: &str,
+) -> GraphAPIResult<Vec<RecordRevision<T>>>
+    where T: TryFrom<AppEntryValue>,
+        A: AsRef<Address>,
+{
+    let initial_addresses = get_linked_addresses(address.as_ref(), initial_entry_link_type, RECORD_INITIAL_ENTRY_LINK_TAG)?;
+
+    match initial_addresses.first() {
+        Some(initial_address) => read_entry_revisions(initial_address),
+        None => Err(GraphHelperError::IndexMissing(address.as_ref().clone())),
+    }
+}
+
+/// Reads all revisions of a record via its `anchor index`, oldest first.
+///
+/// :NOTE: changes to the anchor ID of the record are not reflected in the returned history.
+///
+pub fn read_anchored_record_revisions<T, E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+) -> GraphAPIResult<Vec<RecordRevision<T>>>
+    where E: Into<AppEntryType> + Clone,
+        T: TryFrom<AppEntryValue>,
+{
+    let entry_address = get_anchor_index_entry_address(id_entry_type, id_link_type, id_string)?;
+    match entry_address {
+        Some(address) => read_entry_revisions(&address),
+        None => Err(GraphHelperError::NotFound(Address::from(id_string.to_owned()))),
+    }
+}
+
+/// Follows the CRUD update chain starting at `initial_address`, decoding each version of the entry.
+///
+fn read_entry_revisions<T>(initial_address: &Address) -> GraphAPIResult<Vec<RecordRevision<T>>>
+    where T: TryFrom<AppEntryValue>,
+{
+    let result = get_entry_result(initial_address, GetEntryOptions {
+        status_request: StatusRequestKind::All,
+        entry: true,
+        headers: true,
+        timeout: Default::default(),
+    }).map_err(|e| GraphHelperError::from_read_error(e, initial_address))?;
+
+    let history = match result.result {
+        GetEntryResultType::All(history) => history,
+        GetEntryResultType::Single(item) => {
+            // entry has never been updated
+            return Ok(vec![decode_revision(initial_address, item)?]);
+        },
+    };
+
+    let mut items = history.items;
+    let mut revisions = vec![];
+    let mut current_address = Some(initial_address.clone());
+
+    while let Some(address) = current_address {
+        let position = items.iter().position(|item| {
+            item.meta.as_ref().map(|meta| meta.address == address).unwrap_or(false)
+        });
+        match position {
+            Some(idx) => revisions.push(decode_revision(&address, items.remove(idx))?),
+            None => break,
+        }
+        current_address = history.crud_links.get(&address).cloned();
+    }
+
+    if revisions.is_empty() {
+        return Err(GraphHelperError::NotFound(initial_address.clone()));
     }
+
+    Ok(revisions)
+}
+
+fn decode_revision<T>(address: &Address, item: GetEntryResultItem) -> GraphAPIResult<RecordRevision<T>>
+    where T: TryFrom<AppEntryValue>,
+{
+    let record = match item.entry {
+        Some(AppEntry(_, entry_value)) => T::try_from(entry_value).map_err(|_| GraphHelperError::WrongType(address.clone()))?,
+        Some(_) => return Err(GraphHelperError::WrongType(address.clone())),
+        None => return Err(GraphHelperError::NotFound(address.clone())),
+    };
+    let header: &ChainHeader = item.headers.first()
+        .ok_or(GraphHelperError::Internal(format!("No header available for entry {}", address)))?;
+    let author = header.provenances().first()
+        .map(|p| p.source())
+        .ok_or(GraphHelperError::Internal(format!("No author available for entry {}", address)))?;
+
+    Ok(RecordRevision {
+        revision_id: address.clone(),
+        timestamp: header.timestamp().clone(),
+        author,
+        record,
+    })
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -121,7 +324,7 @@ pub fn create_record<E, C, A, S>(
     entry_type: S,
     initial_entry_link_type: &str,
     create_payload: C,
-) -> ZomeApiResult<(A, E)>
+) -> GraphAPIResult<(A, E)>
     where E: Clone + Into<AppEntryValue>,
         C: Into<E>,
         S: Into<AppEntryType>,
@@ -150,20 +353,23 @@ pub fn create_anchored_record<E, C, S>(
     id_link_type: &str,
     entry_type: S,
     create_payload: C,
-) -> ZomeApiResult<(String, E)>
+) -> GraphAPIResult<(String, E)>
     where E: Clone + Into<AppEntryValue>,
         C: Into<E> + UniquelyIdentifiable,
         S: Into<AppEntryType>,
 {
     // determine unique anchor index key
-    // :TODO: deal with collisions
     let entry_id = create_payload.get_anchor_key();
+    let anchor_entry_type: AppEntryType = base_entry_type.into();
+
+    // reject the record before writing anything if the ID is taken
+    check_anchor_available(&anchor_entry_type, id_link_type, &entry_id, None)?;
 
     // write underlying entry
     let (address, entry_resp) = create_entry(entry_type, create_payload)?;
 
     // write primary key index
-    let _ = create_anchor_index(&base_entry_type.into(), id_link_type, &entry_id, &address)?;
+    let _ = create_anchor_index(&anchor_entry_type, id_link_type, &entry_id, &address)?;
 
     Ok((entry_id, entry_resp))
 }
@@ -175,22 +381,29 @@ pub fn create_anchored_record<E, C, S>(
 /// The way in which the input update payload is applied to the existing
 /// entry data is up to the implementor of `Updateable<U>` for the entry type.
 ///
+/// If the payload specifies the revision it was based on and that revision is no longer
+/// current, the update is rejected with `GraphHelperError::RevisionConflict`.
+///
 /// @see hdk_graph_helpers::record_interface::Updateable
+/// @see hdk_graph_helpers::record_interface::RevisionedUpdate
 ///
 pub fn update_record<E, U, A, S>(
     entry_type: S,
     address: &A,
     update_payload: &U,
-) -> ZomeApiResult<E>
+) -> GraphAPIResult<E>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
         A: AsRef<Address>,
+        U: RevisionedUpdate,
 {
     // read base entry to determine dereferenced entry address
     let data_address: Addressable = get_key_index_address_as_type(address.as_ref())?;
 
     // perform regular entry update using internal address
-    let (_addr, updated_entry): (Address, E) = update_entry(entry_type, &data_address, update_payload)?;
+    let (_addr, updated_entry): (Address, E) = update_entry_from_revision(
+        entry_type, &data_address, update_payload.get_base_revision().as_ref(), update_payload,
+    )?;
 
     Ok(updated_entry)
 }
@@ -200,6 +413,8 @@ pub fn update_record<E, U, A, S>(
 /// The `update_payload` must contain all data necessary to determine both the existing
 /// `anchor index` ID of the record, and the new `anchor index` that it has been moved to (if any).
 ///
+/// Revision conflicts are handled as for `update_record`.
+///
 /// @see hdk_graph_helpers::record_interface::UpdateableIdentifier
 ///
 pub fn update_anchored_record<E, U, S>(
@@ -207,10 +422,10 @@ pub fn update_anchored_record<E, U, S>(
     id_link_type: &str,
     entry_type: S,
     update_payload: &U,
-) -> ZomeApiResult<(String, E)>
+) -> GraphAPIResult<(String, E)>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
-        U: UpdateableIdentifier,
+        U: UpdateableIdentifier + RevisionedUpdate,
 {
     let current_id = update_payload.get_anchor_key();
     let maybe_new_id = update_payload.get_new_anchor_key();
@@ -221,6 +436,17 @@ pub fn update_anchored_record<E, U, S>(
         Some(entry_addr) => {
             let mut final_id = current_id.clone();
 
+            // reject the update before writing anything if the new ID is taken
+            if let Some(new_id) = &maybe_new_id {
+                check_anchor_available(&id_entry_type.to_string(), id_link_type, new_id, Some(&entry_addr))?;
+            }
+
+            // perform update of actual entry object
+            // :NOTE: done first, so that the anchor index is left intact if the update conflicts
+            let (_new_addr, new_entry) = update_entry_from_revision(
+                entry_type, &Addressable::from(entry_addr.clone()), update_payload.get_base_revision().as_ref(), update_payload,
+            )?;
+
             // check if ID has changed
             match maybe_new_id {
                 Some(new_id) => {
@@ -231,13 +457,10 @@ pub fn update_anchored_record<E, U, S>(
                 None => (),
             }
 
-            // perform update of actual entry object
-            let (_new_addr, new_entry) = update_entry(entry_type, &Addressable::from(entry_addr), update_payload)?;
-
             // return updated record to caller
             Ok((final_id, new_entry))
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.into())),
+        None => Err(GraphHelperError::NotFound(Address::from(current_id))),
     }
 }
 
@@ -246,7 +469,7 @@ pub fn update_anchored_record<E, U, S>(
 /// Removes a record of the given `key index` from the DHT by marking it as deleted.
 /// Links are not affected so as to retain a link to the referencing information, which may now need to be updated.
 ///
-pub fn delete_record<T>(address: &dyn AsRef<Address>) -> ZomeApiResult<bool>
+pub fn delete_record<T>(address: &dyn AsRef<Address>) -> GraphAPIResult<bool>
     where T: TryFrom<AppEntryValue>
 {
     // read base entry to determine dereferenced entry address
@@ -262,6 +485,53 @@ pub fn delete_record<T>(address: &dyn AsRef<Address>) -> ZomeApiResult<bool>
     }
 }
 
+/// Marks a record of the given `key index` as deleted, without removing any of its data.
+///
+/// A tombstone link is written from the `key index` to the record's current entry, using the
+/// `initial_entry_link_type` the record was created with. Tombstoned records are excluded from
+/// reads via `read_active_record_entry` & `exclude_deleted_records`, and can be reinstated with
+/// `restore_record`. Any indexes which should not resolve to the record whilst it is deleted
+/// must be removed by the caller.
+///
+/// Returns `false` if the record was already marked as deleted.
+///
+pub fn tombstone_record<T, A>(address: &A, initial_entry_link_type: &str) -> GraphAPIResult<bool>
+    where T: TryFrom<AppEntryValue>,
+        A: AsRef<Address>,
+{
+    let data_address = get_key_index_address(address.as_ref())?;
+
+    // ensure the record is of the expected type before marking it
+    let _entry: Option<T> = try_decode_entry(&data_address, get_entry(&data_address))?;
+
+    if is_record_deleted(address.as_ref(), initial_entry_link_type)? {
+        return Ok(false);
+    }
+
+    link_entries(address.as_ref(), &data_address, initial_entry_link_type, RECORD_TOMBSTONE_LINK_TAG)?;
+    Ok(true)
+}
+
+/// Reinstates a record previously marked as deleted by `tombstone_record`, returning its entry data
+/// so that the caller may re-create any indexes that were removed upon deletion.
+///
+/// The returned flag is `false` if the record was not marked as deleted, in which case nothing
+/// is changed. Records permanently removed with `delete_record` cannot be restored.
+///
+pub fn restore_record<T, A>(address: &A, initial_entry_link_type: &str) -> GraphAPIResult<(bool, T)>
+    where T: TryFrom<AppEntryValue>,
+        A: AsRef<Address>,
+{
+    let entry: T = read_record_entry(address)?;
+    let tombstones = get_linked_addresses(address.as_ref(), initial_entry_link_type, RECORD_TOMBSTONE_LINK_TAG)?;
+
+    for tombstone in tombstones.iter() {
+        remove_link(address.as_ref(), tombstone, initial_entry_link_type, RECORD_TOMBSTONE_LINK_TAG)?;
+    }
+
+    Ok((!tombstones.is_empty(), entry))
+}
+
 /// Removes a record via references to its `anchor index`.
 ///
 /// The index as well as the record's entry data will both be deleted; any failures
@@ -271,7 +541,7 @@ pub fn delete_anchored_record<E>(
     id_entry_type: &str,
     id_link_type: &str,
     entry_id: &String,
-) -> ZomeApiResult<bool>
+) -> GraphAPIResult<bool>
     where E: TryFrom<AppEntryValue>,
 {
     // determine entry address
@@ -288,6 +558,6 @@ pub fn delete_anchored_record<E>(
 
             entry_result
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.into())),
+        None => Err(GraphHelperError::NotFound(Address::from(entry_id.to_owned()))),
     }
 }
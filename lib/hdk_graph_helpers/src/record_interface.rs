@@ -7,6 +7,7 @@
  * @package HoloREA
  * @since   2019-07-02
  */
+use hdk::holochain_persistence_api::cas::content::Address;
 
 /// Interface for Holochain entry structs that can be updated via some predefined logic.
 ///
@@ -21,6 +22,24 @@ pub trait Updateable<T> {
     /// @see hdk_graph_helpers::record_helpers::update_record
     ///
     fn update_with(&self, e: &T) -> Self;
+
+    /// Checks an update payload before it is applied with `update_with`, returning a
+    /// description of the problem if it cannot be applied to this Entry. Accepts all
+    /// payloads unless overridden.
+    ///
+    fn validate_update(&self, _e: &T) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Interface for update payloads which may specify the revision of a record that
+/// the update was made against, for detecting conflicting concurrent edits.
+/// Returning `None` applies the update to whatever revision is current.
+///
+/// @see hdk_graph_helpers::record_helpers::update_record
+///
+pub trait RevisionedUpdate {
+    fn get_base_revision(&self) -> Option<Address>;
 }
 
 /// Interface for obtaining identity information from any data type.
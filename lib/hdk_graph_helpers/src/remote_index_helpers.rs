@@ -17,7 +17,6 @@ use hdk::{
     holochain_core_types::{
         entry::entry_type::AppEntryType,
     },
-    error::{ ZomeApiResult, ZomeApiError },
 };
 use holochain_json_derive::{ DefaultJson };
 
@@ -40,10 +39,13 @@ use super::{
         dereferenced_link_does_not_match,
     },
     rpc::{
-        read_from_zome,
-        build_zome_req_error,
+        read_with_capability,
     },
-    identifiers::{ ERR_MSG_REMOTE_INDEXING_ERR },
+    outbox::{
+        PendingIndexOperation,
+        sync_or_defer_index_operation,
+    },
+    error::{ GraphAPIResult, GraphHelperError },
 };
 
 // Common request format (zome trait) for linking remote entries in cooperating DNAs
@@ -56,8 +58,8 @@ struct RemoteEntryLinkRequest {
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct RemoteEntryLinkResponse {
-    indexes_created: Vec<ZomeApiResult<Address>>,
-    indexes_removed: Vec<ZomeApiResult<()>>,
+    indexes_created: Vec<GraphAPIResult<Address>>,
+    indexes_removed: Vec<GraphAPIResult<()>>,
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -71,7 +73,6 @@ pub fn create_direct_remote_index(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
-    remote_request_cap_token: Address,
     remote_base_entry_type: &str,
     origin_relationship_link_type: &str,
     origin_relationship_link_tag: &str,
@@ -79,7 +80,7 @@ pub fn create_direct_remote_index(
     destination_relationship_link_tag: &str,
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
-) -> Vec<ZomeApiResult<Address>> {
+) -> Vec<GraphAPIResult<Address>> {
     let mut local_results = create_direct_remote_index_origin(
         remote_base_entry_type,
         origin_relationship_link_type,
@@ -94,7 +95,6 @@ pub fn create_direct_remote_index(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
-        remote_request_cap_token,
         source_base_address,
         target_base_addresses,
         vec![],
@@ -104,16 +104,33 @@ pub fn create_direct_remote_index(
     local_results
 }
 
+/// Reduce the results of `create_direct_remote_index` to the first fatal error encountered.
+///
+/// Remote index operations which were deferred to the outbox will be completed when it is
+/// next replayed, and so are not considered failures.
+///
+pub fn check_remote_index_results(results: Vec<GraphAPIResult<Address>>) -> GraphAPIResult<()> {
+    for result in results.into_iter() {
+        match result {
+            Ok(_) | Err(GraphHelperError::RemoteSyncDeferred { .. }) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
 /// Creates a 'origin' query index used for fetching and querying pointers to other
 /// records that are stored externally to this DNA / zome.
 ///
 /// In the local DNA, this consists of `key index` addresses for all referenced foreign
 /// content, bidirectionally linked to the originating record for querying in either direction.
+/// Links which are already present are left as-is.
 ///
 /// In the remote DNA, a corresponding remote query index is built via `create_direct_remote_index_destination`,
 /// which is presumed to be linked to the other end of the specified `remote_zome_method`.
 ///
-/// :TODO: return any errors encountered in internal link creation
+/// Each result holds the `key index` address created for the target, or the first error
+/// encountered in indexing it.
 ///
 fn create_direct_remote_index_origin(
     remote_base_entry_type: &str,
@@ -123,68 +140,82 @@ fn create_direct_remote_index_origin(
     destination_relationship_link_tag: &str,
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
-) -> Vec<ZomeApiResult<Address>> {
+) -> Vec<GraphAPIResult<Address>> {
     // abort if target_base_addresses are empty
     if target_base_addresses.len() == 0 { return vec![] }
 
     // Build local index first (for reading linked record IDs from the `source_base_address`)
-    let results: Vec<ZomeApiResult<Address>> = target_base_addresses.iter()
-        .map(|base_entry_addr| {
+    target_base_addresses.iter()
+        .map(|base_entry_addr| -> GraphAPIResult<Address> {
             // create a base entry pointer for the referenced commitment
-            let base_entry_result = create_key_index(&(remote_base_entry_type.to_string().into()), base_entry_addr);
-
-            match &base_entry_result {
-                Ok(base_address) => {
-                    // link event to commitment by `fulfilled`/`fulfilledBy` edge
-                    create_direct_index(
-                        &source_base_address, base_address,
-                        origin_relationship_link_type, origin_relationship_link_tag,
-                        destination_relationship_link_type, destination_relationship_link_tag
-                    );
-                },
-                _ => (),
+            let base_address = create_key_index(&(remote_base_entry_type.to_string().into()), base_entry_addr)?;
+
+            // link event to commitment by `fulfilled`/`fulfilledBy` edge
+            for link_result in create_direct_index(
+                &source_base_address, &base_address,
+                origin_relationship_link_type, origin_relationship_link_tag,
+                destination_relationship_link_type, destination_relationship_link_tag
+            ) {
+                link_result?;
             }
 
-            base_entry_result
+            Ok(base_address)
         })
-        .collect();
-
-    results
+        .collect()
 }
 
 /// Ask another bridged DNA or zome to build a 'remote query index' to match the
 /// one we have just created locally.
 /// When calling zomes within the same DNA, use `hdk::THIS_INSTANCE` as `remote_dna_id`.
 ///
-/// :TODO: implement bridge genesis callbacks & private chain entry to wire up cross-DNA link calls
-/// :TODO: propagate errors from callee in error context, rather than masking them
+/// If the request cannot be sent, it is stored in the outbox for retrying later and
+/// `GraphHelperError::RemoteSyncDeferred` is returned. @see `outbox::replay_pending_index_operations`
+///
 /// :TODO: return indexes_removed to the caller
 ///
 fn request_sync_direct_remote_index_destination(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
-    remote_request_cap_token: Address,
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
     removed_base_addresses: Vec<Address>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>> {
-    // Call into remote DNA to enable target entries to setup data structures
-    // for querying the associated remote entry records back out.
-    let response: ZomeApiResult<RemoteEntryLinkResponse> = read_from_zome(
-        remote_dna_id, remote_zome_id, remote_request_cap_token, remote_zome_method, RemoteEntryLinkRequest {
-            base_entry: source_base_address.clone().into(),
-            target_entries: target_base_addresses,
-            removed_entries: removed_base_addresses,
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>> {
+    sync_or_defer_index_operation(PendingIndexOperation {
+        sequence: 0,
+        remote_dna_id: remote_dna_id.to_string(),
+        remote_zome_id: remote_zome_id.to_string(),
+        remote_zome_method: remote_zome_method.to_string(),
+        source_base_address: source_base_address.clone(),
+        target_entries: target_base_addresses,
+        removed_entries: removed_base_addresses,
+        attempts: 0,
+        last_error: None,
+    })
+}
+
+/// Call into remote DNA to enable target entries to setup data structures
+/// for querying the associated remote entry records back out.
+///
+/// The call is made with the capability token stored for `remote_zome_method`, which is requested
+/// if not yet held. Errors from the callee are returned as `GraphHelperError::RemoteCallFailed`,
+/// with the remote error as the `cause`.
+///
+pub (crate) fn send_remote_index_operation(
+    operation: &PendingIndexOperation,
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>> {
+    let response: RemoteEntryLinkResponse = read_with_capability(
+        operation.remote_dna_id.as_str(),
+        operation.remote_zome_id.as_str(),
+        operation.remote_zome_method.as_str(),
+        RemoteEntryLinkRequest {
+            base_entry: operation.source_base_address.clone(),
+            target_entries: operation.target_entries.clone(),
+            removed_entries: operation.removed_entries.clone(),
         }.into()
-    );
+    )?;
 
-    match response {
-        Ok(RemoteEntryLinkResponse { indexes_created, .. }) => {
-            Ok(indexes_created) // :TODO: how to treat deletion errors?
-        },
-        Err(_) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_INDEXING_ERR, remote_dna_id, remote_zome_id, remote_zome_method))),
-    }
+    Ok(response.indexes_created) // :TODO: how to treat deletion errors?
 }
 
 /// Respond to a request from an external source to build a link index for some externally linking content.
@@ -204,7 +235,7 @@ pub fn handle_sync_direct_remote_index_destination<'a, A, B>(
     source_base_address: &A,
     target_base_addresses: Vec<B>,
     removed_base_addresses: Vec<B>,
-) -> ZomeApiResult<RemoteEntryLinkResponse>
+) -> GraphAPIResult<RemoteEntryLinkResponse>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
@@ -225,21 +256,21 @@ pub fn handle_sync_direct_remote_index_destination<'a, A, B>(
         destination_relationship_link_type, destination_relationship_link_tag,
         source_base_address,
         target_base_addresses,
-    );
-    if let Err(index_creation_failure) = create_resp {
-        return Err(index_creation_failure);
-    }
+    )?;
 
-    Ok(RemoteEntryLinkResponse { indexes_created: create_resp.unwrap(), indexes_removed: remove_resp })
+    Ok(RemoteEntryLinkResponse { indexes_created: create_resp, indexes_removed: remove_resp })
 }
 
 /// Creates a 'destination' query index used for following a link from some external record
 /// into records contained within the current DNA / zome.
 ///
 /// This basically consists of a `key index` for the remote content and bidirectional
-/// links between it and its `target_base_addresses`.
+/// links between it and its `target_base_addresses`. Links which already exist in either
+/// direction are left as-is, so that requests replayed from the origin DNA's outbox or retried
+/// by clients are not duplicated. @see `local_indexes::create_direct_index`
 ///
-/// :TODO: return any errors encountered in internal link creation
+/// Each result holds the address of the target record, or the first error encountered in
+/// linking it. Failure to create the `key index` for the remote record fails the whole request.
 ///
 pub fn create_direct_remote_index_destination<'a, A, B>(
     remote_base_entry_type: &'a str,
@@ -249,27 +280,25 @@ pub fn create_direct_remote_index_destination<'a, A, B>(
     destination_relationship_link_tag: &str,
     source_base_address: &A,
     target_base_addresses: Vec<B>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
     // create a base entry pointer for the referenced origin record
     let base_entry: AppEntryType = remote_base_entry_type.to_string().into();
-    let base_resp = create_key_index(&base_entry, source_base_address.as_ref());
-    if let Err(base_creation_failure) = base_resp {
-        return Err(base_creation_failure);
-    }
-    let base_address = base_resp.unwrap();
+    let base_address = create_key_index(&base_entry, source_base_address.as_ref())?;
 
     // link all referenced records to our pointer to the remote origin record
     Ok(target_base_addresses.iter()
-        .map(|target_address| {
+        .map(|target_address| -> GraphAPIResult<Address> {
             // link origin record to local records by specified edge
-            create_direct_index(
+            for link_result in create_direct_index(
                 &base_address, target_address.as_ref(),
                 origin_relationship_link_type, origin_relationship_link_tag,
                 destination_relationship_link_type, destination_relationship_link_tag
-            );
+            ) {
+                link_result?;
+            }
 
             Ok(target_address.as_ref().clone())
         })
@@ -282,13 +311,16 @@ pub fn create_direct_remote_index_destination<'a, A, B>(
 /// Toplevel method for triggering a link update flow between two records in
 /// different DNAs. Indexes on both sides of the network boundary will be updated.
 ///
+/// As with `create_direct_remote_index`, errors are returned in place of results and may be
+/// reduced with `check_remote_index_results`. If the local index cannot be updated, the remote
+/// DNA is not contacted.
+///
 /// :TODO: update to accept multiple targets for the replacement links
 ///
 pub fn update_direct_remote_index<A, B, S>(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
-    remote_request_cap_token: Address,
     remote_base_entry_type: S,
     origin_relationship_link_type: &str,
     origin_relationship_link_tag: &str,
@@ -296,18 +328,17 @@ pub fn update_direct_remote_index<A, B, S>(
     destination_relationship_link_tag: &str,
     source_base_address: &A,
     target_base_address: &MaybeUndefined<B>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> Vec<GraphAPIResult<Address>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         S: Into<AppEntryType>,
 {
     // no change, bail early
     if let MaybeUndefined::Undefined = target_base_address {
-        return Ok(vec![]);
+        return vec![];
     }
 
     // process local index first and collect all removed link target address
-    // :TODO: error handling
     let removed_links = replace_direct_remote_index_origin(
         source_base_address,
         target_base_address,
@@ -316,29 +347,32 @@ pub fn update_direct_remote_index<A, B, S>(
         origin_relationship_link_tag,
         destination_relationship_link_type,
         destination_relationship_link_tag,
-    )?.iter()
-        .filter_map(|r| { r.clone().ok() })
-        .collect();
+    ).and_then(|erased| erased.into_iter().collect::<GraphAPIResult<Vec<Address>>>());
+
+    let removed_links = match removed_links {
+        Ok(removed) => removed,
+        Err(e) => return vec![Err(e)],
+    };
 
     // pass removed IDs and new IDs to remote DNA for re-indexing
     request_sync_direct_remote_index_destination(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
-        remote_request_cap_token,
         source_base_address.as_ref(),
         match &target_base_address {
             &MaybeUndefined::Some(target) => vec![target.as_ref().clone()],
             _ => vec![],
         },
         removed_links,
-    )
+    ).unwrap_or_else(|e| { vec![Err(e)] })
 }
 
 /// Same as `replace_direct_index` except that the replaced links
 /// are matched against dereferenced addresses pointing to entries in other DNAs.
 ///
-/// Returns the addresses of the previously erased link targets, if any.
+/// Returns the addresses of the previously erased link targets, if any. Fails upon the first
+/// error encountered in removing or creating links.
 ///
 /// :TODO: update to accept multiple targets for the replacement links
 ///
@@ -350,7 +384,7 @@ pub fn replace_direct_remote_index_origin<A, B, S>(
     link_name: &str,
     link_type_reciprocal: &str,
     link_name_reciprocal: &str,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> GraphAPIResult<Vec<GraphAPIResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         S: Into<AppEntryType>,
@@ -368,15 +402,16 @@ pub fn replace_direct_remote_index_origin<A, B, S>(
         .filter(dereferenced_link_does_not_match(new_dest)).map(|x| { (*x).clone() }).collect();
 
     // wipe stale links. Note we don't remove the base addresses, dangling remnants do no harm.
-    // :TODO: propagate errors
-    let _erased: Vec<ZomeApiResult<()>> = to_erase.iter().flat_map(wipe_links_from_origin(
+    for wipe_result in to_erase.iter().flat_map(wipe_links_from_origin(
         link_type, link_name,
         link_type_reciprocal, link_name_reciprocal,
         source,
-    )).collect();
+    )) {
+        wipe_result?;
+    }
 
     // get base addresses of erased items
-    let erased: Vec<ZomeApiResult<Address>> = to_erase.iter().map(|addr| { get_key_index_address(addr.as_ref()) }).collect();
+    let erased: Vec<GraphAPIResult<Address>> = to_erase.iter().map(|addr| { get_key_index_address(addr.as_ref()) }).collect();
 
     // run insert if needed
     match new_dest {
@@ -386,15 +421,14 @@ pub fn replace_direct_remote_index_origin<A, B, S>(
             if already_present {
                 Ok(erased)
             } else {
-                let new_dest_pointer = create_key_index(&(base_entry_type.into()), new_link.as_ref());
-                if let Err(e) = new_dest_pointer {
-                    return Err(e);
-                }
-                create_direct_index(
-                    source.as_ref(), &(new_dest_pointer.unwrap()),
+                let new_dest_pointer = create_key_index(&(base_entry_type.into()), new_link.as_ref())?;
+                for link_result in create_direct_index(
+                    source.as_ref(), &new_dest_pointer,
                     link_type, link_name,
                     link_type_reciprocal, link_name_reciprocal
-                );  // :TODO: error handling
+                ) {
+                    link_result?;
+                }
                 Ok(erased)
             }
         },
@@ -413,7 +447,6 @@ pub fn remove_direct_remote_index<'a, A, B>(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
-    remote_request_cap_token: Address,
     remote_base_entry_type: &'a str,
     origin_relationship_link_type: &str,
     origin_relationship_link_tag: &str,
@@ -421,7 +454,7 @@ pub fn remove_direct_remote_index<'a, A, B>(
     destination_relationship_link_tag: &str,
     source_base_address: &A,
     remove_base_address: &B,
-) -> Vec<ZomeApiResult<()>>
+) -> Vec<GraphAPIResult<()>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
@@ -442,7 +475,6 @@ pub fn remove_direct_remote_index<'a, A, B>(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
-        remote_request_cap_token,
         source_base_address.as_ref(),
         vec![],
         vec![remove_base_address.as_ref().clone()],
@@ -450,7 +482,7 @@ pub fn remove_direct_remote_index<'a, A, B>(
 
     match remote_results {
         Ok(results) => {
-            let mut remote_errors: Vec<ZomeApiResult<()>> = results.iter()
+            let mut remote_errors: Vec<GraphAPIResult<()>> = results.iter()
                 .filter(|r| { r.is_err() })
                 .map(|e| { Err(e.clone().err().unwrap()) })
                 .collect();
@@ -478,12 +510,12 @@ pub (crate) fn delete_direct_remote_index_destination<'a, A, B>(
     link_name: &str,
     link_type_reciprocal: &str,
     link_name_reciprocal: &str,
-) -> Vec<ZomeApiResult<()>>
+) -> Vec<GraphAPIResult<()>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
 {
-    let dereferenced_source: ZomeApiResult<A> = determine_key_index_address(base_entry_type.to_string(), source.as_ref());
+    let dereferenced_source: GraphAPIResult<A> = determine_key_index_address(base_entry_type.to_string(), source.as_ref());
     if let Err(e) = dereferenced_source {
         return vec![Err(e)]
     }
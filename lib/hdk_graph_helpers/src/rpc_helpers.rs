@@ -13,20 +13,23 @@ use std::convert::{ TryInto, TryFrom };
 use serde::{de::DeserializeOwned};
 use hdk::{
     holochain_json_api::{ json::JsonString, error::JsonError },
-    error::{ ZomeApiError, ZomeApiResult },
+    error::{ ZomeApiError },
     holochain_persistence_api::cas::content::Address,
-    call,
 };
 
 use super::{
-    identifiers::{ ERR_MSG_REMOTE_REQUEST_ERR, ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR },
+    error::{ GraphAPIResult, GraphHelperError },
+    read_cache::clear_read_cache,
+    storage::call,
 };
 
 /// Helper for reading data from other zomes or DNAs. Abstracts away the details of dealing with
 /// response decoding and type conversion.
-/// Simply use `ZomeApiResult<X>` as the return type, where X is the response struct format you wish to decode.
+/// Simply use `GraphAPIResult<X>` as the return type, where X is the response struct format you wish to decode.
 ///
-/// :TODO: propagate errors from callee in error context, rather than masking them
+/// Any error returned by the callee is preserved as the `cause` of a `GraphHelperError::RemoteCallFailed`.
+/// Calls to a DNA which is not bridged to this one fail with `GraphHelperError::BridgeMissing`.
+/// Since the callee may modify any data, the read cache is cleared after the call. @see `read_cache`
 ///
 pub fn read_from_zome<R, S>(
     instance_handle: S,
@@ -34,37 +37,52 @@ pub fn read_from_zome<R, S>(
     cap_token: Address,
     fn_name: S,
     fn_args: JsonString,
-) -> ZomeApiResult<R>
+) -> GraphAPIResult<R>
     where S: Clone + Into<String> + Deref<Target=str>,
         R: TryFrom<JsonString> + Into<JsonString> + DeserializeOwned,
 {
-    let rpc_response = call(instance_handle.clone(), zome_name.clone(), cap_token, fn_name.clone(), fn_args);
+    let rpc_response = call(&instance_handle[..], &zome_name[..], cap_token, &fn_name[..], fn_args);
+    clear_read_cache();
     if let Err(bad_call) = rpc_response {
-        return Err(bad_call);
+        if is_missing_bridge_error(&bad_call) {
+            return Err(GraphHelperError::BridgeMissing(instance_handle.into()));
+        }
+        return Err(GraphHelperError::from(bad_call).into_remote(&instance_handle[..], &zome_name[..], &fn_name[..]));
     }
 
+    // zome responses may contain plain HDK errors (which can wrap encoded `GraphHelperError`s), or structured errors
     let strng = rpc_response.unwrap();
-    let decoded: Result<Result<R, ZomeApiError>, JsonError> = strng.try_into();
+    let decoded: Result<Result<R, ZomeApiError>, JsonError> = strng.clone().try_into();
+    let decoded = match decoded {
+        Ok(result) => Ok(result.map_err(GraphHelperError::from)),
+        Err(_) => {
+            let structured: Result<Result<R, GraphHelperError>, JsonError> = strng.try_into();
+            structured
+        },
+    };
 
     match decoded {
         Ok(Ok(response_data)) => Ok(response_data),
-        Ok(Err(_response_err)) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_REQUEST_ERR, &instance_handle[..], &zome_name[..], &fn_name[..]))),
-        Err(_decoding_err) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR, &instance_handle[..], &zome_name[..], &fn_name[..]))),
+        Ok(Err(response_err)) => Err(response_err.into_remote(&instance_handle[..], &zome_name[..], &fn_name[..])),
+        Err(_decoding_err) => Err(GraphHelperError::RemoteResponseMalformed {
+            dna: instance_handle.into(),
+            zome: zome_name.into(),
+            fn_name: fn_name.into(),
+        }),
     }
 }
 
-/// Error reporting for inter-zome RPC calls
-pub (crate) fn build_zome_req_error(
-    prefix_string: &str,
-    remote_dna_id: &str,
-    remote_zome_id: &str,
-    remote_zome_method: &str,
- ) -> String {
-    let mut err_string = prefix_string.to_string();
-    err_string.push_str(remote_dna_id);
-    err_string.push_str("/");
-    err_string.push_str(remote_zome_id);
-    err_string.push_str("/");
-    err_string.push_str(remote_zome_method);
-    err_string
+/// Determine whether an error returned by the HDK for some call indicates that no bridge is
+/// configured under the instance handle called. The conductor exposes each bridged DNA's zome
+/// API as RPC methods, and so reports calls via unknown handles as unknown methods.
+///
+fn is_missing_bridge_error(err: &ZomeApiError) -> bool {
+    match err {
+        ZomeApiError::Internal(msg) => msg.contains(MISSING_BRIDGE_ERROR),
+        _ => false,
+    }
 }
+
+/// Error message reported by the conductor for calls via bridge handles which are not configured
+///
+pub (crate) const MISSING_BRIDGE_ERROR: &str = "Method not found";
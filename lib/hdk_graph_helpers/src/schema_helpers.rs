@@ -0,0 +1,365 @@
+/**
+ * Versioned entry schemas
+ *
+ * Entry structs declared with `versioned_entry!` are stored wrapped in an envelope recording
+ * the version of their schema. When an entry struct changes shape, an upgrade function is
+ * appended to its list of `SchemaUpgrade`s. Entries stored with an earlier version are then
+ * upgraded as they are decoded, by running each upgrade from their version onwards over the
+ * raw JSON data. The current schema version is the number of upgrades declared.
+ *
+ * Entries written before versioning was adopted for their type have no envelope, and are
+ * treated as version `0`.
+ *
+ * Upgraded entries are not written back on read. `migrate_entries` may be used to rewrite
+ * all entries authored by the current agent in the latest schema version, after which
+ * upgrades of older versions are no longer needed for reading that agent's data.
+ *
+ * Likewise, indexes introduced after records were created are not built on read.
+ * `reindex_records` may be used alongside `migrate_entries` to build them for all
+ * records created by the current agent.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-17
+ */
+use std::convert::TryFrom;
+use serde::{ Serialize, de::DeserializeOwned };
+use serde_json::Value;
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::entry::{
+        Entry::App as AppEntry,
+        entry_type::AppEntryType,
+        AppEntryValue,
+    },
+};
+use holochain_json_derive::{ DefaultJson };
+
+// used by `versioned_entry!`
+#[doc(hidden)]
+pub use hdk::holochain_json_api::{ json::JsonString, error::JsonError };
+
+use super::{
+    read_cache::{ get_entry, update_entry },
+    storage::{ entry_address, query },
+    records::read_active_record_entry,
+    error::{ GraphAPIResult, GraphHelperError },
+};
+
+/// Converts the JSON data of an entry from one schema version to the next
+///
+pub type SchemaUpgrade = fn(Value) -> Result<Value, String>;
+
+/// Implemented for entry structs by `versioned_entry!`
+///
+pub trait VersionedEntry: Serialize + DeserializeOwned {
+    /// Upgrades from each earlier schema version, in order. The upgrade at index `n` converts
+    /// the data of version `n` entries into that of version `n + 1`.
+    const SCHEMA_UPGRADES: &'static [SchemaUpgrade];
+
+    fn schema_version() -> u32 {
+        Self::SCHEMA_UPGRADES.len() as u32
+    }
+}
+
+/// Declare `$entry` as a versioned entry struct, with the given `SchemaUpgrade`s from earlier
+/// versions (if any). Replaces `DefaultJson` for the struct.
+///
+/// ```ignore
+/// versioned_entry!(Entry, &[
+///     upgrade_v0,     // fn(Value) -> Result<Value, String>
+/// ]);
+/// ```
+///
+#[macro_export]
+macro_rules! versioned_entry {
+    ($entry:ty) => {
+        $crate::versioned_entry!($entry, &[]);
+    };
+    ($entry:ty, $upgrades:expr $(,)?) => {
+        impl $crate::schema::VersionedEntry for $entry {
+            const SCHEMA_UPGRADES: &'static [$crate::schema::SchemaUpgrade] = $upgrades;
+        }
+
+        impl From<$entry> for $crate::schema::JsonString {
+            fn from(entry: $entry) -> $crate::schema::JsonString {
+                $crate::schema::encode_versioned_entry(&entry)
+            }
+        }
+
+        impl<'a> From<&'a $entry> for $crate::schema::JsonString {
+            fn from(entry: &'a $entry) -> $crate::schema::JsonString {
+                $crate::schema::encode_versioned_entry(entry)
+            }
+        }
+
+        impl ::std::convert::TryFrom<$crate::schema::JsonString> for $entry {
+            type Error = $crate::schema::JsonError;
+            fn try_from(json: $crate::schema::JsonString) -> Result<Self, Self::Error> {
+                $crate::schema::decode_versioned_entry(&json)
+            }
+        }
+
+        impl<'a> ::std::convert::TryFrom<&'a $crate::schema::JsonString> for $entry {
+            type Error = $crate::schema::JsonError;
+            fn try_from(json: &'a $crate::schema::JsonString) -> Result<Self, Self::Error> {
+                $crate::schema::decode_versioned_entry(json)
+            }
+        }
+    };
+}
+
+/// Storage format of versioned entries
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct EntryEnvelope {
+    schema_version: u32,
+    entry: Value,
+}
+
+/// Result of a call to `migrate_entries`
+///
+/// `migrated` lists the previous addresses of all entries rewritten, `reindexed` the addresses
+/// of any records passed to `reindex_records`, and `errors` any entries which could not be
+/// upgraded, written or reindexed.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub schema_version: u32,
+    pub migrated: Vec<Address>,
+    #[serde(default)]
+    pub reindexed: Vec<Address>,
+    pub errors: Vec<GraphHelperError>,
+}
+
+//-------------------------------[ ENCODING ]-----------------------------------
+
+/// Encode `entry` for storage, in its current schema version
+///
+pub fn encode_versioned_entry<E: VersionedEntry>(entry: &E) -> JsonString {
+    let envelope = EntryEnvelope {
+        schema_version: E::schema_version(),
+        entry: serde_json::to_value(entry).expect("could not Jsonify entry"),
+    };
+    JsonString::from_json(&serde_json::to_string(&envelope).expect("could not Jsonify entry envelope"))
+}
+
+/// Decode stored entry data of any schema version, upgrading it to the current version as needed
+///
+pub fn decode_versioned_entry<E: VersionedEntry>(json: &JsonString) -> Result<E, JsonError> {
+    let stored: Value = serde_json::from_str(&String::from(json.to_owned()))
+        .map_err(|e| JsonError::SerializationError(e.to_string()))?;
+    let data = upgrade_entry_data(stored, E::SCHEMA_UPGRADES)
+        .map_err(JsonError::SerializationError)?;
+
+    serde_json::from_value(data)
+        .map_err(|e| JsonError::SerializationError(e.to_string()))
+}
+
+/// Run all applicable `upgrades` over `stored` entry data, returning data in the latest schema version.
+///
+pub fn upgrade_entry_data(stored: Value, upgrades: &[SchemaUpgrade]) -> Result<Value, String> {
+    let (version, mut data) = split_envelope(stored);
+    if version as usize > upgrades.len() {
+        return Err(format!("Entry schema version {} is newer than the latest known version {}", version, upgrades.len()));
+    }
+
+    for upgrade in upgrades[version as usize..].iter() {
+        data = upgrade(data)?;
+    }
+    Ok(data)
+}
+
+/// Read the schema version and unversioned data of some stored entry data
+///
+pub fn stored_entry_data(json: &JsonString) -> (u32, Value) {
+    match serde_json::from_str(&String::from(json.to_owned())) {
+        Ok(stored) => split_envelope(stored),
+        Err(_) => (0, Value::Null),
+    }
+}
+
+fn split_envelope(stored: Value) -> (u32, Value) {
+    let is_envelope = match &stored {
+        Value::Object(fields) => fields.len() == 2 && fields.contains_key("schemaVersion") && fields.contains_key("entry"),
+        _ => false,
+    };
+    if !is_envelope {
+        return (0, stored);
+    }
+
+    match serde_json::from_value::<EntryEnvelope>(stored.clone()) {
+        Ok(envelope) => (envelope.schema_version, envelope.entry),
+        Err(_) => (0, stored),
+    }
+}
+
+//-------------------------------[ MIGRATE ]------------------------------------
+
+/// Rewrite all entries of `entry_type` authored by the current agent which are stored in an
+/// earlier schema version, as new revisions in the current version.
+///
+/// Only the latest revision of each entry is rewritten, and deleted entries are skipped. Since
+/// only the author of an entry may update it, each agent must run the migration for their own data.
+///
+pub fn migrate_entries<E, S>(entry_type: S) -> GraphAPIResult<MigrationReport>
+    where E: VersionedEntry + TryFrom<AppEntryValue> + Into<AppEntryValue>,
+        S: Into<AppEntryType> + Clone + AsRef<str>,
+{
+    let mut report = MigrationReport { schema_version: E::schema_version(), ..MigrationReport::default() };
+
+    for address in query(entry_type.as_ref())? {
+        let stored = match get_entry(&address)? {
+            Some(AppEntry(_, value)) => value,
+            _ => continue,
+        };
+        // `get_entry` resolves to the latest revision, which will be listed separately if authored by this agent
+        if entry_address(&AppEntry(entry_type.clone().into(), stored.clone()))? != address {
+            continue;
+        }
+        if stored_entry_data(&stored).0 >= E::schema_version() {
+            continue;
+        }
+
+        let upgraded = match E::try_from(stored) {
+            Ok(entry) => entry,
+            Err(_) => {
+                report.errors.push(GraphHelperError::WrongType(address));
+                continue;
+            },
+        };
+        match update_entry(AppEntry(entry_type.clone().into(), upgraded.into()), &address) {
+            Ok(_) => report.migrated.push(address),
+            Err(e) => report.errors.push(GraphHelperError::from_read_error(e, &address)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Call `reindex` with the address & current entry of every record of `base_entry_type` created by
+/// the current agent, recording the outcome for each in `report`. Records which have been deleted
+/// are skipped.
+///
+/// Used to build indexes for records created before the index was introduced. Since records may
+/// already be indexed, `reindex` should leave any links already present in place.
+///
+pub fn reindex_records<E, A, F>(
+    base_entry_type: &str,
+    initial_entry_link_type: &str,
+    mut report: MigrationReport,
+    reindex: F,
+) -> GraphAPIResult<MigrationReport>
+    where E: TryFrom<AppEntryValue>,
+        A: From<Address>,
+        F: Fn(&A, &E) -> GraphAPIResult<()>,
+{
+    for address in query(base_entry_type)? {
+        let entry: E = match read_active_record_entry(&address, initial_entry_link_type) {
+            Ok(entry) => entry,
+            Err(GraphHelperError::NotFound(_)) | Err(GraphHelperError::Deleted(_)) => continue,
+            Err(e) => {
+                report.errors.push(e);
+                continue;
+            },
+        };
+        match reindex(&A::from(address.clone()), &entry) {
+            Ok(()) => report.reindexed.push(address),
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use serde_json::json;
+    use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct TestEntry {
+        name: String,
+        note: Option<String>,
+    }
+
+    fn rename_title(mut data: Value) -> Result<Value, String> {
+        let title = data.as_object_mut().and_then(|fields| fields.remove("title"));
+        data["name"] = title.ok_or("missing title")?;
+        Ok(data)
+    }
+
+    fn add_note(mut data: Value) -> Result<Value, String> {
+        data["note"] = Value::Null;
+        Ok(data)
+    }
+
+    versioned_entry!(TestEntry, &[rename_title, add_note]);
+
+    #[test]
+    fn test_upgrade_on_read() {
+        let legacy = JsonString::from_json(r#"{"title":"a"}"#);
+        let v1 = JsonString::from_json(r#"{"schemaVersion":1,"entry":{"name":"a"}}"#);
+        let expected = TestEntry { name: "a".into(), note: None };
+
+        assert_eq!(TestEntry::try_from(legacy).unwrap(), expected);
+        assert_eq!(TestEntry::try_from(v1).unwrap(), expected);
+        assert_eq!(TestEntry::try_from(JsonString::from(expected.clone())).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_newer_versions_rejected() {
+        let stored = json!({ "schemaVersion": 3, "entry": { "name": "a", "note": null } });
+        assert!(upgrade_entry_data(stored, TestEntry::SCHEMA_UPGRADES).is_err());
+    }
+
+    #[test]
+    fn test_migrate_entries() {
+        let storage = Rc::new(MockStorage::new());
+        let legacy = AppEntry("test_entry".into(), JsonString::from_json(r#"{"title":"a"}"#));
+        let address = storage.commit_entry(&legacy).unwrap();
+
+        with_storage_backend(storage.clone(), || {
+            let report = migrate_entries::<TestEntry, _>("test_entry").unwrap();
+            assert_eq!(report.migrated, vec![address.clone()]);
+            assert!(report.errors.is_empty());
+
+            let stored = match get_entry(&address).unwrap() {
+                Some(AppEntry(_, value)) => value,
+                _ => panic!("migrated entry not readable"),
+            };
+            assert_eq!(stored_entry_data(&stored).0, 2);
+
+            let report = migrate_entries::<TestEntry, _>("test_entry").unwrap();
+            assert!(report.migrated.is_empty(), "entries in the current version are left as-is");
+        });
+    }
+
+    #[test]
+    fn test_reindex_records() {
+        use std::cell::RefCell;
+        use crate::records::{ create_record, tombstone_record };
+
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            let create = |name: &str| -> Address {
+                create_record::<TestEntry, _, Address, _>("test_base", "test_entry", "test_initial", TestEntry { name: name.into(), note: None }).unwrap().0
+            };
+            let (a, b) = (create("a"), create("b"));
+            tombstone_record::<TestEntry, _>(&b, "test_initial").unwrap();
+
+            let indexed = RefCell::new(vec![]);
+            let report = reindex_records("test_base", "test_initial", MigrationReport::default(), |address: &Address, entry: &TestEntry| {
+                indexed.borrow_mut().push((address.clone(), entry.name.clone()));
+                Ok(())
+            }).unwrap();
+
+            assert_eq!(indexed.into_inner(), vec![(a.clone(), "a".to_string())], "deleted records are skipped");
+            assert_eq!(report.reindexed, vec![a]);
+            assert!(report.errors.is_empty());
+        });
+    }
+}
@@ -0,0 +1,319 @@
+/**
+ * Helper methods related to `search indexes`.
+ *
+ * A `search index` is a set of `anchor` entries which locate records by the words in their
+ * text fields. Text is split into lowercase words, and each word is indexed under all of its
+ * prefixes between `MIN_SEARCH_PREFIX_LENGTH` & `MAX_SEARCH_PREFIX_LENGTH` characters long
+ * (or under the whole word, if shorter). Records are linked from the anchor for each prefix,
+ * with the link tagged by the complete word.
+ *
+ * Search terms are matched against the words of a record by prefix, such that partially
+ * typed words find their results. Terms enclosed in double quotes only match whole words.
+ * Records must match every term given.
+ *
+ * Indexes must be kept up to date by calling `create_search_index`, `update_search_index`
+ * & `delete_search_index` with the indexed text of a record as it is written.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-18
+ */
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry,
+        entry::Entry::App as AppEntry,
+        entry::entry_type::AppEntryType,
+    },
+};
+
+use super::{
+    read_cache::{ commit_entry, link_entries, remove_link },
+    storage::entry_address,
+    error::{ GraphAPIResult, GraphHelperError },
+    links::{ get_linked_addresses_with_tags, link_entries_if_absent },
+};
+
+/// Shortest word prefix indexed. Shorter search terms only match whole words.
+pub const MIN_SEARCH_PREFIX_LENGTH: usize = 2;
+
+/// Longest word prefix indexed. Longer search terms are matched against the
+/// words indexed under their leading characters.
+pub const MAX_SEARCH_PREFIX_LENGTH: usize = 12;
+
+/// A single term of a search query
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTerm {
+    /// Matches any word beginning with the term
+    Prefix(String),
+    /// Matches only the word itself
+    Word(String),
+}
+
+impl SearchTerm {
+    fn text(&self) -> &str {
+        match self {
+            SearchTerm::Prefix(term) => term,
+            SearchTerm::Word(term) => term,
+        }
+    }
+
+    fn matches(&self, word: &str) -> bool {
+        match self {
+            SearchTerm::Prefix(term) => word.starts_with(term.as_str()),
+            SearchTerm::Word(term) => word == term,
+        }
+    }
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Determine the addresses of all records in a `search index` which match every term of `query`.
+///
+/// Results are ordered by the position of each record in the index for the first term.
+///
+pub fn read_search_index_addresses<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    query: &str,
+) -> GraphAPIResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    let terms = search_query_terms(query);
+    if terms.is_empty() {
+        return Err(GraphHelperError::ValidationFailed(format!("No searchable terms in query {}", query)));
+    }
+
+    let mut results: Option<Vec<Address>> = None;
+    for term in terms.iter() {
+        let matches = read_term_addresses(index_entry_type, index_id, entry_link_type, term)?;
+        results = Some(match results {
+            None => matches,
+            Some(found) => found.into_iter().filter(|address| matches.contains(address)).collect(),
+        });
+        if results.as_ref().map_or(false, |found| found.is_empty()) {
+            break;
+        }
+    }
+
+    Ok(results.unwrap_or_default())
+}
+
+fn read_term_addresses<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    term: &SearchTerm,
+) -> GraphAPIResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    let anchor_address = search_index_anchor_address(index_entry_type, index_id, &search_index_key(term.text()))?;
+
+    let mut results: Vec<Address> = vec![];
+    for (word, address) in get_linked_addresses_with_tags(&anchor_address, entry_link_type)? {
+        if term.matches(&word) && !results.contains(&address) {
+            results.push(address);
+        }
+    }
+    Ok(results)
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Adds the record at `entry_address` to a `search index`, under all of the words in `text`.
+/// Any anchors not yet present in the index are created, and words the record is already
+/// indexed under are skipped- such that records can safely be reindexed.
+///
+pub fn create_search_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    text: &str,
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    for (key, word) in search_index_links(text) {
+        let anchor_address = commit_entry(&search_index_anchor(index_entry_type, index_id, &key))?;
+        link_entries_if_absent(&anchor_address, entry_address, entry_link_type, &word)?;
+    }
+    Ok(())
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+/// Re-indexes the record at `entry_address` after its indexed text changes from `previous_text`
+/// to `text`. Only links for words which have been added or removed are written.
+///
+pub fn update_search_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    previous_text: &str,
+    text: &str,
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let previous_links = search_index_links(previous_text);
+    let new_links = search_index_links(text);
+
+    for (key, word) in previous_links.iter().filter(|link| !new_links.contains(link)) {
+        let anchor_address = search_index_anchor_address(index_entry_type, index_id, key)?;
+        remove_link(&anchor_address, entry_address, entry_link_type, word)?;
+    }
+    for (key, word) in new_links.iter().filter(|link| !previous_links.contains(link)) {
+        let anchor_address = commit_entry(&search_index_anchor(index_entry_type, index_id, key))?;
+        link_entries(&anchor_address, entry_address, entry_link_type, word)?;
+    }
+    Ok(())
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Removes the record at `entry_address` from a `search index`. `text` must be the same
+/// value that the record was last indexed with.
+///
+/// Anchors are left in place, since they may be shared with other records.
+///
+pub fn delete_search_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    text: &str,
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    for (key, word) in search_index_links(text) {
+        let anchor_address = search_index_anchor_address(index_entry_type, index_id, &key)?;
+        remove_link(&anchor_address, entry_address, entry_link_type, &word)?;
+    }
+    Ok(())
+}
+
+//------------------------------[ INTERNALS ]-----------------------------------
+
+/// Builds the anchor entry for the words indexed under `key` in the search index named `index_id`
+///
+fn search_index_anchor<E>(index_entry_type: &E, index_id: &str, key: &str) -> Entry
+    where E: Into<AppEntryType> + Clone,
+{
+    AppEntry(index_entry_type.to_owned().into(), Some(format!("{}/{}", index_id, key)).into())
+}
+
+fn search_index_anchor_address<E>(index_entry_type: &E, index_id: &str, key: &str) -> GraphAPIResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    Ok(entry_address(&search_index_anchor(index_entry_type, index_id, key))?)
+}
+
+/// Splits `text` into the distinct, lowercased words to be indexed, in order of first appearance
+///
+pub fn search_tokens(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let token = word.to_lowercase();
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// Parses a search query into its terms. Text enclosed in double quotes is matched by whole
+/// words, everything else by prefix.
+///
+pub fn search_query_terms(query: &str) -> Vec<SearchTerm> {
+    let mut terms: Vec<SearchTerm> = vec![];
+    for (i, segment) in query.split('"').enumerate() {
+        for token in search_tokens(segment) {
+            let term = if i % 2 == 1 { SearchTerm::Word(token) } else { SearchTerm::Prefix(token) };
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+    }
+    terms
+}
+
+/// Determines the anchor key under which any word or search term beginning with `text` is found
+///
+fn search_index_key(text: &str) -> String {
+    text.chars().take(MAX_SEARCH_PREFIX_LENGTH).collect()
+}
+
+/// Determines the `(anchor key, word)` pairs linking a record into the index for `text`
+///
+fn search_index_links(text: &str) -> Vec<(String, String)> {
+    let mut links = vec![];
+    for word in search_tokens(text) {
+        let length = word.chars().count();
+        let shortest = MIN_SEARCH_PREFIX_LENGTH.min(length);
+        let longest = MAX_SEARCH_PREFIX_LENGTH.min(length);
+
+        for prefix_length in shortest..=longest {
+            let key: String = word.chars().take(prefix_length).collect();
+            links.push((key, word.clone()));
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+    const INDEX_TYPE: &str = "test_search_index";
+    const LINK_TYPE: &str = "test_search_index_entry";
+
+    fn search(query: &str) -> Vec<Address> {
+        read_search_index_addresses(&INDEX_TYPE.to_string(), "all", LINK_TYPE, query).unwrap()
+    }
+
+    #[test]
+    fn test_query_terms() {
+        assert_eq!(search_tokens("Bread, bread & BUTTER-making"), vec!["bread", "butter", "making"]);
+        assert_eq!(search_query_terms(r#"pro "apple" Pie"#), vec![
+            SearchTerm::Prefix("pro".into()),
+            SearchTerm::Word("apple".into()),
+            SearchTerm::Prefix("pie".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_search_index() {
+        let storage = Rc::new(MockStorage::new());
+        let first = storage.commit_entry(&AppEntry("test_entry".into(), Some("first".to_string()).into())).unwrap();
+        let second = storage.commit_entry(&AppEntry("test_entry".into(), Some("second".to_string()).into())).unwrap();
+
+        with_storage_backend(storage.clone(), || {
+            create_search_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, "Apple pie baking process", &first).unwrap();
+            create_search_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, "Apple processing internationalization", &second).unwrap();
+
+            assert_eq!(search("apple"), vec![first.clone(), second.clone()]);
+            assert_eq!(search("proc"), vec![first.clone(), second.clone()]);
+            assert_eq!(search(r#""process""#), vec![first.clone()]);
+            assert_eq!(search("apple pie"), vec![first.clone()]);
+            assert_eq!(search("internationalisation"), vec![], "terms longer than indexed prefixes are compared in full");
+            assert_eq!(search("internationalization"), vec![second.clone()]);
+            assert!(read_search_index_addresses(&INDEX_TYPE.to_string(), "all", LINK_TYPE, " - ").is_err());
+
+            create_search_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, "Apple pie", &first).unwrap();
+            let anchor_address = search_index_anchor_address(&INDEX_TYPE.to_string(), "all", "pie").unwrap();
+            assert_eq!(storage.get_links(&anchor_address, LINK_TYPE, Some("pie")).unwrap().len(), 1, "reindexing does not duplicate links");
+
+            update_search_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, "Apple pie baking process", "Cherry pie baking", &first).unwrap();
+            assert_eq!(search("apple"), vec![second.clone()]);
+            assert_eq!(search("cherry"), vec![first.clone()]);
+            assert_eq!(search("pie"), vec![first.clone()]);
+
+            delete_search_index(&INDEX_TYPE.to_string(), "all", LINK_TYPE, "Cherry pie baking", &first).unwrap();
+            assert_eq!(search("pie"), vec![]);
+        });
+    }
+}
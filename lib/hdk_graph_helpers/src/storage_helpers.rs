@@ -0,0 +1,487 @@
+/**
+ * Pluggable storage backends
+ *
+ * All reads & writes of DHT primitives made by this library, as well as calls to other
+ * zomes & DNAs, are routed through a `StorageBackend`. Within a running zome this is always
+ * `HDKStorage`, which simply defers to the HDK.
+ *
+ * `MockStorage` instead holds all entries, links & agent source chain data in memory, so
+ * that code built upon these helpers can be exercised by native `cargo test` runs without
+ * compiling to WASM or running a conductor. Use `with_storage_backend` to run test code
+ * against it. Note that entry & link validation callbacks are not run by `MockStorage`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-16
+ */
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{ Hash, Hasher };
+use hdk::{
+    holochain_json_api::json::JsonString,
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::{ Entry, deletion_entry::DeletionEntry },
+        link::LinkMatch,
+        crud_status::CrudStatus,
+        chain_header::ChainHeader,
+        signature::{ Provenance, Signature },
+        time::Iso8601,
+    },
+    holochain_wasm_utils::api_serialization::{
+        get_links::GetLinksOptions,
+        get_entry::{
+            GetEntryOptions, GetEntryResult, GetEntryResultType, GetEntryResultItem,
+            EntryResultMeta, EntryHistory, StatusRequestKind,
+        },
+    },
+    error::{ ZomeApiError, ZomeApiResult },
+};
+
+use super::read_cache_helpers::without_read_cache;
+use super::rpc_helpers::MISSING_BRIDGE_ERROR;
+
+/// DHT & zome API primitives used by this library
+///
+pub trait StorageBackend {
+    fn get_entry(&self, address: &Address) -> ZomeApiResult<Option<Entry>>;
+    fn get_entry_result(&self, address: &Address, options: GetEntryOptions) -> ZomeApiResult<GetEntryResult>;
+    fn get_links(&self, base_address: &Address, link_type: &str, link_tag: Option<&str>) -> ZomeApiResult<Vec<(String, Address)>>;
+    fn entry_address(&self, entry: &Entry) -> ZomeApiResult<Address>;
+    /// Addresses of all entries of the given type in the agent's source chain, oldest first
+    fn query(&self, entry_type_name: &str) -> ZomeApiResult<Vec<Address>>;
+
+    fn commit_entry(&self, entry: &Entry) -> ZomeApiResult<Address>;
+    fn update_entry(&self, new_entry: Entry, address: &Address) -> ZomeApiResult<Address>;
+    fn remove_entry(&self, address: &Address) -> ZomeApiResult<Address>;
+    fn link_entries(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<Address>;
+    fn remove_link(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<()>;
+
+    fn call(&self, instance_handle: &str, zome_name: &str, cap_token: Address, fn_name: &str, fn_args: JsonString) -> ZomeApiResult<JsonString>;
+}
+
+thread_local! {
+    static STORAGE_BACKEND: RefCell<Option<Rc<dyn StorageBackend>>> = RefCell::new(None);
+}
+
+/// Run `run_fn` with all storage operations handled by `backend`, restoring the previous
+/// backend upon completion. Reads cached by any enclosing `read_cache::with_read_cache` are
+/// not visible to `run_fn`.
+///
+pub fn with_storage_backend<T, F>(backend: Rc<dyn StorageBackend>, run_fn: F) -> T
+    where F: FnOnce() -> T,
+{
+    let previous = STORAGE_BACKEND.with(|b| b.replace(Some(backend)));
+    let result = without_read_cache(run_fn);
+    STORAGE_BACKEND.with(|b| *b.borrow_mut() = previous);
+    result
+}
+
+/// The backend currently handling storage operations
+///
+pub (crate) fn backend() -> Rc<dyn StorageBackend> {
+    STORAGE_BACKEND.with(|b| b.borrow().clone())
+        .unwrap_or_else(|| Rc::new(HDKStorage))
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+// :NOTE: these reads are never cached. @see `read_cache` for `get_entry` & `get_links`
+
+/// `hdk::get_entry_result` via the current storage backend
+///
+pub fn get_entry_result(address: &Address, options: GetEntryOptions) -> ZomeApiResult<GetEntryResult> {
+    backend().get_entry_result(address, options)
+}
+
+/// `hdk::entry_address` via the current storage backend
+///
+pub fn entry_address(entry: &Entry) -> ZomeApiResult<Address> {
+    backend().entry_address(entry)
+}
+
+/// Read the addresses of all entries of `entry_type_name` in the agent's source chain, oldest first.
+///
+pub fn query(entry_type_name: &str) -> ZomeApiResult<Vec<Address>> {
+    backend().query(entry_type_name)
+}
+
+//-------------------------------[ REMOTE ]-------------------------------------
+
+/// `hdk::call` via the current storage backend
+///
+pub fn call(instance_handle: &str, zome_name: &str, cap_token: Address, fn_name: &str, fn_args: JsonString) -> ZomeApiResult<JsonString> {
+    backend().call(instance_handle, zome_name, cap_token, fn_name, fn_args)
+}
+
+//---------------------------------[ HDK ]--------------------------------------
+
+/// Storage backend used within running zomes
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HDKStorage;
+
+impl StorageBackend for HDKStorage {
+    fn get_entry(&self, address: &Address) -> ZomeApiResult<Option<Entry>> {
+        hdk::get_entry(address)
+    }
+
+    fn get_entry_result(&self, address: &Address, options: GetEntryOptions) -> ZomeApiResult<GetEntryResult> {
+        hdk::get_entry_result(address, options)
+    }
+
+    fn get_links(&self, base_address: &Address, link_type: &str, link_tag: Option<&str>) -> ZomeApiResult<Vec<(String, Address)>> {
+        let tag_match = match link_tag {
+            Some(tag) => LinkMatch::Exactly(tag),
+            None => LinkMatch::Any,
+        };
+        Ok(hdk::get_links_with_options(
+            base_address,
+            LinkMatch::Exactly(link_type),
+            tag_match,
+            GetLinksOptions::default(),
+        )?.links().iter()
+            .map(|link| (link.tag.to_owned(), link.address.to_owned()))
+            .collect())
+    }
+
+    fn entry_address(&self, entry: &Entry) -> ZomeApiResult<Address> {
+        hdk::entry_address(entry)
+    }
+
+    fn query(&self, entry_type_name: &str) -> ZomeApiResult<Vec<Address>> {
+        hdk::query(entry_type_name.into(), 0, 0)
+    }
+
+    fn commit_entry(&self, entry: &Entry) -> ZomeApiResult<Address> {
+        hdk::commit_entry(entry)
+    }
+
+    fn update_entry(&self, new_entry: Entry, address: &Address) -> ZomeApiResult<Address> {
+        hdk::update_entry(new_entry, address)
+    }
+
+    fn remove_entry(&self, address: &Address) -> ZomeApiResult<Address> {
+        hdk::remove_entry(address)
+    }
+
+    fn link_entries(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<Address> {
+        hdk::link_entries(base, target, link_type, tag)
+    }
+
+    fn remove_link(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<()> {
+        hdk::remove_link(base, target, link_type, tag)
+    }
+
+    fn call(&self, instance_handle: &str, zome_name: &str, cap_token: Address, fn_name: &str, fn_args: JsonString) -> ZomeApiResult<JsonString> {
+        hdk::call(instance_handle, zome_name, cap_token, fn_name, fn_args)
+    }
+}
+
+//--------------------------------[ MOCK ]--------------------------------------
+
+/// Handler for mocked zome API methods, receiving the call arguments
+///
+pub type MockZomeFn = Box<dyn Fn(JsonString) -> ZomeApiResult<JsonString>>;
+
+#[derive(Debug, Clone)]
+struct MockEntry {
+    entry: Entry,
+    header: ChainHeader,
+    crud_status: CrudStatus,
+    // address of the entry which replaced this one, if any
+    crud_link: Option<Address>,
+}
+
+#[derive(Debug, Clone)]
+struct MockLink {
+    base: Address,
+    target: Address,
+    link_type: String,
+    tag: String,
+}
+
+#[derive(Default)]
+struct MockState {
+    entries: HashMap<Address, MockEntry>,
+    links: Vec<MockLink>,
+    // (entry type, address) of every entry committed, in order
+    chain: Vec<(String, Address)>,
+}
+
+/// In-memory storage backend for native tests
+///
+/// Entries & links written are immediately visible to all subsequent reads, as if the DHT were
+/// fully consistent. Calls to other zomes & DNAs are answered by handlers registered with
+/// `register_zome_fn`; to mock a cooperating DNA, run the handler against a separate `MockStorage`
+/// with `with_storage_backend`.
+///
+pub struct MockStorage {
+    agent_address: Address,
+    state: RefCell<MockState>,
+    zome_fns: RefCell<HashMap<(String, String, String), Rc<MockZomeFn>>>,
+}
+
+impl MockStorage {
+    pub fn new() -> Self {
+        Self::with_agent(Address::from("HcMockAgent"))
+    }
+
+    /// Create a backend whose entries are authored by `agent_address`
+    ///
+    pub fn with_agent(agent_address: Address) -> Self {
+        MockStorage {
+            agent_address,
+            state: RefCell::new(MockState::default()),
+            zome_fns: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Answer calls to `fn_name` of `zome_name` in the bridged `instance_handle` with `handler`.
+    /// Capability tokens are not checked. Calls via instance handles which have no methods registered
+    /// fail in the same way as calls via unconfigured bridges.
+    ///
+    pub fn register_zome_fn<F>(&self, instance_handle: &str, zome_name: &str, fn_name: &str, handler: F)
+        where F: Fn(JsonString) -> ZomeApiResult<JsonString> + 'static,
+    {
+        self.zome_fns.borrow_mut().insert(
+            (instance_handle.to_string(), zome_name.to_string(), fn_name.to_string()),
+            Rc::new(Box::new(handler)),
+        );
+    }
+
+    /// Number of (non-removed) links from `base_address` via `link_type`, including any duplicates
+    ///
+    pub fn count_links(&self, base_address: &Address, link_type: &str) -> usize {
+        self.state.borrow().links.iter()
+            .filter(|link| link.base == *base_address && link.link_type == link_type)
+            .count()
+    }
+
+    /// Address of the newest revision of the entry at `address`
+    ///
+    fn latest_address(state: &MockState, address: &Address) -> Address {
+        let mut current = address.clone();
+        while let Some(next) = state.entries.get(&current).and_then(|e| e.crud_link.clone()) {
+            current = next;
+        }
+        current
+    }
+
+    fn result_item(address: &Address, stored: &MockEntry, options: &GetEntryOptions) -> GetEntryResultItem {
+        GetEntryResultItem {
+            meta: Some(EntryResultMeta {
+                address: address.clone(),
+                entry_type: stored.entry.entry_type(),
+                crud_status: stored.crud_status.clone(),
+            }),
+            entry: if options.entry { Some(stored.entry.clone()) } else { None },
+            headers: if options.headers { vec![stored.header.clone()] } else { vec![] },
+        }
+    }
+}
+
+impl Default for MockStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn mock_address(content: &str) -> Address {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Address::from(format!("QmMock{:016x}", hasher.finish()))
+}
+
+fn mock_error(message: String) -> ZomeApiError {
+    ZomeApiError::Internal(message)
+}
+
+impl StorageBackend for MockStorage {
+    fn get_entry(&self, address: &Address) -> ZomeApiResult<Option<Entry>> {
+        let state = self.state.borrow();
+        let latest = Self::latest_address(&state, address);
+        Ok(state.entries.get(&latest)
+            .filter(|stored| stored.crud_status != CrudStatus::Deleted)
+            .map(|stored| stored.entry.clone()))
+    }
+
+    fn get_entry_result(&self, address: &Address, options: GetEntryOptions) -> ZomeApiResult<GetEntryResult> {
+        let state = self.state.borrow();
+        let stored = match state.entries.get(address) {
+            Some(stored) => stored,
+            None => return Ok(GetEntryResult {
+                result: GetEntryResultType::Single(GetEntryResultItem { meta: None, entry: None, headers: vec![] }),
+            }),
+        };
+
+        let result = match options.status_request {
+            StatusRequestKind::Initial => GetEntryResultType::Single(Self::result_item(address, stored, &options)),
+            StatusRequestKind::Latest => {
+                let latest = Self::latest_address(&state, address);
+                GetEntryResultType::Single(Self::result_item(&latest, &state.entries[&latest], &options))
+            },
+            StatusRequestKind::All => {
+                let mut history = EntryHistory { items: vec![], crud_links: HashMap::new() };
+                let mut current = Some(address.clone());
+                while let Some(addr) = current {
+                    let stored = &state.entries[&addr];
+                    history.items.push(Self::result_item(&addr, stored, &options));
+                    if let Some(next) = &stored.crud_link {
+                        history.crud_links.insert(addr.clone(), next.clone());
+                    }
+                    current = stored.crud_link.clone();
+                }
+                GetEntryResultType::All(history)
+            },
+        };
+
+        Ok(GetEntryResult { result })
+    }
+
+    fn get_links(&self, base_address: &Address, link_type: &str, link_tag: Option<&str>) -> ZomeApiResult<Vec<(String, Address)>> {
+        Ok(self.state.borrow().links.iter()
+            .filter(|link| link.base == *base_address && link.link_type == link_type)
+            .filter(|link| link_tag.map_or(true, |tag| link.tag == tag))
+            .map(|link| (link.tag.clone(), link.target.clone()))
+            .collect())
+    }
+
+    fn entry_address(&self, entry: &Entry) -> ZomeApiResult<Address> {
+        let content = serde_json::to_string(entry)
+            .map_err(|e| mock_error(format!("Unable to serialize entry: {}", e)))?;
+        Ok(mock_address(&content))
+    }
+
+    fn query(&self, entry_type_name: &str) -> ZomeApiResult<Vec<Address>> {
+        Ok(self.state.borrow().chain.iter()
+            .filter(|(entry_type, _)| entry_type == entry_type_name)
+            .map(|(_, address)| address.clone())
+            .collect())
+    }
+
+    fn commit_entry(&self, entry: &Entry) -> ZomeApiResult<Address> {
+        let address = self.entry_address(entry)?;
+        let mut state = self.state.borrow_mut();
+        let header = ChainHeader::new(
+            &entry.entry_type(),
+            &address,
+            &[Provenance::new(self.agent_address.clone(), Signature::from("mock"))],
+            &state.chain.last().map(|(_, addr)| addr.clone()),
+            &None,
+            &None,
+            &Iso8601::new(state.chain.len() as i64, 0),
+        );
+
+        // committing identical content again is a no-op on the DHT, but still authors a new chain entry
+        state.entries.entry(address.clone()).or_insert(MockEntry {
+            entry: entry.clone(),
+            header,
+            crud_status: CrudStatus::Live,
+            crud_link: None,
+        });
+        state.chain.push((entry.entry_type().to_string(), address.clone()));
+
+        Ok(address)
+    }
+
+    fn update_entry(&self, new_entry: Entry, address: &Address) -> ZomeApiResult<Address> {
+        if !self.state.borrow().entries.contains_key(address) {
+            return Err(mock_error(format!("Entry {} not found for update", address)));
+        }
+        let new_address = self.commit_entry(&new_entry)?;
+
+        let mut state = self.state.borrow_mut();
+        let latest = Self::latest_address(&state, address);
+        if latest != new_address {
+            let previous = state.entries.get_mut(&latest).expect("latest revision exists");
+            previous.crud_status = CrudStatus::Modified;
+            previous.crud_link = Some(new_address.clone());
+        }
+
+        Ok(new_address)
+    }
+
+    fn remove_entry(&self, address: &Address) -> ZomeApiResult<Address> {
+        {
+            let mut state = self.state.borrow_mut();
+            let latest = Self::latest_address(&state, address);
+            match state.entries.get_mut(&latest) {
+                Some(stored) => stored.crud_status = CrudStatus::Deleted,
+                None => return Err(mock_error(format!("Entry {} not found for removal", address))),
+            }
+        }
+        self.commit_entry(&Entry::Deletion(DeletionEntry::new(address.clone())))
+    }
+
+    fn link_entries(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<Address> {
+        let mut state = self.state.borrow_mut();
+        state.links.push(MockLink {
+            base: base.clone(),
+            target: target.clone(),
+            link_type: link_type.to_string(),
+            tag: tag.to_string(),
+        });
+        Ok(mock_address(&format!("link:{}:{}:{}:{}:{}", base, target, link_type, tag, state.links.len())))
+    }
+
+    fn remove_link(&self, base: &Address, target: &Address, link_type: &str, tag: &str) -> ZomeApiResult<()> {
+        self.state.borrow_mut().links.retain(|link| {
+            !(link.base == *base && link.target == *target && link.link_type == link_type && link.tag == tag)
+        });
+        Ok(())
+    }
+
+    fn call(&self, instance_handle: &str, zome_name: &str, _cap_token: Address, fn_name: &str, fn_args: JsonString) -> ZomeApiResult<JsonString> {
+        let handler = self.zome_fns.borrow()
+            .get(&(instance_handle.to_string(), zome_name.to_string(), fn_name.to_string()))
+            .cloned();
+
+        // handler is run without holding any borrow, since it may call back into this backend
+        let bridged = self.zome_fns.borrow().keys().any(|(handle, _, _)| handle == instance_handle);
+        match handler {
+            Some(handler) => handler(fn_args),
+            None if !bridged => Err(mock_error(format!("{}: {}/{}/{}", MISSING_BRIDGE_ERROR, instance_handle, zome_name, fn_name))),
+            None => Err(mock_error(format!("No mock registered for {}/{}/{}", instance_handle, zome_name, fn_name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hdk::holochain_core_types::entry::{ AppEntryValue, entry_type::AppEntryType };
+
+    fn app_entry(content: &str) -> Entry {
+        Entry::App(AppEntryType::from("mock_entry"), AppEntryValue::from(JsonString::from_json(content)))
+    }
+
+    #[test]
+    fn test_mock_updates_and_removes() {
+        let storage = MockStorage::new();
+
+        let initial = storage.commit_entry(&app_entry("1")).unwrap();
+        let updated = storage.update_entry(app_entry("2"), &initial).unwrap();
+        assert_eq!(storage.get_entry(&initial).unwrap(), Some(app_entry("2")), "reads resolve to latest revision");
+        assert_eq!(storage.query("mock_entry").unwrap(), vec![initial.clone(), updated]);
+
+        storage.remove_entry(&initial).unwrap();
+        assert_eq!(storage.get_entry(&initial).unwrap(), None);
+    }
+
+    #[test]
+    fn test_mock_links() {
+        let storage = MockStorage::new();
+        let (a, b) = (Address::from("QmA"), Address::from("QmB"));
+
+        storage.link_entries(&a, &b, "link", "x").unwrap();
+        storage.link_entries(&a, &b, "link", "y").unwrap();
+        assert_eq!(storage.get_links(&a, "link", Some("y")).unwrap(), vec![("y".to_string(), b.clone())]);
+        assert_eq!(storage.count_links(&a, "link"), 2);
+
+        storage.remove_link(&a, &b, "link", "x").unwrap();
+        assert_eq!(storage.get_links(&a, "link", None).unwrap(), vec![("y".to_string(), b)]);
+    }
+}
@@ -0,0 +1,827 @@
+/**
+ * Helper methods related to `time indexes`.
+ *
+ * A `time index` is a tree of `anchor` entries which partitions a set of records by
+ * the time they relate to. The root anchor links to one anchor per year, which link
+ * to one anchor per month, then per day, then per hour. Records are linked from the
+ * hourly bucket containing their timestamp.
+ *
+ * Each link is tagged with the identifier of its target bucket or, for links to records,
+ * with a normalised UTC timestamp string which sorts lexicographically. This allows
+ * ordered, paginated reads which only visit the buckets within the requested range,
+ * rather than loading every link off of a single anchor.
+ *
+ * Records may also be filtered by time range in queries, @see `IndexQuery::time_range`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-06
+ */
+use std::cmp::Ordering;
+use std::convert::{ TryFrom };
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry,
+        entry::Entry::App as AppEntry,
+        entry::entry_type::AppEntryType,
+        entry::AppEntryValue,
+    },
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    read_cache::{ commit_entry, link_entries, remove_link },
+    storage::entry_address,
+    error::{ GraphAPIResult, GraphHelperError },
+    entries::{
+        get_entries_by_key_index,
+    },
+    links::{
+        get_linked_addresses,
+        get_linked_addresses_with_tags,
+        link_entries_if_absent,
+    },
+};
+
+/// Number of records returned by `read_time_indexed_record_entries` if no `limit` is given
+pub const DEFAULT_TIME_INDEX_PAGE_SIZE: usize = 50;
+
+/// Separates the sort key & record address within pagination cursors
+const CURSOR_SEPARATOR: char = '|';
+
+/// Parameters for reading a page of records from a `time index`.
+///
+/// `after` and `before` are ISO8601 timestamps which exclusively bound the range of results.
+/// `cursor` is the `next_cursor` value returned with the previous page of results.
+/// `limit` must be greater than zero, and defaults to `DEFAULT_TIME_INDEX_PAGE_SIZE`.
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default, PartialEq)]
+pub struct TimeIndexQuery {
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+/// A page of records read from a `time index`, in chronological order.
+///
+/// `next_cursor` is set if there are further results after the last record in this page.
+///
+#[derive(Debug, Clone)]
+pub struct TimeIndexPage<A, T> {
+    pub entries: Vec<(A, GraphAPIResult<T>)>,
+    pub next_cursor: Option<String>,
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Reads a page of records of type `T` from a `time index`, oldest first.
+///
+/// Records with identical timestamps are ordered by their address, such that the ordering
+/// of results (and therefore the validity of any cursor) is stable between requests.
+///
+pub fn read_time_indexed_record_entries<T, E, A>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    query: &TimeIndexQuery,
+) -> GraphAPIResult<TimeIndexPage<A, T>>
+    where E: Into<AppEntryType> + Clone,
+        A: From<Address>,
+        T: Clone + TryFrom<AppEntryValue>,
+{
+    read_time_indexed_record_entries_where(
+        index_entry_type, index_id, bucket_link_type, entry_link_type,
+        query, |_address| true,
+    )
+}
+
+/// Same as `read_time_indexed_record_entries`, but only records whose address passes `include`
+/// are returned. Records are filtered as the index is traversed, so that pages are still filled
+/// up to the requested `limit` (typically the filter checks membership of another index).
+///
+pub fn read_time_indexed_record_entries_where<T, E, A, F>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    query: &TimeIndexQuery,
+    include: F,
+) -> GraphAPIResult<TimeIndexPage<A, T>>
+    where E: Into<AppEntryType> + Clone,
+        A: From<Address>,
+        T: Clone + TryFrom<AppEntryValue>,
+        F: Fn(&Address) -> bool,
+{
+    let bounds = TimeIndexBounds::try_from(query)?;
+    let limit = match query.limit {
+        Some(0) => return Err(GraphHelperError::ValidationFailed("Page limit must be greater than zero".to_string())),
+        Some(limit) => limit,
+        None => DEFAULT_TIME_INDEX_PAGE_SIZE,
+    };
+
+    // read one more record than requested, to determine whether there is a next page
+    let root_address = time_index_anchor_address(index_entry_type, index_id, None)?;
+    let mut found = vec![];
+    collect_time_indexed_addresses(
+        &root_address, TIME_INDEX_BUCKET_DEPTH,
+        bucket_link_type, entry_link_type,
+        &bounds, &include, limit.saturating_add(1), &mut found,
+    )?;
+
+    let mut next_cursor = None;
+    if found.len() > limit {
+        found.truncate(limit);
+        next_cursor = found.last().map(|(key, address)| encode_cursor(key, address));
+    }
+
+    Ok(TimeIndexPage {
+        entries: get_entries_by_key_index(found.into_iter().map(|(_key, address)| address).collect())?,
+        next_cursor,
+    })
+}
+
+/// Depth-first traversal of the time index tree, appending `(sort key, record address)`
+/// pairs for records passing `include` to `results` in ascending order until `max_results`
+/// have been found.
+///
+/// `depth` is the number of bucket levels below `bucket_address`.
+///
+fn collect_time_indexed_addresses(
+    bucket_address: &Address,
+    depth: usize,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    bounds: &TimeIndexBounds,
+    include: &dyn Fn(&Address) -> bool,
+    max_results: usize,
+    results: &mut Vec<(String, Address)>,
+) -> GraphAPIResult<()> {
+    if depth == 0 {
+        let mut links: Vec<(String, Address)> = get_linked_addresses_with_tags(bucket_address, entry_link_type)?
+            .into_iter()
+            .filter(|(key, address)| bounds.includes(key, &address.to_string()) && include(address))
+            .collect();
+        links.sort_by(compare_indexed_addresses);
+        links.dedup();
+
+        let remaining = max_results - results.len();
+        results.extend(links.into_iter().take(remaining));
+        return Ok(());
+    }
+
+    let mut buckets: Vec<(String, Address)> = get_linked_addresses_with_tags(bucket_address, bucket_link_type)?
+        .into_iter()
+        .filter(|(bucket_id, _address)| bounds.overlaps_bucket(bucket_id))
+        .collect();
+    buckets.sort_by(|a, b| a.0.cmp(&b.0));
+    buckets.dedup_by(|a, b| a.0 == b.0);
+
+    for (_bucket_id, child_address) in buckets {
+        if results.len() >= max_results {
+            break;
+        }
+        collect_time_indexed_addresses(
+            &child_address, depth - 1,
+            bucket_link_type, entry_link_type,
+            bounds, include, max_results, results,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Determine the addresses of all records in a `time index` from `start` until `end`, in
+/// chronological order. Both bounds are inclusive and optional. An `end` given as a date
+/// without a time includes the entire day.
+///
+pub fn read_time_index_addresses<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> GraphAPIResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    let bounds = TimeIndexBounds::inclusive(start, end)?;
+
+    let root_address = time_index_anchor_address(index_entry_type, index_id, None)?;
+    let mut found = vec![];
+    collect_time_indexed_addresses(
+        &root_address, TIME_INDEX_BUCKET_DEPTH,
+        bucket_link_type, entry_link_type,
+        &bounds, &|_address: &Address| true, usize::MAX, &mut found,
+    )?;
+
+    Ok(found.into_iter().map(|(_key, address)| address).collect())
+}
+
+fn compare_indexed_addresses(a: &(String, Address), b: &(String, Address)) -> Ordering {
+    a.0.cmp(&b.0).then_with(|| a.1.to_string().cmp(&b.1.to_string()))
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Adds the record at `entry_address` to a `time index`, at the position determined by the
+/// ISO8601 `timestamp` provided. Any bucket anchors not yet present in the index are created.
+///
+/// Returns the address of the hourly bucket anchor the record was linked from. Records already
+/// indexed at the same time are not linked again.
+///
+pub fn create_time_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    timestamp: &str,
+    entry_address: &Address,
+) -> GraphAPIResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    let key = time_index_key(timestamp)?;
+    let bucket_address = create_time_index_buckets(index_entry_type, index_id, bucket_link_type, &key)?;
+
+    link_entries_if_absent(&bucket_address, entry_address, entry_link_type, &key)?;
+    Ok(bucket_address)
+}
+
+/// Ensures all bucket anchors needed to index the given sort `key` exist, and returns the
+/// address of the innermost one.
+///
+/// Buckets are shared between all records in the same time period, so links between them
+/// are only created where not already present.
+///
+pub (crate) fn create_time_index_buckets<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    key: &str,
+) -> GraphAPIResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    let mut parent_address = commit_entry(&time_index_anchor(index_entry_type, index_id, None))?;
+
+    for bucket_id in time_index_buckets(key) {
+        let bucket_address = commit_entry(&time_index_anchor(index_entry_type, index_id, Some(&bucket_id)))?;
+
+        let existing = get_linked_addresses(&parent_address, bucket_link_type, &bucket_id)?;
+        if !existing.contains(&bucket_address) {
+            link_entries(&parent_address, &bucket_address, bucket_link_type, &bucket_id)?;
+        }
+
+        parent_address = bucket_address;
+    }
+
+    Ok(parent_address)
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+/// Moves the record at `entry_address` within a `time index` after its timestamp changes from
+/// `previous_timestamp` to `timestamp`. Records without a timestamp are not indexed, so either
+/// may be `None`. Nothing is written if both refer to the same time.
+///
+pub fn update_time_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    bucket_link_type: &str,
+    entry_link_type: &str,
+    previous_timestamp: Option<&str>,
+    timestamp: Option<&str>,
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let previous_key = previous_timestamp.map(time_index_key).transpose()?;
+    let key = timestamp.map(time_index_key).transpose()?;
+    if previous_key == key {
+        return Ok(());
+    }
+
+    if let Some(previous) = previous_timestamp {
+        delete_time_index(index_entry_type, index_id, entry_link_type, previous, entry_address)?;
+    }
+    if let Some(current) = timestamp {
+        create_time_index(index_entry_type, index_id, bucket_link_type, entry_link_type, current, entry_address)?;
+    }
+    Ok(())
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Removes the record at `entry_address` from a `time index`. `timestamp` must be the same
+/// value that the record was indexed with.
+///
+/// Bucket anchors are left in place, since they may be shared with other records.
+///
+pub fn delete_time_index<E>(
+    index_entry_type: &E,
+    index_id: &str,
+    entry_link_type: &str,
+    timestamp: &str,
+    entry_address: &Address,
+) -> GraphAPIResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let key = time_index_key(timestamp)?;
+    let bucket_id = time_index_buckets(&key).pop().unwrap();
+    let bucket_address = time_index_anchor_address(index_entry_type, index_id, Some(&bucket_id))?;
+
+    Ok(remove_link(&bucket_address, entry_address, entry_link_type, &key)?)
+}
+
+//------------------------------[ INTERNALS ]-----------------------------------
+
+/// Number of bucket levels between the root anchor & indexed records (year, month, day, hour)
+const TIME_INDEX_BUCKET_DEPTH: usize = 4;
+
+/// Builds the anchor entry for the root of the time index named `index_id`, or
+/// for one of its buckets.
+///
+fn time_index_anchor<E>(index_entry_type: &E, index_id: &str, bucket_id: Option<&str>) -> Entry
+    where E: Into<AppEntryType> + Clone,
+{
+    let id_string = match bucket_id {
+        Some(bucket) => format!("{}/{}", index_id, bucket),
+        None => index_id.to_string(),
+    };
+    AppEntry(index_entry_type.to_owned().into(), Some(id_string).into())
+}
+
+fn time_index_anchor_address<E>(index_entry_type: &E, index_id: &str, bucket_id: Option<&str>) -> GraphAPIResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    Ok(entry_address(&time_index_anchor(index_entry_type, index_id, bucket_id))?)
+}
+
+/// Determines the identifiers of all buckets containing the given sort `key`, outermost first.
+///
+/// Bucket identifiers are prefixes of the sort keys they contain (`2020`, `2020-03`,
+/// `2020-03-06` & `2020-03-06T09`), so can be compared against them directly.
+///
+pub (crate) fn time_index_buckets(key: &str) -> Vec<String> {
+    [4, 7, 10, 13].iter()
+        .map(|len| key[0..*len].to_string())
+        .collect()
+}
+
+/// Determines the earliest & latest possible sort keys within the bucket `bucket_id`
+///
+fn time_index_bucket_range(bucket_id: &str) -> (String, String) {
+    let len = bucket_id.len();
+    (
+        format!("{}{}", bucket_id, "0000-01-01T00:00:00.000Z".get(len..).unwrap_or("")),
+        format!("{}{}", bucket_id, "9999-12-31T23:59:59.999Z".get(len..).unwrap_or("")),
+    )
+}
+
+/// Converts an ISO8601 / RFC3339 timestamp into the sort key used to index it.
+///
+/// Sort keys are UTC timestamps of the form `YYYY-MM-DDTHH:MM:SS.sssZ`, such that any two
+/// keys compare lexicographically in the same order as the times they represent.
+///
+pub fn time_index_key(timestamp: &str) -> GraphAPIResult<String> {
+    offset_time_index_key(timestamp, 0)
+}
+
+/// Determines the sort key for the time `offset_millis` after `timestamp`
+///
+fn offset_time_index_key(timestamp: &str, offset_millis: i64) -> GraphAPIResult<String> {
+    match parse_timestamp_millis(timestamp) {
+        Some(millis) => Ok(format_timestamp_millis(millis + offset_millis)),
+        None => Err(GraphHelperError::ValidationFailed(format!("Invalid timestamp {}", timestamp))),
+    }
+}
+
+fn encode_cursor(key: &str, address: &Address) -> String {
+    format!("{}{}{}", key, CURSOR_SEPARATOR, address)
+}
+
+fn decode_cursor(cursor: &str) -> GraphAPIResult<(String, String)> {
+    let mut parts = cursor.splitn(2, CURSOR_SEPARATOR);
+    match (parts.next(), parts.next()) {
+        (Some(key), Some(address)) if parse_timestamp_millis(key).is_some() => Ok((key.to_string(), address.to_string())),
+        _ => Err(GraphHelperError::ValidationFailed(format!("Invalid pagination cursor {}", cursor))),
+    }
+}
+
+/// Range of sort keys requested by a `TimeIndexQuery`
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TimeIndexBounds {
+    after: Option<String>,
+    before: Option<String>,
+    cursor: Option<(String, String)>,
+}
+
+impl TryFrom<&TimeIndexQuery> for TimeIndexBounds {
+    type Error = GraphHelperError;
+
+    fn try_from(query: &TimeIndexQuery) -> GraphAPIResult<Self> {
+        Ok(TimeIndexBounds {
+            after: query.after.as_ref().map(|t| time_index_key(t)).transpose()?,
+            before: query.before.as_ref().map(|t| time_index_key(t)).transpose()?,
+            cursor: query.cursor.as_ref().map(|c| decode_cursor(c)).transpose()?,
+        })
+    }
+}
+
+impl TimeIndexBounds {
+    /// Bounds including all records from `start` until `end`. Since sort keys have millisecond
+    /// precision, these are converted into exclusive bounds one millisecond outside the range.
+    /// An `end` date without a time is extended to the end of that day.
+    ///
+    fn inclusive(start: Option<&str>, end: Option<&str>) -> GraphAPIResult<Self> {
+        Ok(TimeIndexBounds {
+            after: start.map(|t| offset_time_index_key(t, -1)).transpose()?,
+            before: end.map(|t| offset_time_index_key(t, if is_date_only(t) { 86400000 } else { 1 })).transpose()?,
+            cursor: None,
+        })
+    }
+
+    /// Determines whether the record indexed by `key` at `address` is within range
+    ///
+    fn includes(&self, key: &str, address: &str) -> bool {
+        if let Some(after) = &self.after {
+            if key <= &after[..] { return false; }
+        }
+        if let Some(before) = &self.before {
+            if key >= &before[..] { return false; }
+        }
+        if let Some((cursor_key, cursor_address)) = &self.cursor {
+            if (key, address) <= (&cursor_key[..], &cursor_address[..]) { return false; }
+        }
+        true
+    }
+
+    /// Determines whether any keys within the bucket `bucket_id` may be within range
+    ///
+    fn overlaps_bucket(&self, bucket_id: &str) -> bool {
+        let (first_key, last_key) = time_index_bucket_range(bucket_id);
+
+        if let Some(after) = &self.after {
+            if last_key <= *after { return false; }
+        }
+        if let Some((cursor_key, _)) = &self.cursor {
+            if last_key < *cursor_key { return false; }
+        }
+        if let Some(before) = &self.before {
+            if first_key >= *before { return false; }
+        }
+        true
+    }
+}
+
+/// Parses an ISO8601 / RFC3339 timestamp into milliseconds since the UNIX epoch.
+///
+/// Accepts full dates (`2020-03-06`) or datetimes with optional seconds, fractional seconds
+/// and UTC offset (`2020-03-06T09:30`, `2020-03-06T09:30:15.123+10:00`). Datetimes without
+/// an offset are interpreted as UTC. Only years 0000-9999 are supported.
+///
+fn parse_timestamp_millis(timestamp: &str) -> Option<i64> {
+    parse_timestamp(timestamp).map(|(millis, _has_time)| millis)
+}
+
+/// Parses a timestamp as per `parse_timestamp_millis`, additionally returning whether
+/// it held a time component.
+///
+fn parse_timestamp(timestamp: &str) -> Option<(i64, bool)> {
+    let bytes = timestamp.trim().as_bytes();
+    let mut pos = 0;
+
+    let year = read_digits(bytes, &mut pos, 4)?;
+    expect_char(bytes, &mut pos, b'-')?;
+    let month = read_digits(bytes, &mut pos, 2)?;
+    expect_char(bytes, &mut pos, b'-')?;
+    let day = read_digits(bytes, &mut pos, 2)?;
+
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let (mut hour, mut minute, mut second, mut millis, mut offset_minutes) = (0, 0, 0, 0, 0);
+    let has_time = pos < bytes.len();
+
+    if has_time {
+        match bytes[pos] {
+            b'T' | b't' | b' ' => pos += 1,
+            _ => return None,
+        }
+        hour = read_digits(bytes, &mut pos, 2)?;
+        expect_char(bytes, &mut pos, b':')?;
+        minute = read_digits(bytes, &mut pos, 2)?;
+
+        if pos < bytes.len() && bytes[pos] == b':' {
+            pos += 1;
+            second = read_digits(bytes, &mut pos, 2)?;
+
+            if pos < bytes.len() && (bytes[pos] == b'.' || bytes[pos] == b',') {
+                pos += 1;
+                let start = pos;
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    // precision beyond milliseconds is truncated
+                    if pos - start < 3 {
+                        millis = millis * 10 + (bytes[pos] - b'0') as i64;
+                    }
+                    pos += 1;
+                }
+                if pos == start {
+                    return None;
+                }
+                for _ in (pos - start)..3 {
+                    millis *= 10;
+                }
+            }
+        }
+
+        if pos < bytes.len() {
+            match bytes[pos] {
+                b'Z' | b'z' => pos += 1,
+                sign @ b'+' | sign @ b'-' => {
+                    pos += 1;
+                    let offset_hours = read_digits(bytes, &mut pos, 2)?;
+                    if pos < bytes.len() && bytes[pos] == b':' {
+                        pos += 1;
+                    }
+                    let offset_mins = if pos < bytes.len() { read_digits(bytes, &mut pos, 2)? } else { 0 };
+                    offset_minutes = offset_hours * 60 + offset_mins;
+                    if sign == b'-' {
+                        offset_minutes = -offset_minutes;
+                    }
+                },
+                _ => return None,
+            }
+        }
+
+        // leap seconds are clamped to the end of the preceding second
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        second = second.min(59);
+    }
+
+    if pos != bytes.len() {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second - offset_minutes * 60;
+    Some((seconds * 1000 + millis, has_time))
+}
+
+/// Determines whether `timestamp` is a valid date without any time component (`2020-03-06`)
+///
+fn is_date_only(timestamp: &str) -> bool {
+    parse_timestamp(timestamp).map_or(false, |(_millis, has_time)| !has_time)
+}
+
+/// Formats milliseconds since the UNIX epoch as a sort key
+///
+fn format_timestamp_millis(millis: i64) -> String {
+    let days = millis.div_euclid(86400000);
+    let day_millis = millis.rem_euclid(86400000);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day,
+        day_millis / 3600000,
+        (day_millis / 60000) % 60,
+        (day_millis / 1000) % 60,
+        day_millis % 1000,
+    )
+}
+
+fn read_digits(bytes: &[u8], pos: &mut usize, count: usize) -> Option<i64> {
+    if *pos + count > bytes.len() {
+        return None;
+    }
+    let mut value = 0;
+    for b in &bytes[*pos..*pos + count] {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value * 10 + (b - b'0') as i64;
+    }
+    *pos += count;
+    Some(value)
+}
+
+fn expect_char(bytes: &[u8], pos: &mut usize, expected: u8) -> Option<()> {
+    if *pos < bytes.len() && bytes[*pos] == expected {
+        *pos += 1;
+        Some(())
+    } else {
+        None
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for the given proleptic Gregorian calendar date
+///
+/// @see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+///
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of `days_from_civil`
+///
+/// @see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+///
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_keys_normalise_to_utc() {
+        assert_eq!(time_index_key("2019-11-19T04:29:55.000Z"), Ok("2019-11-19T04:29:55.000Z".to_string()));
+        assert_eq!(time_index_key("2019-11-19T14:29:55.123456+10:00"), Ok("2019-11-19T04:29:55.123Z".to_string()));
+        assert_eq!(time_index_key("2020-03-01T00:30-01:00"), Ok("2020-03-01T01:30:00.000Z".to_string()));
+        assert_eq!(time_index_key("2020-03-01T00:30+01:00"), Ok("2020-02-29T23:30:00.000Z".to_string()));
+        assert_eq!(time_index_key("2020-02-29"), Ok("2020-02-29T00:00:00.000Z".to_string()));
+        assert_eq!(time_index_key("1969-12-31T23:59:59.9Z"), Ok("1969-12-31T23:59:59.900Z".to_string()));
+
+        assert!(time_index_key("2019-02-29").is_err());
+        assert!(time_index_key("2019-11-19T25:00:00Z").is_err());
+        assert!(time_index_key("19 November 2019").is_err());
+    }
+
+    #[test]
+    fn test_date_only_timestamps() {
+        assert!(is_date_only("2020-03-06"));
+        assert!(is_date_only(" 2020-03-06 "));
+        assert!(!is_date_only("2020-03-06T09:30"));
+        assert!(!is_date_only("2020-03-06T09:30:15.123+10:00"));
+        assert!(!is_date_only("2020-3-6T9"), "malformed values are not dates");
+        assert!(!is_date_only("2019-02-29"), "invalid dates are not dates");
+    }
+
+    #[test]
+    fn test_buckets_are_key_prefixes() {
+        assert_eq!(
+            time_index_buckets("2019-11-19T04:29:55.000Z"),
+            vec!["2019", "2019-11", "2019-11-19", "2019-11-19T04"],
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = TimeIndexBounds::try_from(&TimeIndexQuery {
+            after: Some("2019-11-19T04:00:00Z".to_string()),
+            before: Some("2019-11-21T00:00:00Z".to_string()),
+            cursor: Some(encode_cursor("2019-11-20T00:00:00.000Z", &Address::from("QmB"))),
+            ..TimeIndexQuery::default()
+        }).unwrap();
+
+        assert!(bounds.overlaps_bucket("2019"));
+        assert!(bounds.overlaps_bucket("2019-11-20"));
+        assert!(!bounds.overlaps_bucket("2019-11-19"));
+        assert!(!bounds.overlaps_bucket("2019-11-21"));
+
+        assert!(bounds.includes("2019-11-20T00:00:00.000Z", "QmC"));
+        assert!(!bounds.includes("2019-11-20T00:00:00.000Z", "QmB"));
+        assert!(!bounds.includes("2019-11-20T00:00:00.000Z", "QmA"));
+        assert!(bounds.includes("2019-11-20T23:59:59.999Z", "QmA"));
+        assert!(!bounds.includes("2019-11-21T00:00:00.000Z", "QmA"));
+
+        assert!(decode_cursor("not-a-cursor").is_err());
+    }
+
+    #[test]
+    fn test_inclusive_bounds() {
+        let bounds = TimeIndexBounds::inclusive(Some("2020-03-01"), Some("2020-03-31")).unwrap();
+        assert!(!bounds.includes("2020-02-29T23:59:59.999Z", "QmA"));
+        assert!(bounds.includes("2020-03-01T00:00:00.000Z", "QmA"));
+        assert!(bounds.includes("2020-03-31T23:59:59.999Z", "QmA"));
+        assert!(!bounds.includes("2020-04-01T00:00:00.000Z", "QmA"));
+
+        let bounds = TimeIndexBounds::inclusive(None, Some("2020-03-31T12:00:00Z")).unwrap();
+        assert!(bounds.includes("2020-03-31T12:00:00.000Z", "QmA"));
+        assert!(!bounds.includes("2020-03-31T12:00:00.001Z", "QmA"));
+        assert!(bounds.overlaps_bucket("1970"));
+    }
+
+    #[test]
+    fn test_time_range_reads() {
+        use std::rc::Rc;
+        use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+        let storage = Rc::new(MockStorage::new());
+        let first = storage.commit_entry(&AppEntry("test_entry".into(), Some("first".to_string()).into())).unwrap();
+        let second = storage.commit_entry(&AppEntry("test_entry".into(), Some("second".to_string()).into())).unwrap();
+        let index_type = "test_time_index".to_string();
+        let range = |start: Option<&str>, end: Option<&str>| {
+            read_time_index_addresses(&index_type, "all", "test_bucket", "test_entry_index", start, end).unwrap()
+        };
+
+        with_storage_backend(storage.clone(), || {
+            create_time_index(&index_type, "all", "test_bucket", "test_entry_index", "2020-03-31T10:00:00Z", &second).unwrap();
+            create_time_index(&index_type, "all", "test_bucket", "test_entry_index", "2020-03-01T00:00:00Z", &first).unwrap();
+
+            assert_eq!(range(Some("2020-03-01"), Some("2020-03-31")), vec![first.clone(), second.clone()]);
+            assert_eq!(range(Some("2020-03-02"), None), vec![second.clone()]);
+            assert_eq!(range(None, Some("2020-03-30")), vec![first.clone()]);
+
+            update_time_index(&index_type, "all", "test_bucket", "test_entry_index", Some("2020-03-01T00:00:00Z"), Some("2020-04-01T00:00:00Z"), &first).unwrap();
+            assert_eq!(range(Some("2020-03-01"), Some("2020-03-31")), vec![second.clone()]);
+            assert_eq!(range(Some("2020-04-01"), None), vec![first.clone()]);
+
+            update_time_index(&index_type, "all", "test_bucket", "test_entry_index", Some("2020-03-31T10:00:00Z"), None, &second).unwrap();
+            assert_eq!(range(None, None), vec![first.clone()]);
+        });
+    }
+
+    #[test]
+    fn test_filtered_pages_are_filled() {
+        use std::rc::Rc;
+        use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+        let storage = Rc::new(MockStorage::new());
+        let records: Vec<Address> = (0..5)
+            .map(|i| storage.commit_entry(&AppEntry("test_entry".into(), Some(format!("record {}", i)).into())).unwrap())
+            .collect();
+        let index_type = "test_time_index".to_string();
+        let included = vec![records[0].clone(), records[2].clone(), records[4].clone()];
+        let page = |cursor: Option<String>| -> TimeIndexPage<Address, AppEntryValue> {
+            read_time_indexed_record_entries_where(
+                &index_type, "all", "test_bucket", "test_entry_index",
+                &TimeIndexQuery { limit: Some(2), cursor, ..TimeIndexQuery::default() },
+                |address| included.contains(address),
+            ).unwrap()
+        };
+
+        with_storage_backend(storage.clone(), || {
+            for (i, address) in records.iter().enumerate() {
+                create_time_index(&index_type, "all", "test_bucket", "test_entry_index", &format!("2020-03-0{}T00:00:00Z", i + 1), address).unwrap();
+            }
+
+            let first_page = page(None);
+            assert_eq!(first_page.entries.iter().map(|(a, _)| a.clone()).collect::<Vec<Address>>(), vec![records[0].clone(), records[2].clone()]);
+            assert!(first_page.next_cursor.is_some());
+
+            let second_page = page(first_page.next_cursor);
+            assert_eq!(second_page.entries.iter().map(|(a, _)| a.clone()).collect::<Vec<Address>>(), vec![records[4].clone()]);
+            assert!(second_page.next_cursor.is_none());
+        });
+    }
+
+    #[test]
+    fn test_page_limits() {
+        use std::rc::Rc;
+        use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+        let storage = Rc::new(MockStorage::new());
+        let record = storage.commit_entry(&AppEntry("test_entry".into(), Some("record".to_string()).into())).unwrap();
+        let index_type = "test_time_index".to_string();
+        let page = |limit: Option<usize>| -> GraphAPIResult<TimeIndexPage<Address, AppEntryValue>> {
+            read_time_indexed_record_entries(
+                &index_type, "all", "test_bucket", "test_entry_index",
+                &TimeIndexQuery { limit, ..TimeIndexQuery::default() },
+            )
+        };
+
+        with_storage_backend(storage.clone(), || {
+            create_time_index(&index_type, "all", "test_bucket", "test_entry_index", "2020-03-01T00:00:00Z", &record).unwrap();
+
+            match page(Some(0)) {
+                Err(GraphHelperError::ValidationFailed(_)) => (),
+                other => panic!("zero limit accepted: {:?}", other.map(|p| p.next_cursor)),
+            }
+
+            let unlimited = page(Some(usize::MAX)).unwrap();
+            assert_eq!(unlimited.entries.len(), 1);
+            assert!(unlimited.next_cursor.is_none());
+        });
+    }
+}
@@ -0,0 +1,427 @@
+/**
+ * Compensating rollback for operations which write multiple entries & links
+ *
+ * Holochain provides no transactionality across multiple DHT writes. Where a zome
+ * API method must write several records and a later step fails (eg. due to entry
+ * validation), any writes made in earlier steps would otherwise remain visible.
+ *
+ * A `UnitOfWork` wraps the standard record & index helpers and keeps a log of every
+ * entry and link written through it. If the operation fails, the log is replayed in
+ * reverse in order to revert those writes:
+ *
+ * - created entries are removed
+ * - updated entries are restored to their previous revision (by committing the
+ *   previous entry data as a new revision), unless they have since been updated again
+ * - created links are removed
+ *
+ * Entries which may be shared between records (anchors and `key indexes` referencing
+ * foreign records) are never removed during rollback; only the links created from them.
+ * Such dangling entries do no harm, as with `remote indexes`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-04
+ */
+use std::convert::TryFrom;
+use serde::Serialize;
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::{
+            Entry,
+            Entry::App as AppEntry,
+            entry_type::AppEntryType,
+            AppEntryValue,
+        },
+    },
+};
+
+use super::{
+    read_cache::{ get_entry, commit_entry, link_entries as hdk_link_entries, remove_link, remove_entry, update_entry as hdk_update_entry },
+    storage::entry_address,
+    links::{ get_linked_addresses, encode_link_tag },
+    identifiers::{ RECORD_INITIAL_ENTRY_LINK_TAG, ANCHOR_POINTER_LINK_TAG },
+    type_wrappers::Addressable,
+    error::{ GraphAPIResult, GraphHelperError },
+    record_interface::Updateable,
+    schema::stored_entry_data,
+    entries::{
+        create_entry,
+        update_entry_from_revision,
+    },
+    keys::{
+        create_key_index,
+        get_key_index_address,
+    },
+    anchors::{
+        check_anchor_available,
+        time_index_key,
+        create_time_index_buckets,
+        category_index_anchor,
+    },
+};
+
+/// Write operations recorded by a `UnitOfWork`, in the order they were performed
+///
+#[derive(Debug, Clone)]
+enum WriteOperation {
+    EntryCreated(Address),
+    EntryUpdated { previous_entry: Entry, replaced_address: Address },
+    LinkCreated { base: Address, target: Address, link_type: String, link_tag: String },
+}
+
+/// Log of writes performed during a multi-record operation, which can be reverted
+/// if the operation fails partway through.
+///
+/// Most callers will want to use `with_unit_of_work` rather than managing this directly.
+///
+#[derive(Debug, Clone, Default)]
+pub struct UnitOfWork {
+    operations: Vec<WriteOperation>,
+}
+
+/// Run `operation` within a new `UnitOfWork`. If the operation returns an error, all writes
+/// made via the provided `UnitOfWork` are reverted before the error is returned to the caller.
+///
+/// If any writes cannot be reverted, the error is returned as `GraphHelperError::RollbackFailed`,
+/// wrapping the operation's error along with every failure encountered during rollback.
+///
+pub fn with_unit_of_work<T, F>(operation: F) -> GraphAPIResult<T>
+    where F: FnOnce(&mut UnitOfWork) -> GraphAPIResult<T>,
+{
+    let mut unit = UnitOfWork::new();
+
+    operation(&mut unit).map_err(|cause| {
+        let rollback_errors: Vec<GraphHelperError> = unit.rollback().into_iter()
+            .filter_map(Result::err)
+            .collect();
+
+        match rollback_errors.len() {
+            0 => cause,
+            _ => GraphHelperError::RollbackFailed { cause: Box::new(cause), rollback_errors },
+        }
+    })
+}
+
+impl UnitOfWork {
+    pub fn new() -> Self {
+        UnitOfWork { operations: vec![] }
+    }
+
+    //-------------------------------[ CREATE ]-------------------------------------
+
+    /// Same as `records::create_record`, with all written entries & links logged for rollback.
+    ///
+    pub fn create_record<E, C, A, S>(
+        &mut self,
+        base_entry_type: S,
+        entry_type: S,
+        initial_entry_link_type: &str,
+        create_payload: C,
+    ) -> GraphAPIResult<(A, E)>
+        where E: Clone + Into<AppEntryValue>,
+            C: Into<E>,
+            S: Into<AppEntryType>,
+            A: From<Address>,
+    {
+        // write underlying entry
+        let (address, entry_resp) = create_entry(entry_type, create_payload)?;
+        self.operations.push(WriteOperation::EntryCreated(address.clone()));
+
+        // create a key index pointer
+        let base_address = create_key_index(&(base_entry_type.into()), &address)?;
+        self.operations.push(WriteOperation::EntryCreated(base_address.clone()));
+
+        self.link_entries(&base_address, &address, initial_entry_link_type, RECORD_INITIAL_ENTRY_LINK_TAG)?;
+
+        Ok((A::from(base_address), entry_resp))
+    }
+
+    /// Same as `anchors::create_anchor_index`, with the anchor link logged for rollback.
+    /// The anchor entry itself is left in place, since it may be shared with other records.
+    ///
+    pub fn create_anchor_index<E>(
+        &mut self,
+        id_entry_type: &E,
+        id_link_type: &str,
+        id_string: &String,
+        entry_address: &Address,
+    ) -> GraphAPIResult<Address>
+        where E: Into<AppEntryType> + Clone,
+    {
+        check_anchor_available(id_entry_type, id_link_type, id_string, Some(entry_address))?;
+
+        let anchor_entry = AppEntry(id_entry_type.to_owned().into(), Some((*id_string).to_owned()).into());
+        let anchor_address = commit_entry(&anchor_entry)?;
+
+        self.link_entries(&anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
+
+        Ok(anchor_address)
+    }
+
+    /// Same as `anchors::create_time_index`, with the link to the indexed entry logged for rollback.
+    /// Bucket anchors & the links between them are left in place, since they may be shared with
+    /// other records.
+    ///
+    pub fn create_time_index<E>(
+        &mut self,
+        index_entry_type: &E,
+        index_id: &str,
+        bucket_link_type: &str,
+        entry_link_type: &str,
+        timestamp: &str,
+        entry_address: &Address,
+    ) -> GraphAPIResult<Address>
+        where E: Into<AppEntryType> + Clone,
+    {
+        let key = time_index_key(timestamp)?;
+        let bucket_address = create_time_index_buckets(index_entry_type, index_id, bucket_link_type, &key)?;
+
+        self.link_entries(&bucket_address, entry_address, entry_link_type, &key)?;
+
+        Ok(bucket_address)
+    }
+
+    /// Same as `anchors::create_category_index`, with the links to the indexed entry logged for rollback.
+    /// Category anchors are left in place, since they may be shared with other records.
+    ///
+    pub fn create_category_index<E>(
+        &mut self,
+        index_entry_type: &E,
+        index_id: &str,
+        entry_link_type: &str,
+        values: &[String],
+        entry_address: &Address,
+    ) -> GraphAPIResult<Vec<Address>>
+        where E: Into<AppEntryType> + Clone,
+    {
+        values.iter()
+            .map(|value| {
+                let anchor_address = commit_entry(&category_index_anchor(index_entry_type, index_id, value))?;
+                self.link_entries(&anchor_address, entry_address, entry_link_type, value)?;
+                Ok(anchor_address)
+            })
+            .collect()
+    }
+
+    /// Same as `links::link_entries`, with the link logged for rollback.
+    ///
+    pub fn link_entries(
+        &mut self,
+        base: &Address,
+        target: &Address,
+        link_type: &str,
+        link_tag: &str,
+    ) -> GraphAPIResult<Address> {
+        let link_address = hdk_link_entries(base, target, link_type, link_tag)?;
+
+        self.operations.push(WriteOperation::LinkCreated {
+            base: base.clone(),
+            target: target.clone(),
+            link_type: link_type.to_string(),
+            link_tag: link_tag.to_string(),
+        });
+
+        Ok(link_address)
+    }
+
+    /// Same as `links::link_entries_with_attributes`, with the link logged for rollback.
+    ///
+    pub fn link_entries_with_attributes<T: Serialize>(
+        &mut self,
+        base: &Address,
+        target: &Address,
+        link_type: &str,
+        link_tag: &str,
+        attributes: &T,
+    ) -> GraphAPIResult<Address> {
+        let tag = encode_link_tag(link_tag, attributes)?;
+        self.link_entries(base, target, link_type, tag.as_str())
+    }
+
+    /// Same as `local_indexes::create_direct_index`, with any links created logged for rollback.
+    /// Links which were already present are neither re-created nor logged, so that rollback
+    /// cannot remove them.
+    ///
+    /// Unlike `create_direct_index`, any failure in creating either link is treated as an error.
+    ///
+    pub fn create_direct_index(
+        &mut self,
+        source: &Address,
+        dest: &Address,
+        link_type: &str,
+        link_name: &str,
+        link_type_reciprocal: &str,
+        link_name_reciprocal: &str,
+    ) -> GraphAPIResult<Vec<Address>> {
+        let mut created = vec![];
+        for (from, to, link_type, link_name) in [
+            (source, dest, link_type, link_name),
+            (dest, source, link_type_reciprocal, link_name_reciprocal),
+        ].iter() {
+            if !get_linked_addresses(from, link_type, link_name)?.contains(to) {
+                created.push(self.link_entries(from, to, link_type, link_name)?);
+            }
+        }
+        Ok(created)
+    }
+
+    /// Same as `remote_indexes::create_direct_remote_index_destination`, with all links logged
+    /// for rollback. The `key index` for the remote record is left in place, since it may be
+    /// shared with other records.
+    ///
+    pub fn create_direct_remote_index_destination<A, B>(
+        &mut self,
+        remote_base_entry_type: &str,
+        origin_relationship_link_type: &str,
+        origin_relationship_link_tag: &str,
+        destination_relationship_link_type: &str,
+        destination_relationship_link_tag: &str,
+        source_base_address: &A,
+        target_base_addresses: Vec<B>,
+    ) -> GraphAPIResult<Vec<Address>>
+        where A: AsRef<Address>,
+            B: AsRef<Address>,
+    {
+        let base_entry: AppEntryType = remote_base_entry_type.to_string().into();
+        let base_address = create_key_index(&base_entry, source_base_address.as_ref())?;
+
+        target_base_addresses.iter()
+            .map(|target_address| {
+                self.create_direct_index(
+                    &base_address, target_address.as_ref(),
+                    origin_relationship_link_type, origin_relationship_link_tag,
+                    destination_relationship_link_type, destination_relationship_link_tag,
+                )?;
+                Ok(target_address.as_ref().clone())
+            })
+            .collect()
+    }
+
+    //-------------------------------[ UPDATE ]-------------------------------------
+
+    /// Same as `records::update_record`, with the previous revision of the entry logged for rollback.
+    ///
+    /// If `base_revision` is provided, the update fails with `GraphHelperError::RevisionConflict`
+    /// unless it is still the most recent revision of the record. @see `entries::update_entry_from_revision`
+    ///
+    pub fn update_record<E, U, A, S>(
+        &mut self,
+        entry_type: S,
+        address: &A,
+        base_revision: Option<&Address>,
+        update_payload: &U,
+    ) -> GraphAPIResult<E>
+        where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
+            S: Into<AppEntryType> + Clone,
+            A: AsRef<Address>,
+    {
+        // read the current revision of the entry before modifying it
+        let data_address = get_key_index_address(address.as_ref())?;
+        // the stored entry is kept as-is for rollback, since it may be in an earlier schema version than `E`
+        let previous_entry = get_entry(&data_address)
+            .map_err(|e| GraphHelperError::from_read_error(e, &data_address))?
+            .ok_or(GraphHelperError::NotFound(address.as_ref().clone()))?;
+        let previous_address = entry_address(&previous_entry)?;
+
+        let (new_address, new_entry): (Address, E) = update_entry_from_revision(entry_type, &Addressable::from(data_address), base_revision, update_payload)?;
+
+        // no-op updates do not write anything, so there is nothing to revert
+        if new_address != previous_address {
+            self.operations.push(WriteOperation::EntryUpdated { previous_entry, replaced_address: new_address });
+        }
+
+        Ok(new_entry)
+    }
+
+    //-------------------------------[ ROLLBACK ]-----------------------------------
+
+    /// Reverts all logged writes, most recent first. Returns the result of each reversion.
+    ///
+    /// Updated entries are only restored if the revision written by this `UnitOfWork` is still the
+    /// most recent, so that concurrent updates are not overwritten. Otherwise, the reversion fails
+    /// with `GraphHelperError::RevisionConflict` describing the current revision.
+    ///
+    pub fn rollback(self) -> Vec<GraphAPIResult<()>> {
+        self.operations.into_iter().rev()
+            .map(|op| {
+                match op {
+                    WriteOperation::LinkCreated { base, target, link_type, link_tag } => {
+                        Ok(remove_link(&base, &target, link_type, link_tag)?)
+                    },
+                    WriteOperation::EntryCreated(address) => {
+                        Ok(remove_entry(&address)?)
+                    },
+                    WriteOperation::EntryUpdated { previous_entry, replaced_address } => {
+                        let current_entry = get_entry(&replaced_address)
+                            .map_err(|e| GraphHelperError::from_read_error(e, &replaced_address))?
+                            .ok_or_else(|| GraphHelperError::NotFound(replaced_address.clone()))?;
+                        let current_revision = entry_address(&current_entry)?;
+                        if current_revision != replaced_address {
+                            return Err(GraphHelperError::RevisionConflict {
+                                base_revision: replaced_address,
+                                current_revision,
+                                current_entry: match current_entry {
+                                    AppEntry(_, value) => stored_entry_data(&value).1,
+                                    _ => serde_json::Value::Null,
+                                },
+                            });
+                        }
+
+                        hdk_update_entry(previous_entry, &replaced_address)?;
+                        Ok(())
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::storage::{ MockStorage, StorageBackend, with_storage_backend };
+
+    fn test_entry(value: &str) -> Entry {
+        AppEntry("test_entry".into(), Some(value.to_string()).into())
+    }
+
+    #[test]
+    fn test_rollback_restores_updated_entries() {
+        let storage = Rc::new(MockStorage::new());
+        let original = storage.commit_entry(&test_entry("first")).unwrap();
+        let updated = storage.update_entry(test_entry("second"), &original).unwrap();
+
+        // previous data differs from the original revision, since the mock cannot revert to an identical entry
+        let unit = UnitOfWork { operations: vec![
+            WriteOperation::EntryUpdated { previous_entry: test_entry("restored"), replaced_address: updated },
+        ] };
+        with_storage_backend(storage.clone(), || {
+            assert_eq!(unit.rollback(), vec![Ok(())]);
+        });
+        assert_eq!(storage.get_entry(&original).unwrap(), Some(test_entry("restored")));
+    }
+
+    #[test]
+    fn test_rollback_preserves_concurrent_updates() {
+        let storage = Rc::new(MockStorage::new());
+        let original = storage.commit_entry(&test_entry("first")).unwrap();
+        let updated = storage.update_entry(test_entry("second"), &original).unwrap();
+        let concurrent = storage.update_entry(test_entry("third"), &updated).unwrap();
+
+        let unit = UnitOfWork { operations: vec![
+            WriteOperation::EntryUpdated { previous_entry: test_entry("first"), replaced_address: updated.clone() },
+        ] };
+        with_storage_backend(storage.clone(), || {
+            match unit.rollback().pop() {
+                Some(Err(GraphHelperError::RevisionConflict { base_revision, current_revision, .. })) => {
+                    assert_eq!(base_revision, updated);
+                    assert_eq!(current_revision, concurrent);
+                },
+                other => panic!("expected revision conflict, got {:?}", other),
+            }
+        });
+        assert_eq!(storage.get_entry(&original).unwrap(), Some(test_entry("third")));
+    }
+}
@@ -0,0 +1,319 @@
+/**
+ * Scaffolding for zomes exposing a standard record API
+ *
+ * `record_zome!` expands to a complete `#[zome]` module for some record type: `init`,
+ * `validate_agent`, the record's entry & base entry definitions, and any of the standard
+ * zome API methods, each of which forwards to the `receive_*` method of the same name
+ * from the record's `_lib` crate. Read methods run with the read cache enabled.
+ *
+ * To override a standard method, omit it from the `api` list and declare it yourself in
+ * the trailing block, along with any additional entry definitions or zome API methods.
+ * The `init` & `validate_agent` callbacks and the record's entry & base entry definitions
+ * are always generated, but may be given a function to call in place of the default via
+ * the `init`, `validate_agent`, `entry_def` & `base_entry_def` options.
+ *
+ * If `capabilities` are given, the zome's `init` callback requests them from the zomes which
+ * grant them. @see `rpc::register_remote_capabilities`
+ *
+ * Names of the generated zome API methods are derived from `record` & `records`, and the
+ * library methods they call from `receive` & `receive_many` (which default to the same).
+ * `receive` also names the zome in errors from methods restricted to the local agent.
+ * The following must be in scope at the call site: `entry_def` & `base_entry_def` from the
+ * record's `_defs` crate, the record's `_rpc` & `_lib` crates, and `hdk::prelude::*`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-12
+ */
+
+/// Build a zome exposing the standard API for a record type.
+///
+/// Available `api` methods are `create`, `get`, `get_history`, `update`, `delete`, `restore`,
+/// `query`, `scope`, `aggregate`, `check_index_integrity` and `migrate_entries`. `check_index_integrity`
+/// & `migrate_entries` may only be called by the agent running the zome. `delete` returns a `bool` unless a return type is
+/// given as `delete -> DeleteReport`. `query` returns a `QueryResponse` holding matching records alongside
+/// errors for any which could not be read. `scope` adds a `get_*_in_scope` method listing all records
+/// `in_scope_of` some scope. `aggregate` adds `count_*` & `aggregate_*` methods, which accept the
+/// same `QueryParams` as `query` and return a `CountResponse` & `AggregateResponse` respectively (as
+/// defined in `entries`, the latter aliased for the record's address type by the rpc crate).
+///
+/// `init` and `validate_agent` hooks have the same signatures as the zome callbacks they replace
+/// (`fn() -> Result<(), String>` & `fn(EntryValidationData<AgentId>) -> Result<(), String>`). An `init`
+/// hook runs after any `capabilities` have been requested. `entry_def` & `base_entry_def` hooks return
+/// a `ValidatingEntryType`, and default to `entry_def()` & `base_entry_def()`.
+///
+/// Zomes which create `remote indexes` should also include `remote_index_outbox`, which adds the
+/// outbox entry definition along with `get_pending_remote_index_operations` and `retry_remote_index_sync`
+/// methods for inspecting & replaying requests which could not be delivered. Only the agent running
+/// the zome may replay requests. @see `outbox`
+///
+/// ```ignore
+/// record_zome! {
+///     zome: rea_commitment_zome,
+///     record: commitment,
+///     records: commitments,
+///     id: CommitmentAddress,
+///     capabilities: COMMITMENT_REMOTE_CAPABILITIES,
+///     validate_agent: validate_commitment_agent,
+///     api: [create, get, get_history, update, delete, query, check_index_integrity],
+///     {
+///         #[zome_fn("hc_public")]
+///         fn some_other_method(address: CommitmentAddress) -> ZomeApiResult<bool> { ... }
+///     }
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! record_zome {
+    // entrypoints
+
+    (
+        zome: $zome:ident,
+        record: $record:ident,
+        records: $records:ident,
+        id: $id:ty,
+        $(capabilities: $capabilities:expr,)?
+        $(init: $init:path,)?
+        $(validate_agent: $validate_agent:path,)?
+        $(entry_def: $entry_def:path,)?
+        $(base_entry_def: $base_entry_def:path,)?
+        api: [ $($api:tt)* ] $(,)?
+        $({ $($extra:tt)* })?
+    ) => {
+        $crate::record_zome! {
+            zome: $zome,
+            record: $record,
+            records: $records,
+            receive: $record,
+            receive_many: $records,
+            id: $id,
+            $(capabilities: $capabilities,)?
+            $(init: $init,)?
+            $(validate_agent: $validate_agent,)?
+            $(entry_def: $entry_def,)?
+            $(base_entry_def: $base_entry_def,)?
+            api: [ $($api)* ],
+            { $($($extra)*)? }
+        }
+    };
+    (
+        zome: $zome:ident,
+        record: $record:ident,
+        records: $records:ident,
+        receive: $receive:ident,
+        receive_many: $receive_many:ident,
+        id: $id:ty,
+        $(capabilities: $capabilities:expr,)?
+        $(init: $init:path,)?
+        $(validate_agent: $validate_agent:path,)?
+        $(entry_def: $entry_def:path,)?
+        $(base_entry_def: $base_entry_def:path,)?
+        api: [ $($api:tt)* ] $(,)?
+        $({ $($extra:tt)* })?
+    ) => {
+        $crate::record_zome! {
+            @build { $zome, $record, $records, $receive, $receive_many, $id }
+            [ $($($extra)*)? ]
+            [
+                #[init]
+                fn init() {
+                    // :NOTE: bridged DNAs may not yet be available; capabilities are otherwise requested upon first use
+                    $( let _ = $crate::rpc::register_remote_capabilities($capabilities); )?
+                    $crate::record_zome!(@hook [ $($init)? ] () { Ok(()) })
+                }
+
+                #[validate_agent]
+                pub fn validate_agent(validation_data: EntryValidationData::<AgentId>) {
+                    $crate::record_zome!(@hook [ $($validate_agent)? ] (validation_data) { Ok(()) })
+                }
+
+                #[entry_def]
+                fn [<$record _entry_def>]() -> ValidatingEntryType {
+                    $crate::record_zome!(@hook [ $($entry_def)? ] () { entry_def() })
+                }
+
+                #[entry_def]
+                fn [<$record _base_entry_def>]() -> ValidatingEntryType {
+                    $crate::record_zome!(@hook [ $($base_entry_def)? ] () { base_entry_def() })
+                }
+            ]
+            [ $($api)* ]
+        }
+    };
+
+    // call an overriding hook function if one was given, otherwise evaluate the default
+
+    (@hook [ $hook:path ] ( $($args:tt)* ) { $($default:tt)* }) => {
+        $hook($($args)*)
+    };
+    (@hook [ ] ( $($args:tt)* ) { $($default:tt)* }) => {
+        $($default)*
+    };
+
+    // append standard API methods one at a time, so that the `#[zome]` macro receives a fully expanded module
+
+    (@build $cfg:tt [ $($extra:tt)* ] [ $($done:tt)* ] [ $(,)? ]) => {
+        $crate::record_zome!(@finish $cfg [ $($done)* $($extra)* ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ create $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<create_ $record>]($record: CreateRequest) -> ZomeApiResult<ResponseData> {
+                [<receive_create_ $receive>]($record)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ get $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<get_ $record>](address: $id) -> ZomeApiResult<ResponseData> {
+                $crate::read_cache::with_read_cache(|| [<receive_get_ $receive>](address))
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ get_history $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<get_ $record _history>](address: $id) -> ZomeApiResult<Vec<RevisionResponse>> {
+                [<receive_get_ $receive _history>](address)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ update $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<update_ $record>]($record: UpdateRequest) -> ZomeApiResult<ResponseData> {
+                [<receive_update_ $receive>]($record)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ delete -> $ret:ty $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<delete_ $record>](address: $id) -> ZomeApiResult<$ret> {
+                [<receive_delete_ $receive>](address)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build $cfg:tt $extra:tt $done:tt [ delete $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build $cfg $extra $done [ delete -> bool $(, $($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ restore $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<restore_ $record>](address: $id) -> ZomeApiResult<ResponseData> {
+                [<receive_restore_ $receive>](address)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ query $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<query_ $records>](params: QueryParams) -> ZomeApiResult<QueryResponse> {
+                $crate::read_cache::with_read_cache(|| [<receive_query_ $receive_many>](params))
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ scope $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<get_ $records _in_scope>](scope: String) -> ZomeApiResult<QueryResponse> {
+                $crate::read_cache::with_read_cache(|| [<receive_get_ $receive_many _in_scope>](scope))
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ aggregate $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn [<count_ $records>](params: QueryParams) -> ZomeApiResult<CountResponse> {
+                $crate::read_cache::with_read_cache(|| [<receive_count_ $receive_many>](params))
+            }
+
+            #[zome_fn("hc_public")]
+            fn [<aggregate_ $records>](params: QueryParams) -> ZomeApiResult<AggregateResponse> {
+                $crate::read_cache::with_read_cache(|| [<receive_aggregate_ $receive_many>](params))
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ check_index_integrity $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn check_index_integrity(repair: bool) -> ZomeApiResult<IntegrityReport> {
+                $crate::rpc::require_local_agent(stringify!($receive), "check_index_integrity")?;
+                [<receive_check_ $receive _indexes>](repair)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ migrate_entries $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[zome_fn("hc_public")]
+            fn migrate_entries() -> ZomeApiResult<$crate::schema::MigrationReport> {
+                $crate::rpc::require_local_agent(stringify!($receive), "migrate_entries")?;
+                [<receive_migrate_ $receive _entries>]()
+            }
+        ] [ $($($rest)*)? ]);
+    };
+
+    (@build { $zome:ident, $record:ident, $records:ident, $receive:ident, $receive_many:ident, $id:ty } $extra:tt [ $($done:tt)* ] [ remote_index_outbox $(, $($rest:tt)*)? ]) => {
+        $crate::record_zome!(@build { $zome, $record, $records, $receive, $receive_many, $id } $extra [ $($done)*
+            #[entry_def]
+            fn remote_index_outbox_entry_def() -> ValidatingEntryType {
+                $crate::outbox::outbox_entry_def()
+            }
+
+            #[zome_fn("hc_public")]
+            fn get_pending_remote_index_operations() -> ZomeApiResult<Vec<$crate::outbox::OutboxItem>> {
+                Ok($crate::outbox::read_pending_index_operations()?)
+            }
+
+            #[zome_fn("hc_public")]
+            fn retry_remote_index_sync() -> ZomeApiResult<$crate::outbox::OutboxReplayReport> {
+                $crate::rpc::require_local_agent(stringify!($receive), "retry_remote_index_sync")?;
+                Ok($crate::outbox::replay_pending_index_operations()?)
+            }
+        ] [ $($($rest)*)? ]);
+    };
+
+    // output the completed zome
+
+    (@finish { $zome:ident, $($cfg:tt)* } [ $($items:tt)* ]) => {
+        $crate::paste::item! {
+            #[$crate::zome]
+            mod $zome {
+                $($items)*
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    fn entry_def() -> &'static str {
+        "entry_def"
+    }
+
+    fn remote_entry_def() -> &'static str {
+        "remote_entry_def"
+    }
+
+    fn reject_agent(agent: &str) -> Result<(), String> {
+        Err(format!("{} rejected", agent))
+    }
+
+    #[test]
+    fn test_hooks_default_when_not_given() {
+        assert_eq!(crate::record_zome!(@hook [ ] () { entry_def() }), "entry_def");
+
+        let validated: Result<(), String> = crate::record_zome!(@hook [ ] ("alice") { Ok(()) });
+        assert_eq!(validated, Ok(()));
+    }
+
+    #[test]
+    fn test_hooks_replace_default() {
+        assert_eq!(crate::record_zome!(@hook [ remote_entry_def ] () { entry_def() }), "remote_entry_def");
+        assert_eq!(crate::record_zome!(@hook [ self::reject_agent ] ("alice") { Ok(()) }), Err("alice rejected".to_string()));
+
+        // defaults are not expanded when overridden, and so need not be in scope
+        assert_eq!(crate::record_zome!(@hook [ remote_entry_def ] () { base_entry_def() }), "remote_entry_def");
+    }
+}
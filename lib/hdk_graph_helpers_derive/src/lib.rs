@@ -0,0 +1,664 @@
+/**
+ * Derive macros for record data structures
+ *
+ * `#[derive(VfRecord)]` is applied to a struct listing all the fields of a **record**,
+ * and generates the following items alongside it in the deriving crate:
+ *
+ * - `Entry`: the DHT entry struct, containing all fields other than `link` fields
+ * - `Response`: the output record, with an `id` and all fields including `link` fields
+ * - `ResponseData`: the `Response` wrapped in a named attribute
+ * - `CreateRequest` & `UpdateRequest`: input structs for create & update operations
+ * - `From<CreateRequest> for Entry` and `Updateable<UpdateRequest> for Entry`
+ * - `RevisionedUpdate for UpdateRequest`
+ * - `construct_response()`, for assembling a `ResponseData` from an `Entry` and any `link` fields
+ *
+ * The container must be annotated with `#[vf_record(entry_type = "...")]`, giving a path to the
+ * constant naming the record's DHT entry type. This is used to determine the `revision_id`
+ * returned in `Response`. It may also be annotated with `#[vf_record(id = "...", response_field = "...")]`
+ * to override the ID type (default `<Name>Address`) and the name of the attribute in
+ * `ResponseData` (default the struct name in `snake_case`). `Entry` is a versioned entry
+ * struct; any upgrades from earlier schema versions are given by a path to a constant
+ * `&[SchemaUpgrade]` with `#[vf_record(schema_upgrades = "...")]`. @see `schema`
+ * `#[vf_record(derive_default)]` additionally derives `Default` for `Entry` & `CreateRequest`.
+ *
+ * Fields of type `Option<T>` are optional. They may be omitted or set to `null` on creation,
+ * left untouched by omitting them from an update or erased by setting them to `null`.
+ * All other fields must be provided on creation and cannot be erased; updates setting them to
+ * `null` fail validation. Fields may be
+ * annotated with `#[vf_record(...)]` to modify this behaviour:
+ *
+ * - `required`: an `Option<T>` field must be provided on creation, and cannot be erased
+ * - `immutable`: the field is omitted from `UpdateRequest` and can never be changed
+ * - `default` / `default = "path::to::fn"`: the Serde default for the `CreateRequest` field.
+ *   For optional fields, the function must return a `MaybeUndefined<T>`.
+ * - `link`: the field is managed by indexes rather than stored in the entry. It appears only
+ *   in `Response`, and must be passed to `construct_response` in declaration order.
+ *
+ * The deriving crate must depend on `serde`, `serde_derive` (via `#[macro_use]`), `holochain_json_derive`,
+ * `hdk_graph_helpers` and `vf_core`, and have `JsonString` and `JsonError` in scope for `DefaultJson`.
+ *
+ * @see     ../../hdk_graph_helpers/README.md
+ * @package HDK Graph Helpers
+ * @since   2020-03-11
+ */
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{ TokenStream as TokenStream2, Span };
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, Ident,
+    Lit, LitStr, Meta, NestedMeta, PathArguments, Type,
+};
+
+#[proc_macro_derive(VfRecord, attributes(vf_record))]
+pub fn derive_vf_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_record(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+//--------------------------------[ PARSING ]-----------------------------------
+
+struct RecordOptions {
+    id: Type,
+    response_field: Ident,
+    entry_type: syn::Path,
+    schema_upgrades: Option<syn::Path>,
+    derive_default: bool,
+}
+
+enum FieldDefault {
+    Trait,
+    Function(LitStr),
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    immutable: bool,
+    link: bool,
+    required: bool,
+    default: Option<FieldDefault>,
+}
+
+struct RecordField {
+    ident: Ident,
+    ty: Type,
+    // wrapped type, for fields of type `Option<T>`
+    optional: Option<Type>,
+    docs: Vec<Attribute>,
+    options: FieldOptions,
+}
+
+/// Read the list of nested `#[vf_record(...)]` attribute values from `attrs`
+///
+fn vf_record_attrs(attrs: &[Attribute]) -> Result<Vec<NestedMeta>, Error> {
+    let mut items = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("vf_record")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => items.extend(list.nested),
+            other => return Err(Error::new_spanned(other, "expected #[vf_record(...)]")),
+        }
+    }
+    Ok(items)
+}
+
+fn parse_record_options(input: &DeriveInput) -> Result<RecordOptions, Error> {
+    let name = input.ident.to_string();
+    let mut id: Type = syn::parse_str(&format!("{}Address", name))?;
+    let mut response_field = Ident::new(&to_snake_case(&name), Span::call_site());
+    let mut entry_type = None;
+    let mut schema_upgrades = None;
+    let mut derive_default = false;
+
+    for item in vf_record_attrs(&input.attrs)? {
+        match item {
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("id") => {
+                id = match &nv.lit {
+                    Lit::Str(s) => s.parse()?,
+                    lit => return Err(Error::new_spanned(lit, "expected an ID type name")),
+                };
+            },
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("response_field") => {
+                response_field = match &nv.lit {
+                    Lit::Str(s) => s.parse()?,
+                    lit => return Err(Error::new_spanned(lit, "expected a field name")),
+                };
+            },
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("entry_type") => {
+                entry_type = match &nv.lit {
+                    Lit::Str(s) => Some(s.parse()?),
+                    lit => return Err(Error::new_spanned(lit, "expected a path to an entry type constant")),
+                };
+            },
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("schema_upgrades") => {
+                schema_upgrades = match &nv.lit {
+                    Lit::Str(s) => Some(s.parse()?),
+                    lit => return Err(Error::new_spanned(lit, "expected a path to a list of schema upgrades")),
+                };
+            },
+            NestedMeta::Meta(Meta::Path(ref p)) if p.is_ident("derive_default") => derive_default = true,
+            other => return Err(Error::new_spanned(other, "unknown vf_record attribute; expected `id`, `response_field`, `entry_type`, `schema_upgrades` or `derive_default`")),
+        }
+    }
+
+    let entry_type = entry_type.ok_or_else(|| {
+        Error::new_spanned(&input.ident, "missing #[vf_record(entry_type = \"...\")] attribute")
+    })?;
+
+    Ok(RecordOptions { id, response_field, entry_type, schema_upgrades, derive_default })
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> Result<FieldOptions, Error> {
+    let mut options = FieldOptions::default();
+
+    for item in vf_record_attrs(attrs)? {
+        match item {
+            NestedMeta::Meta(Meta::Path(ref p)) if p.is_ident("immutable") => options.immutable = true,
+            NestedMeta::Meta(Meta::Path(ref p)) if p.is_ident("link") => options.link = true,
+            NestedMeta::Meta(Meta::Path(ref p)) if p.is_ident("required") => options.required = true,
+            NestedMeta::Meta(Meta::Path(ref p)) if p.is_ident("default") => options.default = Some(FieldDefault::Trait),
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("default") => {
+                options.default = match &nv.lit {
+                    Lit::Str(s) => Some(FieldDefault::Function(s.clone())),
+                    lit => return Err(Error::new_spanned(lit, "expected a function path")),
+                };
+            },
+            other => return Err(Error::new_spanned(other, "unknown vf_record attribute; expected `immutable`, `link`, `required` or `default`")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_fields(input: &DeriveInput) -> Result<Vec<RecordField>, Error> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => &named.named,
+            _ => return Err(Error::new_spanned(&input.ident, "VfRecord requires a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "VfRecord can only be derived for structs")),
+    };
+
+    fields.iter().map(|field| {
+        let options = parse_field_options(&field.attrs)?;
+        let ident = field.ident.clone().unwrap();
+
+        if ident == "id" || ident == "revision_id" {
+            return Err(Error::new_spanned(&ident, "`id` and `revision_id` are managed by VfRecord and cannot be declared"));
+        }
+        if options.link && (options.immutable || options.required || options.default.is_some()) {
+            return Err(Error::new_spanned(&ident, "`link` fields cannot be combined with other vf_record attributes"));
+        }
+        if options.required && options.default.is_some() {
+            return Err(Error::new_spanned(&ident, "`required` fields cannot have a `default`"));
+        }
+
+        Ok(RecordField {
+            ident,
+            ty: field.ty.clone(),
+            optional: option_inner_type(&field.ty),
+            docs: field.attrs.iter().filter(|a| a.path.is_ident("doc")).cloned().collect(),
+            options,
+        })
+    }).collect()
+}
+
+/// Determine the type `T` wrapped by a field of type `Option<T>`
+///
+fn option_inner_type(ty: &Type) -> Option<Type> {
+    let path = match ty {
+        Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" { return None; }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 { result.push('_'); }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+//------------------------------[ GENERATION ]----------------------------------
+
+fn serde_default(options: &FieldOptions) -> TokenStream2 {
+    match &options.default {
+        Some(FieldDefault::Trait) => quote! { #[serde(default)] },
+        Some(FieldDefault::Function(path)) => quote! { #[serde(default = #path)] },
+        None => quote! {},
+    }
+}
+
+fn expand_record(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let RecordOptions { id, response_field, entry_type, schema_upgrades, derive_default } = parse_record_options(input)?;
+    let fields = parse_fields(input)?;
+
+    let (links, entry_fields): (Vec<&RecordField>, Vec<&RecordField>) = fields.iter().partition(|f| f.options.link);
+
+    let default_derive = match derive_default {
+        true => quote! { , Default },
+        false => quote! {},
+    };
+
+    // Entry
+
+    let entry_defs = entry_fields.iter().map(|RecordField { ident, ty, .. }| quote! {
+        pub #ident: #ty,
+    });
+    let entry_versioning = match schema_upgrades {
+        Some(upgrades) => quote! { ::hdk_graph_helpers::versioned_entry!(Entry, #upgrades); },
+        None => quote! { ::hdk_graph_helpers::versioned_entry!(Entry); },
+    };
+
+    // Response
+
+    let response_defs = fields.iter().map(|RecordField { ident, ty, optional, docs, options }| {
+        if options.link {
+            quote! {
+                #(#docs)*
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub #ident: Option<#ty>,
+            }
+        } else if optional.is_some() {
+            quote! {
+                #(#docs)*
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub #ident: #ty,
+            }
+        } else {
+            quote! {
+                #(#docs)*
+                pub #ident: #ty,
+            }
+        }
+    });
+
+    let response_assignments = fields.iter().map(|RecordField { ident, options, .. }| {
+        if options.link {
+            quote! { #ident: #ident.map(::std::borrow::Cow::into_owned), }
+        } else {
+            quote! { #ident: e.#ident.to_owned(), }
+        }
+    });
+
+    let link_idents: Vec<&Ident> = links.iter().map(|f| &f.ident).collect();
+    let link_types: Vec<&Type> = links.iter().map(|f| &f.ty).collect();
+    let link_params = match links.is_empty() {
+        true => quote! {},
+        false => quote! {
+            (#(#link_idents,)*): (#(Option<::std::borrow::Cow<'a, #link_types>>,)*),
+        },
+    };
+
+    // CreateRequest
+
+    let create_defs = entry_fields.iter().map(|RecordField { ident, ty, optional, docs, options }| {
+        let default = serde_default(options);
+        match (optional, options.required) {
+            (Some(inner), true) => quote! {
+                #(#docs)*
+                pub #ident: #inner,
+            },
+            (Some(inner), false) => {
+                let default = if options.default.is_none() { quote! { #[serde(default)] } } else { default };
+                quote! {
+                    #(#docs)*
+                    #default
+                    pub #ident: ::hdk_graph_helpers::MaybeUndefined<#inner>,
+                }
+            },
+            (None, _) => quote! {
+                #(#docs)*
+                #default
+                pub #ident: #ty,
+            },
+        }
+    });
+
+    let create_assignments = entry_fields.iter().map(|RecordField { ident, optional, options, .. }| {
+        match (optional, options.required) {
+            (Some(_), true) => quote! { #ident: Some(e.#ident), },
+            (Some(_), false) => quote! { #ident: e.#ident.into(), },
+            (None, _) => quote! { #ident: e.#ident, },
+        }
+    });
+
+    // UpdateRequest
+
+    let update_defs = entry_fields.iter()
+        .filter(|f| !f.options.immutable)
+        .map(|RecordField { ident, ty, optional, docs, .. }| {
+            let inner = optional.as_ref().unwrap_or(ty);
+            quote! {
+                #(#docs)*
+                #[serde(default)]
+                pub #ident: ::hdk_graph_helpers::MaybeUndefined<#inner>,
+            }
+        });
+
+    let update_assignments = entry_fields.iter().map(|RecordField { ident, optional, options, .. }| {
+        if options.immutable {
+            return quote! { #ident: self.#ident.to_owned(), };
+        }
+        match (optional, options.required) {
+            // optional fields are erased by `null`
+            (Some(_), false) => quote! {
+                #ident: match &e.#ident {
+                    ::hdk_graph_helpers::MaybeUndefined::Some(val) => Some(val.to_owned()),
+                    ::hdk_graph_helpers::MaybeUndefined::None => None,
+                    ::hdk_graph_helpers::MaybeUndefined::Undefined => self.#ident.to_owned(),
+                },
+            },
+            // required fields retain their value unless a new one is given
+            (Some(_), true) => quote! {
+                #ident: match &e.#ident {
+                    ::hdk_graph_helpers::MaybeUndefined::Some(val) => Some(val.to_owned()),
+                    _ => self.#ident.to_owned(),
+                },
+            },
+            (None, _) => quote! {
+                #ident: match &e.#ident {
+                    ::hdk_graph_helpers::MaybeUndefined::Some(val) => val.to_owned(),
+                    _ => self.#ident.to_owned(),
+                },
+            },
+        }
+    });
+
+    // required & mandatory fields cannot be erased, so an update setting them to `null` is rejected
+    let update_checks = entry_fields.iter()
+        .filter(|f| !f.options.immutable && (f.optional.is_none() || f.options.required))
+        .map(|RecordField { ident, .. }| {
+            let message = format!("{} cannot be erased", ident);
+            quote! {
+                if let ::hdk_graph_helpers::MaybeUndefined::None = e.#ident {
+                    return Err(#message.to_string());
+                }
+            }
+        });
+
+    Ok(quote! {
+        /// Storage struct for the DHT entry data of this record type
+        ///
+        #[derive(Serialize, Deserialize, Debug, Clone #default_derive)]
+        pub struct Entry {
+            #(#entry_defs)*
+        }
+
+        #entry_versioning
+
+        /// I/O struct to describe the complete record, including all managed link fields
+        ///
+        #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Response {
+            pub id: #id,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub revision_id: Option<::vf_core::type_aliases::Address>,
+            #(#response_defs)*
+        }
+
+        /// I/O struct to describe what is returned outside the gateway
+        ///
+        #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct ResponseData {
+            pub #response_field: Response,
+        }
+
+        /// I/O struct to describe the complete input record
+        ///
+        #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone #default_derive)]
+        #[serde(rename_all = "camelCase")]
+        pub struct CreateRequest {
+            #(#create_defs)*
+        }
+
+        /// I/O struct to describe an update to some subset of the record's fields
+        ///
+        #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct UpdateRequest {
+            pub id: #id,
+            #[serde(default)]
+            pub revision_id: Option<::vf_core::type_aliases::Address>,
+            #(#update_defs)*
+        }
+
+        impl<'a> UpdateRequest {
+            pub fn get_id(&'a self) -> &#id {
+                &self.id
+            }
+        }
+
+        impl ::hdk_graph_helpers::record_interface::RevisionedUpdate for UpdateRequest {
+            fn get_base_revision(&self) -> Option<::vf_core::type_aliases::Address> {
+                self.revision_id.to_owned()
+            }
+        }
+
+        /// Pick relevant fields out of I/O record into underlying DHT entry
+        impl From<CreateRequest> for Entry {
+            fn from(e: CreateRequest) -> Entry {
+                Entry {
+                    #(#create_assignments)*
+                }
+            }
+        }
+
+        /// Handles update operations by merging any newly provided fields
+        impl ::hdk_graph_helpers::record_interface::Updateable<UpdateRequest> for Entry {
+            fn update_with(&self, e: &UpdateRequest) -> Entry {
+                Entry {
+                    #(#update_assignments)*
+                }
+            }
+
+            fn validate_update(&self, e: &UpdateRequest) -> Result<(), String> {
+                #(#update_checks)*
+                Ok(())
+            }
+        }
+
+        /// Create response from input DHT primitives
+        pub fn construct_response<'a>(
+            address: &#id, e: &Entry,
+            #link_params
+        ) -> ResponseData {
+            ResponseData {
+                #response_field: Response {
+                    id: address.to_owned(),
+                    revision_id: ::hdk_graph_helpers::entries::get_entry_revision(#entry_type, e),
+                    #(#response_assignments)*
+                },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ File, Item, ItemFn, ItemImpl, ItemStruct };
+
+    fn expand(source: &str) -> Result<File, Error> {
+        let input: DeriveInput = syn::parse_str(source).unwrap();
+        expand_record(&input).map(|tokens| syn::parse2(tokens).unwrap())
+    }
+
+    fn find_struct<'a>(file: &'a File, name: &str) -> &'a ItemStruct {
+        file.items.iter()
+            .find_map(|item| match item {
+                Item::Struct(s) if s.ident == name => Some(s),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn find_impl<'a>(file: &'a File, trait_name: &str) -> &'a ItemImpl {
+        file.items.iter()
+            .find_map(|item| match item {
+                Item::Impl(i) if i.trait_.iter().any(|(_, path, _)| path.segments.last().unwrap().ident == trait_name) => Some(i),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn field_names(s: &ItemStruct) -> Vec<String> {
+        s.fields.iter().map(|f| f.ident.as_ref().unwrap().to_string()).collect()
+    }
+
+    fn tokens_of<T: quote::ToTokens>(item: &T) -> String {
+        quote!(#item).to_string()
+    }
+
+    const RECORD: &str = r#"
+        #[vf_record(entry_type = "consts::THING_ENTRY_TYPE")]
+        pub struct Thing {
+            pub name: String,
+            pub note: Option<String>,
+            #[vf_record(required)]
+            pub owner: Option<String>,
+            #[vf_record(immutable)]
+            pub created_by: String,
+            #[vf_record(link)]
+            pub parts: Vec<PartAddress>,
+        }
+    "#;
+
+    #[test]
+    fn test_link_fields_excluded_from_entry_and_requests() {
+        let file = expand(RECORD).unwrap();
+
+        assert_eq!(field_names(find_struct(&file, "Entry")), vec!["name", "note", "owner", "created_by"]);
+        assert_eq!(field_names(find_struct(&file, "CreateRequest")), vec!["name", "note", "owner", "created_by"]);
+        assert_eq!(field_names(find_struct(&file, "Response")), vec!["id", "revision_id", "name", "note", "owner", "created_by", "parts"]);
+    }
+
+    #[test]
+    fn test_immutable_fields_excluded_from_updates() {
+        let file = expand(RECORD).unwrap();
+
+        assert_eq!(field_names(find_struct(&file, "UpdateRequest")), vec!["id", "revision_id", "name", "note", "owner"]);
+        let update = tokens_of(find_impl(&file, "Updateable"));
+        let retained = quote! { created_by: self.created_by.to_owned(), }.to_string();
+        assert!(update.contains(&retained), "immutable field retained: {}", update);
+    }
+
+    #[test]
+    fn test_update_erases_only_optional_fields() {
+        let file = expand(RECORD).unwrap();
+        let update = tokens_of(find_impl(&file, "Updateable"));
+
+        // optional fields are erased by `null`
+        let erasable = quote! {
+            note: match &e.note {
+                ::hdk_graph_helpers::MaybeUndefined::Some(val) => Some(val.to_owned()),
+                ::hdk_graph_helpers::MaybeUndefined::None => None,
+                ::hdk_graph_helpers::MaybeUndefined::Undefined => self.note.to_owned(),
+            },
+        }.to_string();
+        assert!(update.contains(&erasable), "{}", update);
+
+        // required & mandatory fields retain their value unless a new one is given,
+        // and updates erasing them are rejected
+        let required = quote! {
+            owner: match &e.owner {
+                ::hdk_graph_helpers::MaybeUndefined::Some(val) => Some(val.to_owned()),
+                _ => self.owner.to_owned(),
+            },
+        }.to_string();
+        assert!(update.contains(&required), "{}", update);
+        let mandatory = quote! {
+            name: match &e.name {
+                ::hdk_graph_helpers::MaybeUndefined::Some(val) => val.to_owned(),
+                _ => self.name.to_owned(),
+            },
+        }.to_string();
+        assert!(update.contains(&mandatory), "{}", update);
+
+        for field in &["name", "owner"] {
+            let field = Ident::new(field, Span::call_site());
+            let message = format!("{} cannot be erased", field);
+            let check = quote! {
+                if let ::hdk_graph_helpers::MaybeUndefined::None = e.#field {
+                    return Err(#message.to_string());
+                }
+            }.to_string();
+            assert!(update.contains(&check), "{}", update);
+        }
+        assert!(!update.contains("MaybeUndefined :: None = e . note"), "{}", update);
+        assert!(!update.contains("MaybeUndefined :: None = e . created_by"), "{}", update);
+    }
+
+    #[test]
+    fn test_link_fields_passed_to_construct_response() {
+        let file = expand(RECORD).unwrap();
+        let construct = file.items.iter()
+            .find_map(|item| match item {
+                Item::Fn(f) if f.sig.ident == "construct_response" => Some(f),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(construct.sig.inputs.len(), 3);
+        let assignment = quote! { parts: parts.map(::std::borrow::Cow::into_owned), }.to_string();
+        assert!(tokens_of::<ItemFn>(construct).contains(&assignment));
+    }
+
+    #[test]
+    fn test_default_derived_on_request() {
+        let plain = expand(RECORD).unwrap();
+        assert!(!tokens_of(find_struct(&plain, "CreateRequest")).contains("Default ,"));
+
+        let file = expand(r#"
+            #[vf_record(entry_type = "consts::THING_ENTRY_TYPE", derive_default)]
+            pub struct Thing {
+                pub name: String,
+            }
+        "#).unwrap();
+        assert!(tokens_of(find_struct(&file, "Entry")).contains("Clone , Default"));
+        assert!(tokens_of(find_struct(&file, "CreateRequest")).contains("Clone , Default"));
+    }
+
+    #[test]
+    fn test_default_id_and_response_field() {
+        let file = expand(RECORD).unwrap();
+
+        assert_eq!(tokens_of(&find_struct(&file, "Response").fields.iter().next().unwrap().ty), "ThingAddress");
+        assert_eq!(field_names(find_struct(&file, "ResponseData")), vec!["thing"]);
+    }
+
+    #[test]
+    fn test_invalid_definitions_rejected() {
+        assert!(expand("pub struct Thing { pub name: String }").is_err(), "entry_type is required");
+        assert!(expand(r#"
+            #[vf_record(entry_type = "consts::THING_ENTRY_TYPE")]
+            pub struct Thing { #[vf_record(link, required)] pub parts: Vec<PartAddress> }
+        "#).is_err(), "link fields cannot be required");
+        assert!(expand(r#"
+            #[vf_record(entry_type = "consts::THING_ENTRY_TYPE")]
+            pub struct Thing { pub id: String }
+        "#).is_err(), "id is reserved");
+    }
+}
@@ -120,3 +120,135 @@ pub fn base_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn classification_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE,
+        description: "Anchors for each classification URL of all Commitments stored in this zome, for finding them by classification.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                COMMITMENT_BASE_ENTRY_TYPE,
+                link_type: COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn scope_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: COMMITMENT_SCOPE_INDEX_ENTRY_TYPE,
+        description: "Anchors for each scope which Commitments stored in this zome are `in_scope_of`, for listing the contents of a scope.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                COMMITMENT_BASE_ENTRY_TYPE,
+                link_type: COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn time_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: COMMITMENT_TIME_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Commitments stored in this zome, ordered by their beginning, end or exact time.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                COMMITMENT_TIME_INDEX_ENTRY_TYPE,
+                link_type: COMMITMENT_TIME_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                COMMITMENT_BASE_ENTRY_TYPE,
+                link_type: COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn due_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: COMMITMENT_DUE_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Commitments stored in this zome, ordered by due date.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                COMMITMENT_DUE_INDEX_ENTRY_TYPE,
+                link_type: COMMITMENT_DUE_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                COMMITMENT_BASE_ENTRY_TYPE,
+                link_type: COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
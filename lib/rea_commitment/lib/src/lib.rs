@@ -7,17 +7,16 @@
  * @package Holo-REA
  */
 use std::borrow::Cow;
-use hdk::{
-    PUBLIC_TOKEN,
-    prelude::Address,
-    error::{ ZomeApiResult, ZomeApiError },
-};
+use hdk::error::ZomeApiResult;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     MaybeUndefined,
     records::{
         create_record,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
@@ -25,16 +24,27 @@ use hdk_graph_helpers::{
         get_linked_addresses_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_index,
+        count_index,
     },
     remote_indexes::{
         create_direct_remote_index,
         update_direct_remote_index,
         remove_direct_remote_index,
+        check_remote_index_results,
+    },
+    anchors::{
+        create_category_index, update_category_index, delete_category_index,
+        create_time_index, update_time_index, delete_time_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    schema::{ migrate_entries, MigrationReport },
+    rpc::RemoteCapability,
+    entries::{ build_records_response, get_entry_revision, RecordsResponse },
 };
 
+use vf_core::measurement::aggregate_by_unit;
 use vf_core::type_aliases::{
     AgentAddress,
     FulfillmentAddress,
@@ -53,6 +63,16 @@ use hc_zome_rea_process_storage_consts::{
 use hc_zome_rea_fulfillment_storage_consts::{FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG};
 use hc_zome_rea_satisfaction_storage_consts::{SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG};
 
+/// Zome API methods of other DNAs which the commitment zome calls, requested upon `init`
+///
+pub const COMMITMENT_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "process",
+        functions: &["index_committed_inputs", "index_committed_outputs", "get_process"],
+    },
+];
+
 pub fn receive_create_commitment(commitment: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_commitment(&commitment)
 }
@@ -61,6 +81,10 @@ pub fn receive_get_commitment(address: CommitmentAddress) -> ZomeApiResult<Respo
     handle_get_commitment(&address)
 }
 
+pub fn receive_get_commitment_history(address: CommitmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_commitment_history(&address)
+}
+
 pub fn receive_update_commitment(commitment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_commitment(&commitment)
 }
@@ -69,42 +93,111 @@ pub fn receive_delete_commitment(address: CommitmentAddress) -> ZomeApiResult<bo
     handle_delete_commitment(&address)
 }
 
-pub fn receive_query_commitments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_commitments(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_commitments(&params)
 }
 
+pub fn receive_count_commitments(params: QueryParams) -> ZomeApiResult<CountResponse> {
+    handle_count_commitments(&params)
+}
+
+pub fn receive_aggregate_commitments(params: QueryParams) -> ZomeApiResult<AggregateResponse> {
+    handle_aggregate_commitments(&params)
+}
+
+pub fn receive_get_commitments_in_scope(scope: String) -> ZomeApiResult<QueryResponse> {
+    handle_query_commitments(&QueryParams { in_scope_of: Some(scope), ..QueryParams::default() })
+}
+
+pub fn receive_check_commitment_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(COMMITMENT_BASE_ENTRY_TYPE, &[
+        IndexDefinition::remote(
+            COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
+            PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+            BRIDGED_OBSERVATION_DHT, "process", "get_process",
+        ),
+        IndexDefinition::remote(
+            COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
+            PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+            BRIDGED_OBSERVATION_DHT, "process", "get_process",
+        ),
+        IndexDefinition::local(
+            COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
+            FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG,
+            SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
+pub fn receive_migrate_commitment_entries() -> ZomeApiResult<MigrationReport> {
+    Ok(migrate_entries::<Entry, _>(COMMITMENT_ENTRY_TYPE)?)
+}
+
 fn handle_get_commitment(address: &CommitmentAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(&address)?;
     Ok(construct_response(&address, &entry, get_link_fields(&address)))
 }
 
+fn handle_get_commitment_history(address: &CommitmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, COMMITMENT_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (None, None, None))))
+        .collect())
+}
+
 fn handle_create_commitment(commitment: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (CommitmentAddress, Entry) = create_record(
         COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_ENTRY_TYPE,
         COMMITMENT_INITIAL_ENTRY_LINK_TYPE,
         commitment.to_owned()
     )?;
+    create_category_index(
+        &COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_CLASSIFICATION_INDEX_ID, COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &COMMITMENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_SCOPE_INDEX_ID, COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry_resp), base_address.as_ref(),
+    )?;
+    if let Some(time) = get_planned_time(&entry_resp) {
+        create_time_index(
+            &COMMITMENT_TIME_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_TIME_INDEX_ID,
+            COMMITMENT_TIME_INDEX_BUCKET_LINK_TYPE, COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            &time, base_address.as_ref(),
+        )?;
+    }
+    if let Some(time) = get_due_time(&entry_resp) {
+        create_time_index(
+            &COMMITMENT_DUE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_DUE_INDEX_ID,
+            COMMITMENT_DUE_INDEX_BUCKET_LINK_TYPE, COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE,
+            &time, base_address.as_ref(),
+        )?;
+    }
 
     // handle link fields
     if let CreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = commitment {
-        let _results = create_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
             base_address.as_ref(),
             vec![(input_of.as_ref()).clone()],
-        );
+        ))?;
     };
     if let CreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = commitment {
-        let _results = create_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
             base_address.as_ref(),
             vec![(output_of.as_ref()).clone()],
-        );
+        ))?;
     };
 
     // :TODO: pass results from link creation rather than re-reading
@@ -113,26 +206,45 @@ fn handle_create_commitment(commitment: &CreateRequest) -> ZomeApiResult<Respons
 
 fn handle_update_commitment(commitment: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = commitment.get_id();
+    let prev_entry: Entry = read_record_entry(address)?;
     let new_entry = update_record(COMMITMENT_ENTRY_TYPE, &address, commitment)?;
+    update_category_index(
+        &COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_CLASSIFICATION_INDEX_ID, COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&prev_entry), &get_classifications(&new_entry), address.as_ref(),
+    )?;
+    update_category_index(
+        &COMMITMENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_SCOPE_INDEX_ID, COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&prev_entry), &get_scopes(&new_entry), address.as_ref(),
+    )?;
+    update_time_index(
+        &COMMITMENT_TIME_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_TIME_INDEX_ID,
+        COMMITMENT_TIME_INDEX_BUCKET_LINK_TYPE, COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE,
+        get_planned_time(&prev_entry).as_ref().map(String::as_str), get_planned_time(&new_entry).as_ref().map(String::as_str), address.as_ref(),
+    )?;
+    update_time_index(
+        &COMMITMENT_DUE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_DUE_INDEX_ID,
+        COMMITMENT_DUE_INDEX_BUCKET_LINK_TYPE, COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE,
+        get_due_time(&prev_entry).as_ref().map(String::as_str), get_due_time(&new_entry).as_ref().map(String::as_str), address.as_ref(),
+    )?;
 
     // handle link fields
     if MaybeUndefined::Undefined != commitment.input_of {
-        let _results = update_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(update_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
             address, &commitment.input_of,
-        );
+        ))?;
     }
     if MaybeUndefined::Undefined != commitment.output_of {
-        let _results = update_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(update_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
             address, &commitment.output_of,
-        );
+        ))?;
     }
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
@@ -144,9 +256,29 @@ fn handle_delete_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool>
     let entry: Entry = read_record_entry(&address)?;
 
     // handle link fields
+    let _results = delete_category_index(
+        &COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_CLASSIFICATION_INDEX_ID, COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&entry), address.as_ref(),
+    );
+    let _results = delete_category_index(
+        &COMMITMENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_SCOPE_INDEX_ID, COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry), address.as_ref(),
+    );
+    if let Some(time) = get_planned_time(&entry) {
+        let _results = delete_time_index(
+            &COMMITMENT_TIME_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_TIME_INDEX_ID, COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            &time, address.as_ref(),
+        );
+    }
+    if let Some(time) = get_due_time(&entry) {
+        let _results = delete_time_index(
+            &COMMITMENT_DUE_INDEX_ENTRY_TYPE.to_string(), COMMITMENT_DUE_INDEX_ID, COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE,
+            &time, address.as_ref(),
+        );
+    }
     if let Some(process_address) = entry.input_of {
         let _results = remove_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
@@ -155,7 +287,7 @@ fn handle_delete_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool>
     }
     if let Some(process_address) = entry.output_of {
         let _results = remove_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
             PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
@@ -164,66 +296,149 @@ fn handle_delete_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool>
     }
 
     // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
+}
+
+fn handle_query_commitments(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    Ok(build_records_response(read_matching_commitments(params)?, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
+}
+
+/// Commitments are only read if they must be filtered by `finished`; otherwise only the index is counted
+fn handle_count_commitments(params: &QueryParams) -> ZomeApiResult<CountResponse> {
+    let count = match params.finished {
+        None => count_index(&build_query(params), COMMITMENT_INITIAL_ENTRY_LINK_TYPE)?,
+        Some(_) => read_matching_commitment_entries(params)?.results.len(),
+    };
+    Ok(CountResponse { count })
+}
+
+/// Aggregates are computed from commitment entries alone, without loading their link fields
+fn handle_aggregate_commitments(params: &QueryParams) -> ZomeApiResult<AggregateResponse> {
+    let RecordsResponse { results: entries, errors } = read_matching_commitment_entries(params)?;
+
+    Ok(AggregateResponse {
+        count: entries.len(),
+        resource_quantity: aggregate_by_unit(entries.iter().filter_map(|entry| entry.resource_quantity.to_owned())),
+        effort_quantity: aggregate_by_unit(entries.iter().filter_map(|entry| entry.effort_quantity.to_owned())),
+        errors,
+    })
+}
+
+/// Load all commitments matching `params`. Commitments which could not be read are reported in place.
+fn read_matching_commitments(params: &QueryParams) -> GraphAPIResult<Vec<(CommitmentAddress, GraphAPIResult<Entry>)>> {
+    let entries: Vec<(CommitmentAddress, GraphAPIResult<Entry>)> = query_index(&build_query(params))?;
+
+    Ok(entries.into_iter()
+        .filter(|(_, entry)| match (params.finished, entry) {
+            (Some(finished), Ok(entry)) => entry.finished == finished,
+            _ => true,
+        })
+        .collect())
+}
+
+/// Load the entries of all commitments matching `params`, alongside errors for any which could not be read
+fn read_matching_commitment_entries(params: &QueryParams) -> ZomeApiResult<RecordsResponse<CommitmentAddress, Entry>> {
+    Ok(build_records_response(read_matching_commitments(params)?, |_, entry| entry.to_owned()))
 }
 
-fn handle_query_commitments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(CommitmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+/// Build an index query matching all commitments which satisfy the indexed filters in `params`
+fn build_query(params: &QueryParams) -> IndexQuery<'static> {
+    let mut filters = vec![];
 
-    // :TODO: implement proper AND search rather than exclusive operations
     match &params.fulfilled_by {
         Some(fulfilled_by) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 fulfilled_by, FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.satisfies {
         Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 satisfies, SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.input_of {
         Some(input_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 input_of, PROCESS_BASE_ENTRY_TYPE,
                 PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.output_of {
         Some(output_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 output_of, PROCESS_BASE_ENTRY_TYPE,
                 PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
-
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                            get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+    match &params.classified_as {
+        Some(classified_as) => {
+            filters.push(IndexQuery::category(
+                COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE, COMMITMENT_CLASSIFICATION_INDEX_ID, COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                &String::from(classified_as.to_owned()),
+            ));
+        },
+        _ => (),
+    };
+    match &params.in_scope_of {
+        Some(in_scope_of) => {
+            filters.push(IndexQuery::category(
+                COMMITMENT_SCOPE_INDEX_ENTRY_TYPE, COMMITMENT_SCOPE_INDEX_ID, COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                in_scope_of,
+            ));
+        },
+        _ => (),
+    };
+    if params.start_date.is_some() || params.end_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            COMMITMENT_TIME_INDEX_ENTRY_TYPE, COMMITMENT_TIME_INDEX_ID,
+            COMMITMENT_TIME_INDEX_BUCKET_LINK_TYPE, COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            params.start_date.to_owned(), params.end_date.to_owned(),
+        ));
+    }
+    if params.due_start_date.is_some() || params.due_end_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            COMMITMENT_DUE_INDEX_ENTRY_TYPE, COMMITMENT_DUE_INDEX_ID,
+            COMMITMENT_DUE_INDEX_BUCKET_LINK_TYPE, COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE,
+            params.due_start_date.to_owned(), params.due_end_date.to_owned(),
+        ));
     }
+
+    IndexQuery::And(filters)
+}
+
+/// Classification URLs of the resources a commitment refers to, which are indexed for querying
+fn get_classifications(e: &Entry) -> Vec<String> {
+    e.resource_classified_as.to_owned().unwrap_or_default().into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Scopes which a commitment is indexed under for querying
+fn get_scopes(e: &Entry) -> Vec<String> {
+    e.in_scope_of.to_owned().unwrap_or_default()
+}
+
+/// Time which a commitment is indexed under for querying by date range,
+/// being the first of `has_point_in_time`, `has_beginning` and `has_end` which is set
+fn get_planned_time(e: &Entry) -> Option<String> {
+    e.has_point_in_time.as_ref()
+        .or(e.has_beginning.as_ref())
+        .or(e.has_end.as_ref())
+        .map(|time| time.as_ref().to_string())
+}
+
+/// Due date which a commitment is indexed under for querying
+fn get_due_time(e: &Entry) -> Option<String> {
+    e.due.as_ref().map(|time| time.as_ref().to_string())
 }
 
 /// Create response from input DHT primitives
@@ -241,6 +456,7 @@ pub fn construct_response<'a>(
     ResponseData {
         commitment: Response {
             id: address.to_owned(),
+            revision_id: get_entry_revision(COMMITMENT_ENTRY_TYPE, e),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             input_of: e.input_of.to_owned(),
@@ -17,6 +17,9 @@ use holochain_json_derive::{ DefaultJson };
 use hdk_graph_helpers::{
     MaybeUndefined,
     maybe_undefined::default_false,
+    records::RecordRevision,
+    entries::RecordsResponse,
+    record_interface::RevisionedUpdate,
 };
 use vf_core::{
     measurement::QuantityValue,
@@ -33,6 +36,7 @@ use vf_core::{
         AgreementAddress,
         FulfillmentAddress,
         SatisfactionAddress,
+        Address,
     },
 };
 
@@ -47,6 +51,8 @@ pub use vf_core::type_aliases::{ CommitmentAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: CommitmentAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub action: ActionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -108,6 +114,20 @@ pub struct ResponseData {
     pub commitment: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<CommitmentAddress, ResponseData>;
+
+/// I/O struct to describe the number of commitments matching a query, as returned by `count_*` endpoints
+pub use hdk_graph_helpers::entries::CountResponse;
+
+/// I/O struct to describe totals over all commitments matching a query, as returned by `aggregate_*` endpoints
+pub type AggregateResponse = hdk_graph_helpers::entries::AggregateResponse<CommitmentAddress>;
+
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -171,6 +191,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: CommitmentAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     pub action: MaybeUndefined<ActionId>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
@@ -224,13 +246,35 @@ impl<'a> UpdateRequest {
     // :TODO: accessors for other field data
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
     pub input_of: Option<ProcessAddress>,
     pub output_of: Option<ProcessAddress>,
     pub fulfilled_by: Option<FulfillmentAddress>,
     pub satisfies: Option<SatisfactionAddress>,
+    /// Classification URL which commitments must have in their `resource_classified_as`
+    pub classified_as: Option<ExternalURL>,
+    /// Scope which commitments must be `in_scope_of`
+    pub in_scope_of: Option<String>,
+    /// Only return commitments occurring at or after this time. Commitments are timed by their
+    /// `has_point_in_time`, `has_beginning` or `has_end`, whichever is first set.
+    pub start_date: Option<String>,
+    /// Only return commitments occurring at or before this time. A date without a time includes the entire day.
+    pub end_date: Option<String>,
+    /// Only return commitments due at or after this time
+    pub due_start_date: Option<String>,
+    /// Only return commitments due at or before this time. A date without a time includes the entire day.
+    pub due_end_date: Option<String>,
+    /// Only return commitments which are (or are not) `finished`. Matched against each commitment,
+    /// rather than via an index.
+    pub finished: Option<bool>,
 }
@@ -11,9 +11,6 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-use holochain_json_api::{ json::JsonString, error::JsonError };
-use holochain_json_derive::{ DefaultJson };
-
 use hdk_graph_helpers::{
     MaybeUndefined,
     record_interface::Updateable,
@@ -41,7 +38,7 @@ use hc_zome_rea_commitment_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
     pub action: ActionId,
     pub provider: AgentAddress,
@@ -67,6 +64,8 @@ pub struct Entry {
     pub note: Option<String>,
 }
 
+hdk_graph_helpers::versioned_entry!(Entry);
+
 impl Entry {
     pub fn validate_action(&self) -> Result<(), String> {
         validate_flow_action(self.action.to_owned(), self.input_of.to_owned(), self.output_of.to_owned())
@@ -17,4 +17,22 @@ pub const COMMITMENT_INPUT_OF_LINK_TAG: &str = "input_of";
 pub const COMMITMENT_OUTPUT_OF_LINK_TYPE: &str = "vf_commitment_output_of";
 pub const COMMITMENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 
+pub const COMMITMENT_CLASSIFICATION_INDEX_ENTRY_TYPE: &str = "vf_commitments_classification_index";
+pub const COMMITMENT_CLASSIFICATION_INDEX_ID: &str = "all_vf_commitments";
+pub const COMMITMENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE: &str = "vf_commitment_classification_index";
+
+pub const COMMITMENT_SCOPE_INDEX_ENTRY_TYPE: &str = "vf_commitments_scope_index";
+pub const COMMITMENT_SCOPE_INDEX_ID: &str = "all_vf_commitments";
+pub const COMMITMENT_SCOPE_INDEX_ENTRY_LINK_TYPE: &str = "vf_commitment_scope_index";
+
+pub const COMMITMENT_TIME_INDEX_ENTRY_TYPE: &str = "vf_commitments_time_index";
+pub const COMMITMENT_TIME_INDEX_ID: &str = "all_vf_commitments";
+pub const COMMITMENT_TIME_INDEX_BUCKET_LINK_TYPE: &str = "vf_commitments_time_bucket";
+pub const COMMITMENT_TIME_INDEX_ENTRY_LINK_TYPE: &str = "vf_commitment_time_index";
+
+pub const COMMITMENT_DUE_INDEX_ENTRY_TYPE: &str = "vf_commitments_due_index";
+pub const COMMITMENT_DUE_INDEX_ID: &str = "all_vf_commitments";
+pub const COMMITMENT_DUE_INDEX_BUCKET_LINK_TYPE: &str = "vf_commitments_due_bucket";
+pub const COMMITMENT_DUE_INDEX_ENTRY_LINK_TYPE: &str = "vf_commitment_due_index";
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
@@ -126,10 +126,10 @@ pub fn base_entry_def() -> ValidatingEntryType {
     )
 }
 
-pub fn root_entry_def() -> ValidatingEntryType {
+pub fn time_index_entry_def() -> ValidatingEntryType {
     entry!(
-        name: EVENT_INDEX_ROOT_ENTRY_TYPE,
-        description: "Root anchor which connects to all Economic Events stored in this zome.",
+        name: EVENT_TIME_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Economic Events stored in this zome, ordered by time.",
         sharing: Sharing::Public,
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
@@ -138,14 +138,78 @@ pub fn root_entry_def() -> ValidatingEntryType {
             Ok(())
         },
         links: [
+            to!(
+                EVENT_TIME_INDEX_ENTRY_TYPE,
+                link_type: EVENT_TIME_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
             to!(
                 EVENT_BASE_ENTRY_TYPE,
-                link_type: EVENT_INDEX_ENTRY_LINK_TYPE,
+                link_type: EVENT_TIME_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
 
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn classification_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: EVENT_CLASSIFICATION_INDEX_ENTRY_TYPE,
+        description: "Anchors for each classification URL of all EconomicEvents stored in this zome, for finding them by classification.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                EVENT_BASE_ENTRY_TYPE,
+                link_type: EVENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
                 validation_package: || {
                     hdk::ValidationPackageDefinition::Entry
                 },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
 
+pub fn scope_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: EVENT_SCOPE_INDEX_ENTRY_TYPE,
+        description: "Anchors for each scope which EconomicEvents stored in this zome are `in_scope_of`, for listing the contents of a scope.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                EVENT_BASE_ENTRY_TYPE,
+                link_type: EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
                 validation: | _validation_data: hdk::LinkValidationData| {
                     Ok(())
                 }
@@ -7,37 +7,49 @@
  * @package Holo-REA
  */
 use std::borrow::Cow;
-use hdk::error::{ ZomeApiResult, ZomeApiError };
+use hdk::error::ZomeApiResult;
 
 use hdk_graph_helpers::{
+    GraphAPIResult, GraphHelperError,
     MaybeUndefined,
     records::{
-        create_record,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
+        UnitOfWork,
+        with_unit_of_work,
     },
     links::{
-        link_entries,
+        LinkAttributeFilter,
         get_linked_addresses_as_type,
+        link_entries_with_attributes_if_absent,
     },
     anchors::{
-        create_anchor_index,
-        read_anchored_record_entries,
+        TimeIndexPage,
+        read_time_indexed_record_entries_where,
+        read_category_index_addresses,
+        delete_time_index,
+        update_category_index,
+        delete_category_index,
     },
     local_indexes::{
-        create_direct_index,
         delete_direct_index,
-        query_direct_index_with_foreign_key,
-    },
-    remote_indexes::{
-        create_direct_remote_index_destination,
+        IndexQuery,
+        query_index,
+        count_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    schema::{ migrate_entries, reindex_records, MigrationReport },
+    entries::{ build_records_response, get_entry_revision, RecordsResponse },
 };
 
+use vf_core::measurement::aggregate_by_unit;
 use vf_core::type_aliases::{
     EventAddress,
     ResourceAddress,
+    ProcessAddress,
     ActionId,
     FulfillmentAddress,
     SatisfactionAddress,
@@ -58,10 +70,16 @@ use hc_zome_rea_economic_event_storage::{
 use hc_zome_rea_economic_event_rpc::{
     ResourceInventoryType,
     QueryParams,
+    ListParams,
+    ResponsePage,
     CreateRequest as EconomicEventCreateRequest,
     UpdateRequest as EconomicEventUpdateRequest,
     Response,
     ResponseData,
+    QueryResponse,
+    CountResponse,
+    AggregateResponse,
+    IntegrityReport,
 };
 
 use hc_zome_rea_economic_resource_storage_consts::*;
@@ -76,60 +94,163 @@ use hc_zome_rea_economic_resource_lib::{
     resource_creation,
     construct_response_record as construct_resource_response,
     get_link_fields as get_resource_link_fields,
+    get_classifications as get_resource_classifications,
+    get_units as get_resource_units,
 };
 
 use hc_zome_rea_process_storage_consts::*;
+use hc_zome_rea_process_storage::Entry as ProcessEntry;
 
 // API gateway entrypoints. All methods must accept parameters by value.
 
 pub fn receive_create_economic_event(event: EconomicEventCreateRequest, new_inventoried_resource: Option<EconomicResourceCreateRequest>) -> ZomeApiResult<ResponseData> {
+    // all writes are reverted if any step fails, so that failed validation of the event
+    // does not leave dangling or modified resources behind
+    Ok(with_unit_of_work(|unit| {
+        handle_create_economic_event_with_resources(unit, &event, new_inventoried_resource)
+    })?)
+}
+
+pub fn receive_get_economic_event(address: EventAddress) -> ZomeApiResult<ResponseData> {
+    handle_get_economic_event(&address)
+}
+
+pub fn receive_get_economic_event_history(address: EventAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_economic_event_history(&address)
+}
+
+pub fn receive_update_economic_event(event: EconomicEventUpdateRequest) -> ZomeApiResult<ResponseData> {
+    handle_update_economic_event(&event)
+}
+
+pub fn receive_delete_economic_event(address: EventAddress) -> ZomeApiResult<bool> {
+    handle_delete_economic_event(&address)
+}
+
+pub fn receive_get_all_economic_events(params: ListParams) -> ZomeApiResult<ResponsePage> {
+    handle_get_all_economic_events(&params)
+}
+
+pub fn receive_query_events(params: QueryParams) -> ZomeApiResult<QueryResponse> {
+    handle_query_events(&params)
+}
+
+pub fn receive_count_events(params: QueryParams) -> ZomeApiResult<CountResponse> {
+    handle_count_events(&params)
+}
+
+pub fn receive_aggregate_events(params: QueryParams) -> ZomeApiResult<AggregateResponse> {
+    handle_aggregate_events(&params)
+}
+
+pub fn receive_get_events_in_scope(scope: String) -> ZomeApiResult<QueryResponse> {
+    handle_query_events(&QueryParams { in_scope_of: Some(scope), ..QueryParams::default() })
+}
+
+pub fn receive_check_economic_event_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(EVENT_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
+            PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
+            PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
+            FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
+            SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
+pub fn receive_migrate_economic_event_entries() -> ZomeApiResult<MigrationReport> {
+    let report = migrate_entries::<Entry, _>(EVENT_ENTRY_TYPE)?;
+    // events created before process links carried their `action` are linked again with it
+    Ok(reindex_records(EVENT_BASE_ENTRY_TYPE, EVENT_INITIAL_ENTRY_LINK_TYPE, report, |address: &EventAddress, entry: &Entry| {
+        for (process_address, link_type, link_tag) in get_process_event_links(entry) {
+            link_entries_with_attributes_if_absent(
+                process_address.as_ref(), address.as_ref(),
+                link_type, link_tag, &get_process_event_link_attributes(&entry.action),
+            )?;
+        }
+        Ok(())
+    })?)
+}
+
+// API logic handlers
+
+fn handle_create_economic_event_with_resources(
+    unit: &mut UnitOfWork,
+    event: &EconomicEventCreateRequest,
+    new_inventoried_resource: Option<EconomicResourceCreateRequest>,
+) -> GraphAPIResult<ResponseData> {
+    let event = &with_inherited_scope(event)?;
     let mut resources_affected: Vec<(ResourceAddress, EconomicResourceEntry)> = vec![];
     let mut resource_created: Option<(ResourceAddress, EconomicResourceEntry)> = None;
 
     // if the event observes a new resource, create that resource & return it in the response
     if let Some(economic_resource) = new_inventoried_resource {
-        let new_resource = handle_create_economic_resource(&economic_resource, &event)?;
+        let new_resource = handle_create_economic_resource(unit, &economic_resource, event)?;
         resource_created = Some(new_resource.clone());
         resources_affected.push(new_resource);
     }
 
     // if the event is a transfer-like event, run the receiver's update first
     if let MaybeUndefined::Some(receiver_inventory) = event.to_resource_inventoried_as.to_owned() {
-        resources_affected.push(handle_update_economic_resource(&receiver_inventory, ResourceInventoryType::ReceivingInventory, &event)?);
+        resources_affected.push(handle_update_economic_resource(unit, &receiver_inventory, ResourceInventoryType::ReceivingInventory, event)?);
     }
     // after receiver, run provider. This entry data will be returned in the response.
     if let MaybeUndefined::Some(provider_inventory) = event.resource_inventoried_as.to_owned() {
-        resources_affected.push(handle_update_economic_resource(&provider_inventory, ResourceInventoryType::ProvidingInventory, &event)?);
+        resources_affected.push(handle_update_economic_resource(unit, &provider_inventory, ResourceInventoryType::ProvidingInventory, event)?);
     }
 
     // now that the resource updates have succeeded, write the event
     // :TODO: rethinking this, it's probably the event that should be written first, and the resource
     // validation should eventually depend on an event already having been authored.
-    let (event_address, event_entry) = handle_create_economic_event(&event, match resource_created.clone() {
+    let (event_address, event_entry) = handle_create_economic_event(unit, event, match resource_created.clone() {
         Some(data) => Some(data.0.to_owned()),
         None => None,
     })?;
 
-    // :IMPORTANT: we don't create indexes until after the event has saved since the event storage may fail validation and
-    // we don't want dangling resources without events to be visible to callers.
-    // This is dirty and introduces some non-obvious complexity, but works around transactionality limitations for now.
+    // :NOTE: indexes are created after the event has saved. Should any of the above steps fail
+    // validation, the resource writes are reverted by the enclosing `UnitOfWork`.
 
     // Index the event for retrieval via `get_all` API endpoints
-    // :TODO: change to use DAG indexes for time ordering & pagination
-    create_anchor_index(&EVENT_INDEX_ROOT_ENTRY_TYPE.to_string(), EVENT_INDEX_ENTRY_LINK_TYPE, &EVENT_INDEX_ROOT_ENTRY_ID.to_string(), &event_address.as_ref())?;
-    // Index any new resource for retrieval via `get_all` API endpoints
+    let event_time = get_event_time(&event_entry)?;
+    unit.create_time_index(
+        &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_ID,
+        EVENT_TIME_INDEX_BUCKET_LINK_TYPE, EVENT_TIME_INDEX_ENTRY_LINK_TYPE,
+        &event_time, event_address.as_ref(),
+    )?;
+    unit.create_category_index(
+        &EVENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), EVENT_CLASSIFICATION_INDEX_ID, EVENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&event_entry), event_address.as_ref(),
+    )?;
+    unit.create_category_index(
+        &EVENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), EVENT_SCOPE_INDEX_ID, EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&event_entry), event_address.as_ref(),
+    )?;
+    // Index any new resource for retrieval via `get_all` API endpoints, ordered by the time of its creating event
     if let Some(resource_data) = &resource_created {
         let resource_addr = resource_data.0.to_owned();
-        create_anchor_index(&RESOURCE_INDEX_ROOT_ENTRY_TYPE.to_string(), RESOURCE_INDEX_ENTRY_LINK_TYPE, &RESOURCE_INDEX_ROOT_ENTRY_ID.to_string(), &resource_addr.as_ref())?;
+        unit.create_time_index(
+            &RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_ID,
+            RESOURCE_TIME_INDEX_BUCKET_LINK_TYPE, RESOURCE_TIME_INDEX_ENTRY_LINK_TYPE,
+            &event_time, resource_addr.as_ref(),
+        )?;
     }
     // Link any affected resources to this event so that we can pull all the events which affect any resource
-    // :TODO: error handling
     for resource_data in resources_affected.iter() {
-        let _ = link_entries(
+        unit.link_entries(
             resource_data.0.as_ref(),
             event_address.as_ref(),
             RESOURCE_AFFECTED_BY_EVENT_LINK_TYPE, RESOURCE_AFFECTED_BY_EVENT_LINK_TAG,
-        );
+        )?;
     }
 
     match resource_created {
@@ -149,30 +270,8 @@ pub fn receive_create_economic_event(event: EconomicEventCreateRequest, new_inve
     }
 }
 
-pub fn receive_get_economic_event(address: EventAddress) -> ZomeApiResult<ResponseData> {
-    handle_get_economic_event(&address)
-}
-
-pub fn receive_update_economic_event(event: EconomicEventUpdateRequest) -> ZomeApiResult<ResponseData> {
-    handle_update_economic_event(&event)
-}
-
-pub fn receive_delete_economic_event(address: EventAddress) -> ZomeApiResult<bool> {
-    handle_delete_economic_event(&address)
-}
-
-pub fn receive_get_all_economic_events() -> ZomeApiResult<Vec<ResponseData>> {
-    handle_get_all_economic_events()
-}
-
-pub fn receive_query_events(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    handle_query_events(&params)
-}
-
-// API logic handlers
-
-fn handle_create_economic_event(event: &EconomicEventCreateRequest, resource_address: Option<ResourceAddress>) -> ZomeApiResult<(EventAddress, Entry)> {
-    let (base_address, entry_resp): (EventAddress, Entry) = create_record(
+fn handle_create_economic_event(unit: &mut UnitOfWork, event: &EconomicEventCreateRequest, resource_address: Option<ResourceAddress>) -> GraphAPIResult<(EventAddress, Entry)> {
+    let (base_address, entry_resp): (EventAddress, Entry) = unit.create_record(
         EVENT_BASE_ENTRY_TYPE, EVENT_ENTRY_TYPE,
         EVENT_INITIAL_ENTRY_LINK_TYPE,
         match resource_address {
@@ -182,30 +281,35 @@ fn handle_create_economic_event(event: &EconomicEventCreateRequest, resource_add
     )?;
 
     // handle link fields
-    // :TODO: propagate errors
     if let EconomicEventCreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = event {
-        let _results = create_direct_index(
+        unit.create_direct_index(
             base_address.as_ref(),
             input_of.as_ref(),
             EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
             PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
-        );
+        )?;
     };
     if let EconomicEventCreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = event {
-        let _results = create_direct_index(
+        unit.create_direct_index(
             base_address.as_ref(),
             output_of.as_ref(),
             EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
             PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
-        );
+        )?;
     };
+    for (process_address, link_type, link_tag) in get_process_event_links(&entry_resp) {
+        unit.link_entries_with_attributes(
+            process_address.as_ref(), base_address.as_ref(),
+            link_type, link_tag, &get_process_event_link_attributes(&entry_resp.action),
+        )?;
+    }
 
     Ok((base_address, entry_resp))
 }
 
 /// Handle creation of new resources via events + resource metadata
 ///
-fn handle_create_economic_resource(economic_resource: &EconomicResourceCreateRequest, event: &EconomicEventCreateRequest) -> ZomeApiResult<(ResourceAddress, EconomicResourceEntry)> {
+fn handle_create_economic_resource(unit: &mut UnitOfWork, economic_resource: &EconomicResourceCreateRequest, event: &EconomicEventCreateRequest) -> GraphAPIResult<(ResourceAddress, EconomicResourceEntry)> {
     // :TODO: move this assertion to validation callback
     if let MaybeUndefined::Some(_sent_inventory_id) = &event.resource_inventoried_as {
         panic!("cannot create a new EconomicResource and specify an inventoried resource ID in the same event");
@@ -216,7 +320,7 @@ fn handle_create_economic_resource(economic_resource: &EconomicResourceCreateReq
         &economic_resource
     );
 
-    let (base_address, entry_resp): (ResourceAddress, EconomicResourceEntry) = create_record(
+    let (base_address, entry_resp): (ResourceAddress, EconomicResourceEntry) = unit.create_record(
         RESOURCE_BASE_ENTRY_TYPE, RESOURCE_ENTRY_TYPE, RESOURCE_INITIAL_ENTRY_LINK_TYPE,
         EconomicResourceEntry::from(params.clone())
     )?;
@@ -225,24 +329,33 @@ fn handle_create_economic_resource(economic_resource: &EconomicResourceCreateReq
 
     // :NOTE: this will always run- resource without a specification ID would fail entry validation (implicit in the above)
     if let Some(conforms_to) = params.get_resource_specification_id() {
-        let _results = create_direct_remote_index_destination(
+        unit.create_direct_remote_index_destination(
             ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
             RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
             RESOURCE_CONFORMS_TO_LINK_TYPE, RESOURCE_CONFORMS_TO_LINK_TAG,
             &conforms_to,
             vec![base_address.clone()],
-        );
+        )?;
     }
 
     if let Some(contained_in) = resource_params.get_contained_in() {
-        let _results = create_direct_index(
+        unit.create_direct_index(
             base_address.as_ref(),
             contained_in.as_ref(),
             RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
             RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
-        );
+        )?;
     };
 
+    unit.create_category_index(
+        &RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), RESOURCE_CLASSIFICATION_INDEX_ID, RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_resource_classifications(&entry_resp), base_address.as_ref(),
+    )?;
+    unit.create_category_index(
+        &RESOURCE_UNIT_INDEX_ENTRY_TYPE.to_string(), RESOURCE_UNIT_INDEX_ID, RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE,
+        &get_resource_units(&entry_resp), base_address.as_ref(),
+    )?;
+
     Ok((base_address, entry_resp))
 }
 
@@ -251,9 +364,22 @@ fn handle_get_economic_event(address: &EventAddress) -> ZomeApiResult<ResponseDa
     Ok(construct_response(address, &entry, get_link_fields(address)))
 }
 
+fn handle_get_economic_event_history(address: &EventAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, EVENT_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (None, None))))
+        .collect())
+}
+
 fn handle_update_economic_event(event: &EconomicEventUpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = event.get_id();
+    let prev_entry: Entry = read_record_entry(address)?;
     let new_entry = update_record(EVENT_ENTRY_TYPE, &address, event)?;
+    update_category_index(
+        &EVENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), EVENT_SCOPE_INDEX_ID, EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&prev_entry), &get_scopes(&new_entry), address.as_ref(),
+    )?;
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
     Ok(construct_response(address, &new_entry, get_link_fields(address)))
@@ -261,10 +387,33 @@ fn handle_update_economic_event(event: &EconomicEventUpdateRequest) -> ZomeApiRe
 
 /// Handle alteration of existing resources via events
 ///
-fn handle_update_economic_resource(resource_addr: &ResourceAddress, inventory_type: ResourceInventoryType, event: &EconomicEventCreateRequest) -> ZomeApiResult<(ResourceAddress, EconomicResourceEntry)> {
+fn handle_update_economic_resource(unit: &mut UnitOfWork, resource_addr: &ResourceAddress, inventory_type: ResourceInventoryType, event: &EconomicEventCreateRequest) -> GraphAPIResult<(ResourceAddress, EconomicResourceEntry)> {
     let context_event = event.with_inventory_type(inventory_type);
 
-    let new_resource = update_record(RESOURCE_ENTRY_TYPE, &resource_addr.to_owned(), &context_event)?;
+    // the resource must not change between being read here and updated, so that indexes are kept in step with it
+    let prev_resource: EconomicResourceEntry = read_record_entry(resource_addr)?;
+    let prev_revision = get_entry_revision(RESOURCE_ENTRY_TYPE, &prev_resource);
+    let new_resource = unit.update_record(RESOURCE_ENTRY_TYPE, &resource_addr.to_owned(), prev_revision.as_ref(), &context_event)?;
+
+    // events only ever add classifications to a resource, so there are none to remove from the index
+    let prev_classifications = get_resource_classifications(&prev_resource);
+    let added_classifications: Vec<String> = get_resource_classifications(&new_resource).into_iter()
+        .filter(|classification| !prev_classifications.contains(classification))
+        .collect();
+    unit.create_category_index(
+        &RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), RESOURCE_CLASSIFICATION_INDEX_ID, RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &added_classifications, resource_addr.as_ref(),
+    )?;
+
+    // likewise, units are only set upon the first event affecting each quantity
+    let prev_units = get_resource_units(&prev_resource);
+    let added_units: Vec<String> = get_resource_units(&new_resource).into_iter()
+        .filter(|unit| !prev_units.contains(unit))
+        .collect();
+    unit.create_category_index(
+        &RESOURCE_UNIT_INDEX_ENTRY_TYPE.to_string(), RESOURCE_UNIT_INDEX_ID, RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE,
+        &added_units, resource_addr.as_ref(),
+    )?;
 
     Ok((resource_addr.to_owned(), new_resource))
 }
@@ -289,77 +438,244 @@ fn handle_delete_economic_event(address: &EventAddress) -> ZomeApiResult<bool> {
         );
     }
 
+    if let Ok(event_time) = get_event_time(&entry) {
+        let _results = delete_time_index(
+            &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_ID, EVENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            &event_time, address.as_ref(),
+        );
+    }
+    let _results = delete_category_index(
+        &EVENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), EVENT_CLASSIFICATION_INDEX_ID, EVENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&entry), address.as_ref(),
+    );
+    let _results = delete_category_index(
+        &EVENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), EVENT_SCOPE_INDEX_ID, EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry), address.as_ref(),
+    );
+
     // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-fn handle_get_all_economic_events() -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>> = read_anchored_record_entries(
-        &EVENT_INDEX_ROOT_ENTRY_TYPE.to_string(), EVENT_INDEX_ENTRY_LINK_TYPE, &EVENT_INDEX_ROOT_ENTRY_ID.to_string(),
-    );
+fn handle_get_all_economic_events(params: &ListParams) -> ZomeApiResult<ResponsePage> {
+    // scoped listings page through the time index, skipping events absent from the scope index
+    let in_scope = match &params.in_scope_of {
+        Some(scope) => Some(read_category_index_addresses(
+            &EVENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), EVENT_SCOPE_INDEX_ID, EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+            scope,
+        )?),
+        None => None,
+    };
+
+    let page: TimeIndexPage<EventAddress, Entry> = read_time_indexed_record_entries_where(
+        &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_ID,
+        EVENT_TIME_INDEX_BUCKET_LINK_TYPE, EVENT_TIME_INDEX_ENTRY_LINK_TYPE,
+        &params.page,
+        |address| in_scope.as_ref().map_or(true, |scoped| scoped.contains(address)),
+    )?;
+
+    let RecordsResponse { results, errors } = handle_list_output(Ok(page.entries))?;
+
+    Ok(ResponsePage {
+        results,
+        errors,
+        next_cursor: page.next_cursor,
+    })
+}
+
+fn handle_query_events(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    handle_list_output(read_matching_events(params))
+}
 
-    handle_list_output(entries_result)
+/// Events are only read if they must be filtered by `action` without a process to filter
+/// them by; otherwise only the index is counted
+fn handle_count_events(params: &QueryParams) -> ZomeApiResult<CountResponse> {
+    let count = match (&params.action, &params.input_of, &params.output_of) {
+        (Some(_), None, None) => read_matching_event_entries(params)?.results.len(),
+        _ => count_index(&build_query(params), EVENT_INITIAL_ENTRY_LINK_TYPE)?,
+    };
+    Ok(CountResponse { count })
+}
+
+/// Aggregates are computed from event entries alone, without loading their link fields
+fn handle_aggregate_events(params: &QueryParams) -> ZomeApiResult<AggregateResponse> {
+    let RecordsResponse { results: entries, errors } = read_matching_event_entries(params)?;
+
+    Ok(AggregateResponse {
+        count: entries.len(),
+        resource_quantity: aggregate_by_unit(entries.iter().filter_map(|entry| entry.resource_quantity.to_owned())),
+        effort_quantity: aggregate_by_unit(entries.iter().filter_map(|entry| entry.effort_quantity.to_owned())),
+        errors,
+    })
+}
+
+/// Load all events matching `params`. Events which could not be read are reported in place.
+fn read_matching_events(params: &QueryParams) -> GraphAPIResult<Vec<(EventAddress, GraphAPIResult<Entry>)>> {
+    let entries: Vec<(EventAddress, GraphAPIResult<Entry>)> = query_index(&build_query(params))?;
+
+    Ok(entries.into_iter()
+        .filter(|(_, entry)| match (&params.action, entry) {
+            (Some(action), Ok(entry)) => entry.action == *action,
+            _ => true,
+        })
+        .collect())
 }
 
-fn handle_query_events(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+/// Load the entries of all events matching `params`, alongside errors for any which could not be read
+fn read_matching_event_entries(params: &QueryParams) -> ZomeApiResult<RecordsResponse<EventAddress, Entry>> {
+    Ok(build_records_response(read_matching_events(params)?, |_, entry| entry.to_owned()))
+}
+
+/// Build an index query matching all events which satisfy the indexed filters in `params`
+fn build_query(params: &QueryParams) -> IndexQuery<'static> {
+    let mut filters = vec![];
 
-    // :TODO: implement proper AND search rather than exclusive operations
     match &params.satisfies {
         Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 satisfies, SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.fulfills {
         Some(fulfills) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 fulfills, FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
-    match &params.input_of {
-        Some(input_of) => {
-            entries_result = query_direct_index_with_foreign_key(
+    // the `action` of events is held in the attributes of links from their processes
+    match (&params.input_of, &params.action) {
+        (Some(input_of), Some(action)) => {
+            filters.push(IndexQuery::attributed(
                 input_of, PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
-            );
+                get_process_event_link_attributes(action),
+            ));
+        },
+        (Some(input_of), None) => {
+            filters.push(IndexQuery::direct(
+                input_of, PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
+            ));
         },
         _ => (),
     };
-    match &params.output_of {
-        Some(output_of) => {
-            entries_result = query_direct_index_with_foreign_key(
+    match (&params.output_of, &params.action) {
+        (Some(output_of), Some(action)) => {
+            filters.push(IndexQuery::attributed(
+                output_of, PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
+                get_process_event_link_attributes(action),
+            ));
+        },
+        (Some(output_of), None) => {
+            filters.push(IndexQuery::direct(
                 output_of, PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
-            );
+            ));
+        },
+        _ => (),
+    };
+    match &params.classified_as {
+        Some(classified_as) => {
+            filters.push(IndexQuery::category(
+                EVENT_CLASSIFICATION_INDEX_ENTRY_TYPE, EVENT_CLASSIFICATION_INDEX_ID, EVENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                &String::from(classified_as.to_owned()),
+            ));
+        },
+        _ => (),
+    };
+    match &params.in_scope_of {
+        Some(in_scope_of) => {
+            filters.push(IndexQuery::category(
+                EVENT_SCOPE_INDEX_ENTRY_TYPE, EVENT_SCOPE_INDEX_ID, EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                in_scope_of,
+            ));
         },
         _ => (),
     };
+    // all events are time indexed, so an unbounded time range matches every event when unfiltered
+    if params.start_date.is_some() || params.end_date.is_some() || filters.is_empty() {
+        filters.push(IndexQuery::time_range(
+            EVENT_TIME_INDEX_ENTRY_TYPE, EVENT_TIME_INDEX_ID,
+            EVENT_TIME_INDEX_BUCKET_LINK_TYPE, EVENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            params.start_date.to_owned(), params.end_date.to_owned(),
+        ));
+    }
 
-    handle_list_output(entries_result)
-}
-
-fn handle_list_output(entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+    IndexQuery::And(filters)
+}
+
+fn handle_list_output(entries_result: GraphAPIResult<Vec<(EventAddress, GraphAPIResult<Entry>)>>) -> ZomeApiResult<QueryResponse> {
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
+}
+
+/// Determines the time used to order an event within `get_all` API endpoints.
+/// This is the first of `has_point_in_time`, `has_beginning` and `has_end` which is set.
+///
+fn get_event_time(entry: &Entry) -> GraphAPIResult<String> {
+    match entry.has_point_in_time.as_ref()
+        .or(entry.has_beginning.as_ref())
+        .or(entry.has_end.as_ref())
+    {
+        Some(time) => Ok(time.as_ref().to_string()),
+        None => Err(GraphHelperError::ValidationFailed("EconomicEvent must have a beginning, end or exact time".to_string())),
     }
 }
 
+/// Classification URLs of the resource an event refers to, which are indexed for querying
+///
+fn get_classifications(entry: &Entry) -> Vec<String> {
+    entry.resource_classified_as.to_owned().unwrap_or_default().into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Links from the processes which an event is input to & output of, as `(process, link type, link tag)`
+///
+fn get_process_event_links(entry: &Entry) -> Vec<(ProcessAddress, &'static str, &'static str)> {
+    let mut links = vec![];
+    if let Some(input_of) = &entry.input_of {
+        links.push((input_of.to_owned(), PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG));
+    }
+    if let Some(output_of) = &entry.output_of {
+        links.push((output_of.to_owned(), PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG));
+    }
+    links
+}
+
+/// Attributes of the links from processes to their events, alongside the plain links of the
+/// process `inputs` & `outputs` indexes. These allow the events of a process to be filtered
+/// by `action` without reading them.
+///
+fn get_process_event_link_attributes(action: &ActionId) -> LinkAttributeFilter {
+    let mut attributes = LinkAttributeFilter::new();
+    attributes.insert("action".to_string(), serde_json::Value::String(String::from(action.to_owned())));
+    attributes
+}
+
+/// Scopes which an event is indexed under for querying
+///
+fn get_scopes(entry: &Entry) -> Vec<String> {
+    entry.in_scope_of.to_owned().unwrap_or_default()
+}
+
+/// Events recorded against a process default to the scope of that process, unless
+/// a scope is provided. Where an event is both input to and output of processes, its
+/// scope is inherited from the process it is an input of.
+///
+fn with_inherited_scope(event: &EconomicEventCreateRequest) -> GraphAPIResult<EconomicEventCreateRequest> {
+    if MaybeUndefined::Undefined != event.in_scope_of {
+        return Ok(event.to_owned());
+    }
+    let process_address = match (&event.input_of, &event.output_of) {
+        (MaybeUndefined::Some(process_address), _) | (_, MaybeUndefined::Some(process_address)) => process_address,
+        _ => return Ok(event.to_owned()),
+    };
+
+    let process: ProcessEntry = read_record_entry(process_address)?;
+    Ok(event.with_scope(process.in_scope_of))
+}
+
 /**
  * Create response from input DHT primitives
  *
@@ -390,6 +706,7 @@ pub fn construct_response_with_resource<'a>(
     ResponseData {
         economic_event: Response {
             id: event_address.to_owned(),
+            revision_id: get_entry_revision(EVENT_ENTRY_TYPE, event),
             action: event.action.to_owned(),
             note: event.note.to_owned(),
             input_of: event.input_of.to_owned(),
@@ -433,6 +750,7 @@ pub fn construct_response<'a>(
     ResponseData {
         economic_event: Response {
             id: address.to_owned().into(),
+            revision_id: get_entry_revision(EVENT_ENTRY_TYPE, e),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             input_of: e.input_of.to_owned(),
@@ -471,15 +789,116 @@ pub fn get_link_fields<'a>(event: &EventAddress) -> (
     )
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use serde_json::{ json, Value, from_value, to_value };
+    use hdk_graph_helpers::storage::{ MockStorage, with_storage_backend };
+    use hc_zome_rea_economic_resource_lib::{ receive_get_economic_resource, receive_query_economic_resources };
+
+    fn event_request(action: &str, quantity: f64, extra_fields: Value) -> EconomicEventCreateRequest {
+        let mut fields = json!({
+            "action": action,
+            "provider": "agentA",
+            "receiver": "agentB",
+            "hasPointInTime": "2019-11-19T04:29:55.056Z",
+            "resourceQuantity": { "hasNumericalValue": quantity, "hasUnit": "kg" },
+        });
+        if let (Value::Object(fields), Value::Object(extra)) = (&mut fields, extra_fields) {
+            fields.extend(extra);
+        }
+        from_value(fields).unwrap()
+    }
+
+    #[test]
+    fn test_resource_accounting() {
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            let created = receive_create_economic_event(
+                event_request("produce", 10.0, json!({})),
+                Some(from_value(json!({ "conformsTo": "resourceSpecA" })).unwrap()),
+            ).unwrap();
+            let resource_id = created.economic_resource.unwrap().id;
+
+            receive_create_economic_event(
+                event_request("consume", 3.0, json!({ "resourceInventoriedAs": resource_id })),
+                None,
+            ).unwrap();
+
+            let resource = to_value(receive_get_economic_resource(resource_id).unwrap()).unwrap();
+            assert_eq!(resource["economicResource"]["accountingQuantity"], json!({ "hasNumericalValue": 7.0, "hasUnit": "kg" }));
+        });
+    }
 
-    // #[test]
-    // fn test_derived_fields() {
-    //     let e = Entry { note: Some("a note".into()), ..Entry::default() };
-    //     assert_eq!(e.note, Some("a note".into()))
-    // }
+    #[test]
+    fn test_resource_classifications_indexed() {
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            let created = receive_create_economic_event(
+                event_request("produce", 10.0, json!({ "resourceClassifiedAs": ["http://x.org/beef"] })),
+                Some(from_value(json!({ "conformsTo": "resourceSpecA" })).unwrap()),
+            ).unwrap();
+            let resource_id = created.economic_resource.unwrap().id;
+
+            receive_create_economic_event(
+                event_request("raise", 1.0, json!({ "resourceInventoriedAs": resource_id, "resourceClassifiedAs": ["http://x.org/carcass"] })),
+                None,
+            ).unwrap();
+
+            let by_classification = |classification: &str| -> Vec<ResourceAddress> {
+                receive_query_economic_resources(from_value(json!({ "classifiedAs": classification })).unwrap()).unwrap()
+                    .results.into_iter().map(|resource| resource.economic_resource.id).collect()
+            };
+            assert_eq!(by_classification("http://x.org/beef"), vec![resource_id.clone()]);
+            assert_eq!(by_classification("http://x.org/carcass"), vec![resource_id.clone()], "classifications merged from events are indexed");
+
+            let events = receive_query_events(from_value(json!({ "classifiedAs": "http://x.org/carcass" })).unwrap()).unwrap();
+            assert_eq!(events.results.len(), 1);
+            assert!(events.errors.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_event_aggregates() {
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            receive_create_economic_event(event_request("produce", 10.0, json!({})), None).unwrap();
+            receive_create_economic_event(event_request("consume", 3.0, json!({})), None).unwrap();
+            receive_create_economic_event(event_request("consume", 2.0, json!({})), None).unwrap();
+            receive_create_economic_event(event_request("consume", 1.0, json!({ "resourceQuantity": { "hasNumericalValue": 4.0, "hasUnit": "l" } })), None).unwrap();
+
+            let count = receive_count_events(from_value(json!({})).unwrap()).unwrap();
+            assert_eq!(count.count, 4);
+
+            let consumed = to_value(receive_aggregate_events(from_value(json!({ "action": "consume" })).unwrap()).unwrap()).unwrap();
+            assert_eq!(consumed, json!({
+                "count": 3,
+                "resourceQuantity": [
+                    { "hasUnit": "kg", "count": 2, "sum": 5.0, "min": 2.0, "max": 3.0 },
+                    { "hasUnit": "l", "count": 1, "sum": 4.0, "min": 4.0, "max": 4.0 },
+                ],
+                "effortQuantity": [],
+                "errors": [],
+            }), "quantities in different units are aggregated separately");
+        });
+    }
+
+    #[test]
+    fn test_process_events_filtered_by_action() {
+        with_storage_backend(Rc::new(MockStorage::new()), || {
+            let input = json!({ "inputOf": "QmProcess", "inScopeOf": [] });
+            let consumed = receive_create_economic_event(event_request("consume", 3.0, input.clone()), None).unwrap();
+            receive_create_economic_event(event_request("consume", 2.0, input.clone()), None).unwrap();
+            receive_create_economic_event(event_request("use", 1.0, input.clone()), None).unwrap();
+
+            let consume_inputs = json!({ "inputOf": "QmProcess", "action": "consume" });
+            assert_eq!(receive_count_events(from_value(json!({ "inputOf": "QmProcess" })).unwrap()).unwrap().count, 3);
+            assert_eq!(receive_count_events(from_value(consume_inputs.clone()).unwrap()).unwrap().count, 2);
+            assert_eq!(receive_count_events(from_value(json!({ "outputOf": "QmProcess", "action": "consume" })).unwrap()).unwrap().count, 0);
+
+            receive_delete_economic_event(consumed.economic_event.id).unwrap();
+            assert_eq!(receive_count_events(from_value(consume_inputs.clone()).unwrap()).unwrap().count, 1, "links with attributes removed with the event");
+            assert_eq!(receive_query_events(from_value(consume_inputs).unwrap()).unwrap().results.len(), 1);
+        });
+    }
 
     // :TODO: unit tests for type conversions... though maybe these should be macro tests, not tests for every single record type
-// }
+}
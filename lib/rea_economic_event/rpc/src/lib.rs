@@ -14,7 +14,12 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    records::RecordRevision,
+    entries::{ RecordsResponse, RecordReadError },
+    record_interface::RevisionedUpdate,
+};
 use vf_core::measurement::QuantityValue;
 use vf_core::type_aliases::{
     ActionId,
@@ -32,6 +37,7 @@ use vf_core::type_aliases::{
     AgreementAddress,
     ProductBatchAddress,
     UnitId,
+    Address,
 };
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
@@ -44,6 +50,8 @@ pub use vf_core::type_aliases::{ EventAddress, ResourceAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: EventAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub action: ActionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -97,6 +105,8 @@ pub struct Response {
 pub struct ResourceResponse {
     pub id: ResourceAddress,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub conforms_to: Option<ResourceSpecificationAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub classified_as: Option<Vec<ExternalURL>>,
@@ -148,6 +158,44 @@ pub struct ResourceResponseData {
     pub economic_resource: ResourceResponse,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<EventAddress, ResponseData>;
+
+/// I/O struct to describe the number of events matching a query, as returned by `count_*` endpoints
+pub use hdk_graph_helpers::entries::CountResponse;
+
+/// I/O struct to describe totals over all events matching a query, as returned by `aggregate_*` endpoints
+pub type AggregateResponse = hdk_graph_helpers::entries::AggregateResponse<EventAddress>;
+
+/// I/O struct to describe a historical revision of a resource, as returned by `get_economic_resource_history`
+pub type ResourceRevisionResponse = RecordRevision<ResourceResponseData>;
+
+/// I/O struct to describe the resources matched by `query_resources`, and any which could not be read
+pub type ResourceQueryResponse = RecordsResponse<ResourceAddress, ResourceResponseData>;
+
+/// I/O struct to describe a page of results returned from `get_all` endpoints
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePage {
+    pub results: Vec<ResponseData>,
+    pub errors: Vec<RecordReadError<EventAddress>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// I/O struct to describe a page of results returned from `get_all` endpoints
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceResponsePage {
+    pub results: Vec<ResourceResponseData>,
+    pub errors: Vec<RecordReadError<ResourceAddress>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 //---------------- CREATE REQUEST ----------------
 
 #[derive(Serialize, Deserialize, DefaultJson, Clone, Debug)]
@@ -218,6 +266,13 @@ impl<'a> CreateRequest {
         }
     }
 
+    pub fn with_scope(&self, in_scope_of: Option<Vec<String>>) -> Self {
+        CreateRequest {
+            in_scope_of: in_scope_of.into(),
+            ..self.to_owned()
+        }
+    }
+
     // accessors for field data
 
     pub fn get_action(&'a self) -> &str {
@@ -237,6 +292,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: EventAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub agreed_in: MaybeUndefined<ExternalURL>,
@@ -256,13 +313,46 @@ impl<'a> UpdateRequest {
     // :TODO: accessors for other field data
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+use hdk_graph_helpers::anchors::TimeIndexQuery;
+
+/// Time range & pagination parameters for `get_all` endpoints
+///
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListParams {
+    #[serde(flatten)]
+    pub page: TimeIndexQuery,
+    /// Scope which events must be `in_scope_of`. Events are matched against the scope
+    /// index as the time index is read, so each page is still filled up to `limit`.
+    pub in_scope_of: Option<String>,
+}
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
     pub input_of: Option<ProcessAddress>,
     pub output_of: Option<ProcessAddress>,
     pub satisfies: Option<IntentAddress>,
     pub fulfills: Option<CommitmentAddress>,
+    /// Classification URL which events must have in their `resource_classified_as`
+    pub classified_as: Option<ExternalURL>,
+    /// Scope which events must be `in_scope_of`
+    pub in_scope_of: Option<String>,
+    /// Only return events occurring at or after this time. Events are timed by their
+    /// `has_point_in_time`, `has_beginning` or `has_end`, whichever is first set.
+    pub start_date: Option<String>,
+    /// Only return events occurring at or before this time. A date without a time includes the entire day.
+    pub end_date: Option<String>,
+    /// Only return events with this `action`. Where `input_of` or `output_of` is given, this is matched
+    /// via the links from the process; otherwise it is matched against each event.
+    pub action: Option<ActionId>,
 }
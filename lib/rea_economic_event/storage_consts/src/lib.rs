@@ -17,7 +17,15 @@ pub const EVENT_INPUT_OF_LINK_TAG: &str = "input_of";
 pub const EVENT_OUTPUT_OF_LINK_TYPE: &str = "vf_economic_event_output_of";
 pub const EVENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 
-// :TODO: replace with a DAG
-pub const EVENT_INDEX_ROOT_ENTRY_TYPE: &str = "vf_economic_events_root";
-pub const EVENT_INDEX_ROOT_ENTRY_ID: &str = "all_vf_economic_events";
-pub const EVENT_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_event_root_index";
+pub const EVENT_TIME_INDEX_ENTRY_TYPE: &str = "vf_economic_events_time_index";
+pub const EVENT_TIME_INDEX_ID: &str = "all_vf_economic_events";
+pub const EVENT_TIME_INDEX_BUCKET_LINK_TYPE: &str = "vf_economic_events_time_bucket";
+pub const EVENT_TIME_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_event_time_index";
+
+pub const EVENT_CLASSIFICATION_INDEX_ENTRY_TYPE: &str = "vf_economic_events_classification_index";
+pub const EVENT_CLASSIFICATION_INDEX_ID: &str = "all_vf_economic_events";
+pub const EVENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_event_classification_index";
+
+pub const EVENT_SCOPE_INDEX_ENTRY_TYPE: &str = "vf_economic_events_scope_index";
+pub const EVENT_SCOPE_INDEX_ID: &str = "all_vf_economic_events";
+pub const EVENT_SCOPE_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_event_scope_index";
@@ -111,10 +111,10 @@ pub fn base_entry_def() -> ValidatingEntryType {
     )
 }
 
-pub fn root_entry_def() -> ValidatingEntryType {
+pub fn time_index_entry_def() -> ValidatingEntryType {
     entry!(
-        name: RESOURCE_INDEX_ROOT_ENTRY_TYPE,
-        description: "Root anchor which connects to all Economic Resources stored in this zome.",
+        name: RESOURCE_TIME_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Economic Resources stored in this zome, ordered by time.",
         sharing: Sharing::Public,
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
@@ -123,14 +123,78 @@ pub fn root_entry_def() -> ValidatingEntryType {
             Ok(())
         },
         links: [
+            to!(
+                RESOURCE_TIME_INDEX_ENTRY_TYPE,
+                link_type: RESOURCE_TIME_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
             to!(
                 RESOURCE_BASE_ENTRY_TYPE,
-                link_type: RESOURCE_INDEX_ENTRY_LINK_TYPE,
+                link_type: RESOURCE_TIME_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
 
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn classification_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE,
+        description: "Anchors for each classification URL of all EconomicResources stored in this zome, for finding them by classification.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                RESOURCE_BASE_ENTRY_TYPE,
+                link_type: RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
                 validation_package: || {
                     hdk::ValidationPackageDefinition::Entry
                 },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
 
+pub fn unit_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: RESOURCE_UNIT_INDEX_ENTRY_TYPE,
+        description: "Anchors for each measurement unit used by EconomicResources stored in this zome, for finding the resources referencing a unit.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                RESOURCE_BASE_ENTRY_TYPE,
+                link_type: RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
                 validation: | _validation_data: hdk::LinkValidationData| {
                     Ok(())
                 }
@@ -8,21 +8,35 @@
  */
 use std::borrow::Cow;
 use hdk::{
-    error::{ ZomeApiResult, ZomeApiError },
+    error::ZomeApiResult,
+    holochain_persistence_api::cas::content::Address,
 };
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     records::{
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
     },
     links::get_linked_addresses_as_type,
-    anchors::read_anchored_record_entries,
+    anchors::{
+        TimeIndexPage,
+        read_time_indexed_record_entries,
+        update_category_index,
+        read_category_index_addresses,
+    },
     local_indexes::{
         replace_direct_index,
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    schema::{ migrate_entries, MigrationReport },
+    entries::{ build_records_response, get_entry_revision, RecordsResponse },
+    read_cache::with_read_cache,
+    rpc::RemoteCapability,
 };
 
 use hc_zome_rea_resource_specification_storage_consts::{
@@ -47,22 +61,67 @@ use hc_zome_rea_economic_event_rpc::{
     CreateRequest as EventCreateRequest,
     ResourceResponse as Response,
     ResourceResponseData as ResponseData,
+    ResourceResponsePage as ResponsePage,
+    ResourceRevisionResponse as RevisionResponse,
+    ResourceQueryResponse as QueryResponse,
 };
 
+/// Zome API methods of other DNAs which the economic resource zome calls, requested upon `init`
+///
+pub const ECONOMIC_RESOURCE_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_SPECIFICATION_DHT,
+        zome: "resource_specification",
+        functions: &["get_resource_specification"],
+    },
+];
+
 pub fn receive_get_economic_resource(address: ResourceAddress) -> ZomeApiResult<ResponseData> {
-    handle_get_economic_resource(&address)
+    with_read_cache(|| handle_get_economic_resource(&address))
+}
+
+pub fn receive_get_economic_resource_history(address: ResourceAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_economic_resource_history(&address)
 }
 
 pub fn receive_update_economic_resource(resource: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_economic_resource(&resource)
 }
 
-pub fn receive_get_all_economic_resources() -> ZomeApiResult<Vec<ResponseData>> {
-    handle_get_all_economic_resources()
+pub fn receive_get_all_economic_resources(params: ListParams) -> ZomeApiResult<ResponsePage> {
+    // affecting events & their processes are shared between resources, so are cached across the whole page
+    with_read_cache(|| handle_get_all_economic_resources(&params))
+}
+
+pub fn receive_query_economic_resources(params: QueryParams) -> ZomeApiResult<QueryResponse> {
+    with_read_cache(|| handle_query_economic_resources(&params))
+}
+
+pub fn receive_check_economic_resource_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(RESOURCE_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
+            RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
+        ),
+        IndexDefinition::remote(
+            RESOURCE_CONFORMS_TO_LINK_TYPE, RESOURCE_CONFORMS_TO_LINK_TAG,
+            RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
+            BRIDGED_SPECIFICATION_DHT, "resource_specification", "get_resource_specification",
+        ),
+    ], repair)?)
+}
+
+/// Read the IDs of all resources measured in the unit with ID `address`.
+/// Called by the specification DNA when determining whether a unit may be deleted.
+pub fn receive_get_unit_resource_ids(address: Address) -> ZomeApiResult<Vec<Address>> {
+    Ok(read_category_index_addresses(
+        &RESOURCE_UNIT_INDEX_ENTRY_TYPE.to_string(), RESOURCE_UNIT_INDEX_ID, RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE,
+        &address.to_string(),
+    )?)
 }
 
-pub fn receive_query_economic_resources(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    handle_query_economic_resources(&params)
+pub fn receive_migrate_economic_resource_entries() -> ZomeApiResult<MigrationReport> {
+    Ok(migrate_entries::<Entry, _>(RESOURCE_ENTRY_TYPE)?)
 }
 
 fn handle_get_economic_resource(address: &ResourceAddress) -> ZomeApiResult<ResponseData> {
@@ -70,10 +129,28 @@ fn handle_get_economic_resource(address: &ResourceAddress) -> ZomeApiResult<Resp
     Ok(construct_response(&address, &entry, get_link_fields(&address)))
 }
 
+fn handle_get_economic_resource_history(address: &ResourceAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, RESOURCE_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (None, None, None, None))))
+        .collect())
+}
+
 fn handle_update_economic_resource(resource: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = resource.get_id();
+    let prev_entry: Entry = read_record_entry(address)?;
     let new_entry = update_record(RESOURCE_ENTRY_TYPE, &address, resource)?;
 
+    update_category_index(
+        &RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), RESOURCE_CLASSIFICATION_INDEX_ID, RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&prev_entry), &get_classifications(&new_entry), address.as_ref(),
+    )?;
+    update_category_index(
+        &RESOURCE_UNIT_INDEX_ENTRY_TYPE.to_string(), RESOURCE_UNIT_INDEX_ID, RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE,
+        &get_units(&prev_entry), &get_units(&new_entry), address.as_ref(),
+    )?;
+
     // :TODO: handle link fields
     replace_direct_index(address, &resource.get_contained_in(),
         RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
@@ -84,62 +161,67 @@ fn handle_update_economic_resource(resource: &UpdateRequest) -> ZomeApiResult<Re
     Ok(construct_response(address, &new_entry, get_link_fields(address)))
 }
 
-fn handle_get_all_economic_resources() -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>> = read_anchored_record_entries(
-        &RESOURCE_INDEX_ROOT_ENTRY_TYPE.to_string(), RESOURCE_INDEX_ENTRY_LINK_TYPE, &RESOURCE_INDEX_ROOT_ENTRY_ID.to_string(),
-    );
+fn handle_get_all_economic_resources(params: &ListParams) -> ZomeApiResult<ResponsePage> {
+    // resources are ordered by the time of the event which created them
+    let page: TimeIndexPage<ResourceAddress, Entry> = read_time_indexed_record_entries(
+        &RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_ID,
+        RESOURCE_TIME_INDEX_BUCKET_LINK_TYPE, RESOURCE_TIME_INDEX_ENTRY_LINK_TYPE,
+        params,
+    )?;
 
-    handle_list_output(entries_result)
+    let RecordsResponse { results, errors } = handle_list_output(Ok(page.entries))?;
+
+    Ok(ResponsePage {
+        results,
+        errors,
+        next_cursor: page.next_cursor,
+    })
 }
 
-fn handle_query_economic_resources(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_economic_resources(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.contains {
         Some(contains) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 &contains, RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.contained_in {
         Some(contained_in) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 contained_in, RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.conforms_to {
         Some(conforms_to) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 conforms_to, ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
-            );
+            ));
+        },
+        _ => (),
+    };
+    match &params.classified_as {
+        Some(classified_as) => {
+            filters.push(IndexQuery::category(
+                RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE, RESOURCE_CLASSIFICATION_INDEX_ID, RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                &String::from(classified_as.to_owned()),
+            ));
         },
         _ => (),
     };
 
+    let entries_result: GraphAPIResult<Vec<(ResourceAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
     handle_list_output(entries_result)
 }
 
-fn handle_list_output(entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address)
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
-    }
+fn handle_list_output(entries_result: GraphAPIResult<Vec<(ResourceAddress, GraphAPIResult<Entry>)>>) -> ZomeApiResult<QueryResponse> {
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
 }
 
 pub fn resource_creation(event: &EventCreateRequest, resource: &CreateRequest) -> CreationPayload {
@@ -149,6 +231,29 @@ pub fn resource_creation(event: &EventCreateRequest, resource: &CreateRequest) -
     }
 }
 
+/// Classification URLs of a resource which are indexed for querying.
+/// Also used by the economic event zome, which maintains the index for resources altered by events.
+pub fn get_classifications(e: &Entry) -> Vec<String> {
+    e.classified_as.to_owned().unwrap_or_default().into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// IDs of all measurement units referenced by a resource, which are indexed such that units
+/// cannot be deleted whilst in use. Also used by the economic event zome.
+pub fn get_units(e: &Entry) -> Vec<String> {
+    let mut units: Vec<String> = vec![
+        e.accounting_quantity.as_ref().and_then(|q| q.get_unit()),
+        e.onhand_quantity.as_ref().and_then(|q| q.get_unit()),
+        e.unit_of_effort.to_owned(),
+    ].into_iter()
+        .filter_map(|unit| unit.map(String::from))
+        .collect();
+    units.sort();
+    units.dedup();
+    units
+}
+
 /// Create response from input DHT primitives
 pub fn construct_response<'a>(
     address: &ResourceAddress, e: &Entry, (
@@ -185,6 +290,7 @@ pub fn construct_response_record<'a>(
     Response {
         // entry fields
         id: address.to_owned(),
+        revision_id: get_entry_revision(RESOURCE_ENTRY_TYPE, e),
         conforms_to: e.conforms_to.to_owned(),
         classified_as: e.classified_as.to_owned(),
         tracking_identifier: e.tracking_identifier.to_owned(),
@@ -233,7 +339,7 @@ fn get_resource_state(resource: &ResourceAddress) -> Option<ActionId> {
                 return result;
             }
 
-            let entry: ZomeApiResult<EventEntry> = read_record_entry(event);
+            let entry: GraphAPIResult<EventEntry> = read_record_entry(event);
             match entry {
                 Err(_) => result, // :TODO: this indicates some data integrity error
                 Ok(entry) => {
@@ -259,14 +365,14 @@ fn get_resource_stage(resource: &ResourceAddress) -> Option<ProcessSpecification
                 return result;
             }
 
-            let entry: ZomeApiResult<EventEntry> = read_record_entry(event);
+            let entry: GraphAPIResult<EventEntry> = read_record_entry(event);
             match entry {
                 Err(_) => result, // :TODO: this indicates some data integrity error
                 Ok(entry) => {
                     match &entry.output_of {
                         Some(output_of) => {
                             // get the associated process
-                            let maybe_process_entry: ZomeApiResult<ProcessEntry> = read_record_entry(output_of);
+                            let maybe_process_entry: GraphAPIResult<ProcessEntry> = read_record_entry(output_of);
                             // check to see if it has an associated specification
                             match &maybe_process_entry {
                                 Ok(process_entry) => match &process_entry.based_on {
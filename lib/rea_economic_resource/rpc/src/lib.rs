@@ -14,13 +14,17 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    record_interface::RevisionedUpdate,
+};
 use vf_core::type_aliases::{
     ExternalURL,
     LocationAddress,
     ResourceSpecificationAddress,
     UnitId,
     ProductBatchAddress,
+    Address,
 };
 
 use hc_zome_rea_economic_event_rpc::CreateRequest as EventCreateRequest;
@@ -83,6 +87,8 @@ impl<'a> CreationPayload {
 pub struct UpdateRequest {
     pub id: ResourceAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     pub classified_as: MaybeUndefined<Vec<ExternalURL>>,
     #[serde(default)]
     pub image: MaybeUndefined<ExternalURL>,
@@ -104,12 +110,24 @@ impl<'a> UpdateRequest {
     }
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
+// Time range & pagination parameters for `get_all` endpoints
+pub use hdk_graph_helpers::anchors::TimeIndexQuery as ListParams;
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
     pub contains: Option<ResourceAddress>,
     pub contained_in: Option<ResourceAddress>,
     pub conforms_to: Option<ResourceSpecificationAddress>,
+    /// Classification URL which resources must be `classified_as`
+    pub classified_as: Option<ExternalURL>,
 }
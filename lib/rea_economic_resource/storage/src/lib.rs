@@ -11,18 +11,15 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-use hdk::{
-    PUBLIC_TOKEN,
-    error::{ZomeApiResult},
-    holochain_persistence_api::cas::content::Address,
-};
+use hdk::holochain_persistence_api::cas::content::Address;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::{
     MaybeUndefined,
+    GraphAPIResult,
     record_interface::Updateable,
-    rpc::read_from_zome,
+    rpc::read_with_capability,
 };
 
 use vf_core::measurement::*;
@@ -46,7 +43,7 @@ use hc_zome_rea_economic_event_rpc::{
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
     pub conforms_to: Option<ResourceSpecificationAddress>,
     pub classified_as: Option<Vec<ExternalURL>>,
@@ -60,6 +57,8 @@ pub struct Entry {
     pub note: Option<String>,
 }
 
+hdk_graph_helpers::versioned_entry!(Entry);
+
 impl Entry {
     pub fn validate(&self) -> Result<(), String> {
         if !(self.classified_as.is_some() || self.conforms_to.is_some()) {
@@ -129,10 +128,9 @@ pub struct GetSpecificationRequest {
 }
 
 fn get_default_unit_for_specification(specification_id: ResourceSpecificationAddress) -> Option<UnitId> {
-    let spec_data: ZomeApiResult<ResourceSpecificationResponse> = read_from_zome(
+    let spec_data: GraphAPIResult<ResourceSpecificationResponse> = read_with_capability(
         BRIDGED_SPECIFICATION_DHT,
         "resource_specification",
-        Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
         "get_resource_specification",
         GetSpecificationRequest { address: specification_id.to_owned().into() }.into(),
     );
@@ -17,9 +17,17 @@ pub const RESOURCE_AFFECTED_BY_EVENT_LINK_TAG: &str = "affected_by";
 pub const RESOURCE_CONFORMS_TO_LINK_TYPE: &str = "vf_economic_resource_conforms_to";
 pub const RESOURCE_CONFORMS_TO_LINK_TAG: &str = "conforms_to";
 
-// :TODO: replace with a DAG
-pub const RESOURCE_INDEX_ROOT_ENTRY_TYPE: &str = "vf_economic_resources_root";
-pub const RESOURCE_INDEX_ROOT_ENTRY_ID: &str = "all_vf_economic_resources";
-pub const RESOURCE_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_resource_root_index";
+pub const RESOURCE_TIME_INDEX_ENTRY_TYPE: &str = "vf_economic_resources_time_index";
+pub const RESOURCE_TIME_INDEX_ID: &str = "all_vf_economic_resources";
+pub const RESOURCE_TIME_INDEX_BUCKET_LINK_TYPE: &str = "vf_economic_resources_time_bucket";
+pub const RESOURCE_TIME_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_resource_time_index";
+
+pub const RESOURCE_CLASSIFICATION_INDEX_ENTRY_TYPE: &str = "vf_economic_resources_classification_index";
+pub const RESOURCE_CLASSIFICATION_INDEX_ID: &str = "all_vf_economic_resources";
+pub const RESOURCE_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_resource_classification_index";
+
+pub const RESOURCE_UNIT_INDEX_ENTRY_TYPE: &str = "vf_economic_resources_unit_index";
+pub const RESOURCE_UNIT_INDEX_ID: &str = "all_vf_economic_resources";
+pub const RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_resource_unit_index";
 
 pub const BRIDGED_SPECIFICATION_DHT: &str = "vf_specification";
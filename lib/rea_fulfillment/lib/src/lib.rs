@@ -10,6 +10,8 @@
  * @package Holo-REA
  */
 use vf_core::type_aliases::FulfillmentAddress;
+use hdk_graph_helpers::entries::get_entry_revision;
+use hc_zome_rea_fulfillment_storage_consts::FULFILLMENT_ENTRY_TYPE;
 use hc_zome_rea_fulfillment_storage::Entry;
 use hc_zome_rea_fulfillment_rpc::*;
 
@@ -18,6 +20,7 @@ pub fn construct_response(address: &FulfillmentAddress, e: &Entry) -> ResponseDa
     ResponseData {
         fulfillment: Response {
             id: address.to_owned(),
+            revision_id: get_entry_revision(FULFILLMENT_ENTRY_TYPE, e),
             fulfilled_by: e.fulfilled_by.to_owned(),
             fulfills: e.fulfills.to_owned(),
             resource_quantity: e.resource_quantity.to_owned(),
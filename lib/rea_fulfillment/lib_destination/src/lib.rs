@@ -12,16 +12,21 @@
 use hdk::prelude::*;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     records::{
-        create_record,
+        with_unit_of_work,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
     local_indexes::{
-        create_direct_index,
-        query_direct_index_with_foreign_key,
+        IndexQuery,
+        query_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    entries::build_records_response,
 };
 
 use hc_zome_rea_economic_event_storage_consts::{EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG};
@@ -38,32 +43,50 @@ pub fn receive_get_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<Res
     handle_get_fulfillment(&address)
 }
 
+pub fn receive_get_fulfillment_history(address: FulfillmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_fulfillment_history(&address)
+}
+
 pub fn receive_update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_fulfillment(&fulfillment)
 }
 
 pub fn receive_delete_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_fulfillments(&params)
 }
 
+pub fn receive_check_fulfillment_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(FULFILLMENT_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
+            EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
 fn handle_create_fulfillment(fulfillment: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = create_record(
-        FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
-        FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
-        fulfillment.to_owned()
-    )?;
-
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        fulfillment_address.as_ref(),
-        fulfillment.get_fulfilled_by().as_ref(),
-        FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
-        EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
-    );
+    // record & local index writes are reverted if any of them fail
+    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = with_unit_of_work(|unit| {
+        let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = unit.create_record(
+            FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
+            FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
+            fulfillment.to_owned()
+        )?;
+
+        // link entries in the local DNA
+        unit.create_direct_index(
+            fulfillment_address.as_ref(),
+            fulfillment.get_fulfilled_by().as_ref(),
+            FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
+            EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
+        )?;
+
+        Ok((fulfillment_address, entry_resp))
+    })?;
 
     // register in the associated foreign DNA as well
     // :TODO: probably need to remove this, can't do bridging bidirectionally
@@ -90,29 +113,24 @@ fn handle_get_fulfillment(base_address: &FulfillmentAddress) -> ZomeApiResult<Re
     Ok(construct_response(base_address, &entry))
 }
 
-fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(FulfillmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_get_fulfillment_history(address: &FulfillmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, FULFILLMENT_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
+fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.fulfilled_by {
         Some(fulfilled_by) => {
-            entries_result = query_direct_index_with_foreign_key(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG);
+            filters.push(IndexQuery::direct(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG));
         },
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    // :TODO: avoid cloning entry
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, &entry)),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
-    }
+    let entries_result: GraphAPIResult<Vec<(FulfillmentAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
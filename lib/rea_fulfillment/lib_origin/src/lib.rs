@@ -10,22 +10,25 @@
  * @package Holo-REA
  */
 use hdk::prelude::*;
-use hdk::{
-    PUBLIC_TOKEN,
-    call,
-};
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
+    storage::call,
     records::{
-        create_record,
+        with_unit_of_work,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
     local_indexes::{
-        create_direct_index,
-        query_direct_index_with_foreign_key,
+        IndexQuery,
+        query_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    rpc::{ RemoteCapability, capability_token },
+    entries::build_records_response,
 };
 
 use hc_zome_rea_commitment_storage_consts::{COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG};
@@ -34,6 +37,16 @@ use hc_zome_rea_fulfillment_storage::Entry;
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_lib::construct_response;
 
+/// Zome API methods of other DNAs which the fulfillment zome calls, requested upon `init`
+///
+pub const FULFILLMENT_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "fulfillment",
+        functions: &["fulfillment_created", "fulfillment_updated", "fulfillment_deleted"],
+    },
+];
+
 pub fn receive_create_fulfillment(fulfillment: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_fulfillment(&fulfillment)
 }
@@ -42,6 +55,10 @@ pub fn receive_get_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<Res
     handle_get_fulfillment(&address)
 }
 
+pub fn receive_get_fulfillment_history(address: FulfillmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_fulfillment_history(&address)
+}
+
 pub fn receive_update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_fulfillment(&fulfillment)
 }
@@ -50,30 +67,44 @@ pub fn receive_delete_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<
     handle_delete_fulfillment(&address)
 }
 
-pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_fulfillments(&params)
 }
 
+pub fn receive_check_fulfillment_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(FULFILLMENT_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
+            COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
 fn handle_create_fulfillment(fulfillment: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = create_record(
-        FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
-        FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
-        fulfillment.to_owned(),
-    )?;
-
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        fulfillment_address.as_ref(),
-        fulfillment.get_fulfills().as_ref(),
-        FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
-        COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
-    );
+    // record & local index writes are reverted if any of them fail
+    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = with_unit_of_work(|unit| {
+        let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = unit.create_record(
+            FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
+            FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
+            fulfillment.to_owned(),
+        )?;
+
+        // link entries in the local DNA
+        unit.create_direct_index(
+            fulfillment_address.as_ref(),
+            fulfillment.get_fulfills().as_ref(),
+            FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
+            COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
+        )?;
+
+        Ok((fulfillment_address, entry_resp))
+    })?;
 
     // update in the associated foreign DNA as well
     let _pingback = call(
         BRIDGED_OBSERVATION_DHT,
         "fulfillment",
-        Address::from(PUBLIC_TOKEN.to_string()),
+        capability_token(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_created")?,
         "fulfillment_created",
         FwdCreateRequest { fulfillment: fulfillment.to_owned() }.into()
     );
@@ -87,6 +118,13 @@ fn handle_get_fulfillment(base_address: &FulfillmentAddress) -> ZomeApiResult<Re
     Ok(construct_response(&base_address, &entry))
 }
 
+fn handle_get_fulfillment_history(address: &FulfillmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, FULFILLMENT_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_update_fulfillment(fulfillment: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = fulfillment.get_id();
     let new_entry = update_record(FULFILLMENT_ENTRY_TYPE, &base_address, fulfillment)?;
@@ -95,7 +133,7 @@ fn handle_update_fulfillment(fulfillment: &UpdateRequest) -> ZomeApiResult<Respo
     let _pingback = call(
         BRIDGED_OBSERVATION_DHT,
         "fulfillment",
-        Address::from(PUBLIC_TOKEN.to_string()),
+        capability_token(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_updated")?,
         "fulfillment_updated",
         FwdUpdateRequest { fulfillment: fulfillment.clone() }.into()
     );
@@ -110,45 +148,32 @@ fn handle_delete_fulfillment(address: &FulfillmentAddress) -> ZomeApiResult<bool
     let _pingback = call(
         BRIDGED_OBSERVATION_DHT,
         "fulfillment",
-        Address::from(PUBLIC_TOKEN.to_string()),
+        capability_token(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_deleted")?,
         "fulfillment_deleted",
         address.into(),
     );
 
-    result
+    Ok(result?)
 }
 
-fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(FulfillmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
-    // :TODO: proper search logic, not mutually exclusive ID filters
     match &params.fulfills {
         Some(fulfills) => {
-            entries_result = query_direct_index_with_foreign_key(fulfills, COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG);
+            filters.push(IndexQuery::direct(fulfills, COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG));
         },
         _ => (),
     };
     // :TODO: observation DNA handles this. Should queries be possible in planning DNA, too?
     // match &params.fulfilled_by {
     //     Some(fulfilled_by) => {
-    //         entries_result = query_direct_index_with_foreign_key(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG);
+    //         filters.push(IndexQuery::direct(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG));
     //     },
     //     _ => (),
     // };
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    // :TODO: avoid cloning entry
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, entry)),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
-    }
+    let entries_result: GraphAPIResult<Vec<(FulfillmentAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
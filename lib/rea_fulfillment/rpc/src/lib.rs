@@ -14,12 +14,18 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    records::RecordRevision,
+    entries::RecordsResponse,
+    record_interface::RevisionedUpdate,
+};
 use vf_core::{
     measurement::QuantityValue,
     type_aliases::{
         EventAddress,
         CommitmentAddress,
+        Address,
     },
 };
 
@@ -34,6 +40,8 @@ pub use vf_core::type_aliases::{ FulfillmentAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: FulfillmentAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub fulfilled_by: EventAddress,
     pub fulfills: CommitmentAddress,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -54,6 +62,14 @@ pub struct ResponseData {
     pub fulfillment: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<FulfillmentAddress, ResponseData>;
+
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -102,6 +118,8 @@ pub struct FwdCreateRequest {
 pub struct UpdateRequest {
     pub id: FulfillmentAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
     pub fulfilled_by: MaybeUndefined<EventAddress>, // note this setup allows None to be passed but `update_with` ignores it
     #[serde(default)]
@@ -126,6 +144,12 @@ impl<'a> UpdateRequest {
     // :TODO: accessors for other field data
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 /// I/O struct for forwarding records to other DNAs via zome API
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -11,9 +11,6 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-use holochain_json_api::{ json::JsonString, error::JsonError };
-use holochain_json_derive::{ DefaultJson };
-
 use hdk_graph_helpers::{
     MaybeUndefined,
     record_interface::Updateable,
@@ -31,7 +28,7 @@ use hc_zome_rea_fulfillment_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
     pub fulfilled_by: EventAddress,
     pub fulfills: CommitmentAddress,
@@ -40,6 +37,8 @@ pub struct Entry {
     pub note: Option<String>,
 }
 
+hdk_graph_helpers::versioned_entry!(Entry);
+
 //---------------- CREATE ----------------
 
 /// Pick relevant fields out of I/O record into underlying DHT entry
@@ -108,3 +108,161 @@ pub fn base_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn search_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: INTENT_SEARCH_INDEX_ENTRY_TYPE,
+        description: "Anchors for the words in the notes of all Intents stored in this zome, for finding them by text search.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                INTENT_BASE_ENTRY_TYPE,
+                link_type: INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn classification_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE,
+        description: "Anchors for each classification URL of all Intents stored in this zome, for finding them by classification.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                INTENT_BASE_ENTRY_TYPE,
+                link_type: INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn scope_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: INTENT_SCOPE_INDEX_ENTRY_TYPE,
+        description: "Anchors for each scope which Intents stored in this zome are `in_scope_of`, for listing the contents of a scope.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                INTENT_BASE_ENTRY_TYPE,
+                link_type: INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn time_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: INTENT_TIME_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Intents stored in this zome, ordered by their beginning, end or exact time.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                INTENT_TIME_INDEX_ENTRY_TYPE,
+                link_type: INTENT_TIME_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                INTENT_BASE_ENTRY_TYPE,
+                link_type: INTENT_TIME_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn due_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: INTENT_DUE_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Intents stored in this zome, ordered by due date.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                INTENT_DUE_INDEX_ENTRY_TYPE,
+                link_type: INTENT_DUE_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                INTENT_BASE_ENTRY_TYPE,
+                link_type: INTENT_DUE_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
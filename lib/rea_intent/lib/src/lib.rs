@@ -7,32 +7,45 @@
  * @package Holo-REA
  */
 use std::borrow::Cow;
-use hdk::{
-    PUBLIC_TOKEN,
-    prelude::Address,
-    error::{ ZomeApiResult, ZomeApiError },
-};
+use hdk::error::ZomeApiResult;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     MaybeUndefined,
     records::{
         create_record,
         read_record_entry,
+        read_active_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
-        delete_record,
+        check_not_deleted,
+        tombstone_record,
+        restore_record,
+        exclude_deleted_records,
     },
     links::{
         get_linked_addresses_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_index,
     },
     remote_indexes::{
         create_direct_remote_index,
         update_direct_remote_index,
+        check_remote_index_results,
         remove_direct_remote_index,
     },
+    search::{ create_search_index, update_search_index },
+    anchors::{
+        create_category_index, update_category_index,
+        create_time_index, update_time_index,
+    },
+    integrity::{ IndexDefinition, check_record_indexes },
+    schema::{ migrate_entries, reindex_records, MigrationReport },
+    rpc::RemoteCapability,
+    entries::{ build_records_response, get_entry_revision },
 };
 
 use vf_core::type_aliases::{
@@ -53,6 +66,16 @@ use hc_zome_rea_satisfaction_storage_consts::{
     SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
 };
 
+/// Zome API methods of other DNAs which the intent zome calls, requested upon `init`
+///
+pub const INTENT_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "process",
+        functions: &["index_intended_inputs", "index_intended_outputs", "get_process"],
+    },
+];
+
 pub fn receive_create_intent(intent: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_intent(&intent)
 }
@@ -61,6 +84,10 @@ pub fn receive_get_intent(address: IntentAddress) -> ZomeApiResult<ResponseData>
     handle_get_intent(&address)
 }
 
+pub fn receive_get_intent_history(address: IntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_intent_history(&address)
+}
+
 pub fn receive_update_intent(intent: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_intent(&intent)
 }
@@ -69,44 +96,116 @@ pub fn receive_delete_intent(address: IntentAddress) -> ZomeApiResult<bool> {
     handle_delete_intent(&address)
 }
 
-pub fn receive_query_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_restore_intent(address: IntentAddress) -> ZomeApiResult<ResponseData> {
+    handle_restore_intent(&address)
+}
+
+pub fn receive_query_intents(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_intents(&params)
 }
 
+pub fn receive_get_intents_in_scope(scope: String) -> ZomeApiResult<QueryResponse> {
+    handle_query_intents(&QueryParams { in_scope_of: Some(scope), ..QueryParams::default() })
+}
+
+pub fn receive_check_intent_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(INTENT_BASE_ENTRY_TYPE, &[
+        IndexDefinition::remote(
+            INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
+            PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+            BRIDGED_OBSERVATION_DHT, "process", "get_process",
+        ),
+        IndexDefinition::remote(
+            INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
+            PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+            BRIDGED_OBSERVATION_DHT, "process", "get_process",
+        ),
+        IndexDefinition::local(
+            INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
+            SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
+pub fn receive_migrate_intent_entries() -> ZomeApiResult<MigrationReport> {
+    let report = migrate_entries::<Entry, _>(INTENT_ENTRY_TYPE)?;
+    // intents created before search indexing was introduced are added to the index
+    Ok(reindex_records(INTENT_BASE_ENTRY_TYPE, INTENT_INITIAL_ENTRY_LINK_TYPE, report, |address: &IntentAddress, entry: &Entry| {
+        create_search_index(
+            &INTENT_SEARCH_INDEX_ENTRY_TYPE.to_string(), INTENT_SEARCH_INDEX_ID, INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(entry), address.as_ref(),
+        )
+    })?)
+}
+
 // :TODO: move to hdk_graph_helpers module
 
 fn handle_get_intent(address: &IntentAddress) -> ZomeApiResult<ResponseData> {
-    let entry = read_record_entry(&address)?;
+    let entry = read_active_record_entry(&address, INTENT_INITIAL_ENTRY_LINK_TYPE)?;
     Ok(construct_response(&address, &entry, get_link_fields(&address)))
 }
 
+fn handle_get_intent_history(address: &IntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, INTENT_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (None,))))
+        .collect())
+}
+
 fn handle_create_intent(intent: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (IntentAddress, Entry) = create_record(
         INTENT_BASE_ENTRY_TYPE, INTENT_ENTRY_TYPE,
         INTENT_INITIAL_ENTRY_LINK_TYPE,
         intent.to_owned(),
     )?;
+    create_search_index(
+        &INTENT_SEARCH_INDEX_ENTRY_TYPE.to_string(), INTENT_SEARCH_INDEX_ID, INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), INTENT_CLASSIFICATION_INDEX_ID, INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &INTENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), INTENT_SCOPE_INDEX_ID, INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry_resp), base_address.as_ref(),
+    )?;
+    if let Some(time) = get_planned_time(&entry_resp) {
+        create_time_index(
+            &INTENT_TIME_INDEX_ENTRY_TYPE.to_string(), INTENT_TIME_INDEX_ID,
+            INTENT_TIME_INDEX_BUCKET_LINK_TYPE, INTENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            &time, base_address.as_ref(),
+        )?;
+    }
+    if let Some(time) = get_due_time(&entry_resp) {
+        create_time_index(
+            &INTENT_DUE_INDEX_ENTRY_TYPE.to_string(), INTENT_DUE_INDEX_ID,
+            INTENT_DUE_INDEX_BUCKET_LINK_TYPE, INTENT_DUE_INDEX_ENTRY_LINK_TYPE,
+            &time, base_address.as_ref(),
+        )?;
+    }
 
     // handle link fields
     if let CreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = intent {
-        let _results = create_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
             PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
             base_address.as_ref(),
             vec![(input_of.as_ref()).clone()],
-        );
+        ))?;
     };
     if let CreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = intent {
-        let _results = create_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
             PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
             base_address.as_ref(),
             vec![(output_of.as_ref()).clone()],
-        );
+        ))?;
     };
 
     // return entire record structure
@@ -115,26 +214,50 @@ fn handle_create_intent(intent: &CreateRequest) -> ZomeApiResult<ResponseData> {
 
 fn handle_update_intent(intent: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = intent.get_id();
+    check_not_deleted(address.as_ref(), INTENT_INITIAL_ENTRY_LINK_TYPE)?;
+    let prev_entry: Entry = read_record_entry(address)?;
     let new_entry = update_record(INTENT_ENTRY_TYPE, address, intent)?;
+    update_search_index(
+        &INTENT_SEARCH_INDEX_ENTRY_TYPE.to_string(), INTENT_SEARCH_INDEX_ID, INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&prev_entry), &get_search_text(&new_entry), address.as_ref(),
+    )?;
+    update_category_index(
+        &INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), INTENT_CLASSIFICATION_INDEX_ID, INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&prev_entry), &get_classifications(&new_entry), address.as_ref(),
+    )?;
+    update_category_index(
+        &INTENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), INTENT_SCOPE_INDEX_ID, INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&prev_entry), &get_scopes(&new_entry), address.as_ref(),
+    )?;
+    update_time_index(
+        &INTENT_TIME_INDEX_ENTRY_TYPE.to_string(), INTENT_TIME_INDEX_ID,
+        INTENT_TIME_INDEX_BUCKET_LINK_TYPE, INTENT_TIME_INDEX_ENTRY_LINK_TYPE,
+        get_planned_time(&prev_entry).as_ref().map(String::as_str), get_planned_time(&new_entry).as_ref().map(String::as_str), address.as_ref(),
+    )?;
+    update_time_index(
+        &INTENT_DUE_INDEX_ENTRY_TYPE.to_string(), INTENT_DUE_INDEX_ID,
+        INTENT_DUE_INDEX_BUCKET_LINK_TYPE, INTENT_DUE_INDEX_ENTRY_LINK_TYPE,
+        get_due_time(&prev_entry).as_ref().map(String::as_str), get_due_time(&new_entry).as_ref().map(String::as_str), address.as_ref(),
+    )?;
 
     // handle link fields
     if MaybeUndefined::Undefined != intent.input_of {
-        let _results = update_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(update_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
             PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
             address, &intent.input_of,
-        );
+        ))?;
     }
     if MaybeUndefined::Undefined != intent.output_of {
-        let _results = update_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+        check_remote_index_results(update_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
             PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
             address, &intent.output_of,
-        );
+        ))?;
     }
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
@@ -143,12 +266,12 @@ fn handle_update_intent(intent: &UpdateRequest) -> ZomeApiResult<ResponseData> {
 
 fn handle_delete_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
     // read any referencing indexes
-    let entry: Entry = read_record_entry(&address)?;
+    let entry: Entry = read_active_record_entry(address, INTENT_INITIAL_ENTRY_LINK_TYPE)?;
 
-    // handle link fields
+    // handle link fields. Local indexes are retained, so that deleted intents can still be queried with `include_deleted`
     if let Some(process_address) = entry.input_of {
         let _results = remove_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
             PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
@@ -157,7 +280,7 @@ fn handle_delete_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
     }
     if let Some(process_address) = entry.output_of {
         let _results = remove_direct_remote_index(
-            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs",
             PROCESS_BASE_ENTRY_TYPE,
             INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
             PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
@@ -165,56 +288,178 @@ fn handle_delete_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
         );
     }
 
-    // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    // mark entry as deleted last as it must be present in order for links to be removed
+    Ok(tombstone_record::<Entry, _>(address, INTENT_INITIAL_ENTRY_LINK_TYPE)?)
 }
 
-fn handle_query_intents(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(IntentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_restore_intent(address: &IntentAddress) -> ZomeApiResult<ResponseData> {
+    let (restored, entry): (bool, Entry) = restore_record(address, INTENT_INITIAL_ENTRY_LINK_TYPE)?;
+
+    // reinstate link fields removed upon deletion, and any indexes removed by earlier versions
+    if restored {
+        create_search_index(
+            &INTENT_SEARCH_INDEX_ENTRY_TYPE.to_string(), INTENT_SEARCH_INDEX_ID, INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(&entry), address.as_ref(),
+        )?;
+        create_category_index(
+            &INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), INTENT_CLASSIFICATION_INDEX_ID, INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+            &get_classifications(&entry), address.as_ref(),
+        )?;
+        create_category_index(
+            &INTENT_SCOPE_INDEX_ENTRY_TYPE.to_string(), INTENT_SCOPE_INDEX_ID, INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+            &get_scopes(&entry), address.as_ref(),
+        )?;
+        if let Some(time) = get_planned_time(&entry) {
+            create_time_index(
+                &INTENT_TIME_INDEX_ENTRY_TYPE.to_string(), INTENT_TIME_INDEX_ID,
+                INTENT_TIME_INDEX_BUCKET_LINK_TYPE, INTENT_TIME_INDEX_ENTRY_LINK_TYPE,
+                &time, address.as_ref(),
+            )?;
+        }
+        if let Some(time) = get_due_time(&entry) {
+            create_time_index(
+                &INTENT_DUE_INDEX_ENTRY_TYPE.to_string(), INTENT_DUE_INDEX_ID,
+                INTENT_DUE_INDEX_BUCKET_LINK_TYPE, INTENT_DUE_INDEX_ENTRY_LINK_TYPE,
+                &time, address.as_ref(),
+            )?;
+        }
+        if let Some(process_address) = &entry.input_of {
+            check_remote_index_results(create_direct_remote_index(
+                BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs",
+                PROCESS_BASE_ENTRY_TYPE,
+                INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
+                PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+                address.as_ref(),
+                vec![(process_address.as_ref()).clone()],
+            ))?;
+        }
+        if let Some(process_address) = &entry.output_of {
+            check_remote_index_results(create_direct_remote_index(
+                BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs",
+                PROCESS_BASE_ENTRY_TYPE,
+                INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
+                PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+                address.as_ref(),
+                vec![(process_address.as_ref()).clone()],
+            ))?;
+        }
+    }
+
+    Ok(construct_response(address, &entry, get_link_fields(address)))
+}
+
+fn handle_query_intents(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.satisfied_by {
         Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 &satisfied_by, SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.input_of {
         Some(input_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 input_of, PROCESS_BASE_ENTRY_TYPE,
                 PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.output_of {
         Some(output_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 output_of, PROCESS_BASE_ENTRY_TYPE,
                 PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
-            );
+            ));
+        },
+        _ => (),
+    };
+    match &params.search {
+        Some(search) => {
+            filters.push(IndexQuery::search(
+                INTENT_SEARCH_INDEX_ENTRY_TYPE, INTENT_SEARCH_INDEX_ID, INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                search,
+            ));
         },
         _ => (),
     };
+    match &params.classified_as {
+        Some(classified_as) => {
+            filters.push(IndexQuery::category(
+                INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE, INTENT_CLASSIFICATION_INDEX_ID, INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                &String::from(classified_as.to_owned()),
+            ));
+        },
+        _ => (),
+    };
+    match &params.in_scope_of {
+        Some(in_scope_of) => {
+            filters.push(IndexQuery::category(
+                INTENT_SCOPE_INDEX_ENTRY_TYPE, INTENT_SCOPE_INDEX_ID, INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                in_scope_of,
+            ));
+        },
+        _ => (),
+    };
+    if params.start_date.is_some() || params.end_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            INTENT_TIME_INDEX_ENTRY_TYPE, INTENT_TIME_INDEX_ID,
+            INTENT_TIME_INDEX_BUCKET_LINK_TYPE, INTENT_TIME_INDEX_ENTRY_LINK_TYPE,
+            params.start_date.to_owned(), params.end_date.to_owned(),
+        ));
+    }
+    if params.due_start_date.is_some() || params.due_end_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            INTENT_DUE_INDEX_ENTRY_TYPE, INTENT_DUE_INDEX_ID,
+            INTENT_DUE_INDEX_BUCKET_LINK_TYPE, INTENT_DUE_INDEX_ENTRY_LINK_TYPE,
+            params.due_start_date.to_owned(), params.due_end_date.to_owned(),
+        ));
+    }
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address)
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+    let entries_result: GraphAPIResult<Vec<(IntentAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    let mut entries = entries_result?;
+
+    // omit records which have been marked as deleted, unless requested
+    if !params.include_deleted.unwrap_or(false) {
+        entries = exclude_deleted_records(entries, INTENT_INITIAL_ENTRY_LINK_TYPE);
     }
+
+    Ok(build_records_response(entries, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
+}
+
+/// Text of an intent which is indexed for searching
+fn get_search_text(e: &Entry) -> String {
+    e.note.to_owned().unwrap_or_default()
+}
+
+/// Classification URLs of the resources an intent refers to, which are indexed for querying
+fn get_classifications(e: &Entry) -> Vec<String> {
+    e.resource_classified_as.to_owned().unwrap_or_default().into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Scopes which an intent is indexed under for querying
+fn get_scopes(e: &Entry) -> Vec<String> {
+    e.in_scope_of.to_owned().unwrap_or_default()
+}
+
+/// Time which an intent is indexed under for querying by date range,
+/// being the first of `has_point_in_time`, `has_beginning` and `has_end` which is set
+fn get_planned_time(e: &Entry) -> Option<String> {
+    e.has_point_in_time.as_ref()
+        .or(e.has_beginning.as_ref())
+        .or(e.has_end.as_ref())
+        .map(|time| time.as_ref().to_string())
+}
+
+/// Due date which an intent is indexed under for querying
+fn get_due_time(e: &Entry) -> Option<String> {
+    e.due.as_ref().map(|time| time.as_ref().to_string())
 }
 
 /// Create response from input DHT primitives
@@ -230,6 +475,7 @@ pub fn construct_response<'a>(
     ResponseData {
         intent: Response {
             id: address.to_owned(),
+            revision_id: get_entry_revision(INTENT_ENTRY_TYPE, e),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             image: e.image.to_owned(),
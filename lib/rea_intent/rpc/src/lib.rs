@@ -17,6 +17,9 @@ use holochain_json_derive::{ DefaultJson };
 use hdk_graph_helpers::{
     MaybeUndefined,
     maybe_undefined::default_false,
+    records::RecordRevision,
+    entries::RecordsResponse,
+    record_interface::RevisionedUpdate,
 };
 use vf_core::{
     measurement::QuantityValue,
@@ -30,6 +33,7 @@ use vf_core::{
         ResourceSpecificationAddress,
         SatisfactionAddress,
         LocationAddress,
+        Address,
     },
 };
 
@@ -44,6 +48,8 @@ pub use vf_core::type_aliases::{ IntentAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: IntentAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub action: ActionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -100,6 +106,14 @@ pub struct ResponseData {
     pub intent: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<IntentAddress, ResponseData>;
+
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -163,6 +177,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: IntentAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     pub action: MaybeUndefined<ActionId>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
@@ -214,12 +230,36 @@ impl<'a> UpdateRequest {
     // :TODO: accessors for other field data
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
     pub input_of: Option<ProcessAddress>,
     pub output_of: Option<ProcessAddress>,
     pub satisfied_by: Option<SatisfactionAddress>,
+    /// Classification URL which intents must have in their `resource_classified_as`
+    pub classified_as: Option<ExternalURL>,
+    /// Scope which intents must be `in_scope_of`
+    pub in_scope_of: Option<String>,
+    /// Only return intents occurring at or after this time. Intents are timed by their
+    /// `has_point_in_time`, `has_beginning` or `has_end`, whichever is first set.
+    pub start_date: Option<String>,
+    /// Only return intents occurring at or before this time. A date without a time includes the entire day.
+    pub end_date: Option<String>,
+    /// Only return intents due at or after this time
+    pub due_start_date: Option<String>,
+    /// Only return intents due at or before this time. A date without a time includes the entire day.
+    pub due_end_date: Option<String>,
+    /// Text to find in the `note` of intents. Terms match words by prefix,
+    /// or as whole words if enclosed in double quotes.
+    pub search: Option<String>,
+    /// Set to include records which have been marked as deleted
+    pub include_deleted: Option<bool>,
 }
@@ -18,4 +18,26 @@ pub const INTENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 pub const INTENT_PUBLISHED_IN_LINK_TYPE: &str = "vf_intent_published_in";
 pub const INTENT_PUBLISHED_IN_LINK_TAG: &str = "published_in";
 
+pub const INTENT_SEARCH_INDEX_ENTRY_TYPE: &str = "vf_intents_search_index";
+pub const INTENT_SEARCH_INDEX_ID: &str = "all_vf_intents";
+pub const INTENT_SEARCH_INDEX_ENTRY_LINK_TYPE: &str = "vf_intent_search_index";
+
+pub const INTENT_CLASSIFICATION_INDEX_ENTRY_TYPE: &str = "vf_intents_classification_index";
+pub const INTENT_CLASSIFICATION_INDEX_ID: &str = "all_vf_intents";
+pub const INTENT_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE: &str = "vf_intent_classification_index";
+
+pub const INTENT_SCOPE_INDEX_ENTRY_TYPE: &str = "vf_intents_scope_index";
+pub const INTENT_SCOPE_INDEX_ID: &str = "all_vf_intents";
+pub const INTENT_SCOPE_INDEX_ENTRY_LINK_TYPE: &str = "vf_intent_scope_index";
+
+pub const INTENT_TIME_INDEX_ENTRY_TYPE: &str = "vf_intents_time_index";
+pub const INTENT_TIME_INDEX_ID: &str = "all_vf_intents";
+pub const INTENT_TIME_INDEX_BUCKET_LINK_TYPE: &str = "vf_intents_time_bucket";
+pub const INTENT_TIME_INDEX_ENTRY_LINK_TYPE: &str = "vf_intent_time_index";
+
+pub const INTENT_DUE_INDEX_ENTRY_TYPE: &str = "vf_intents_due_index";
+pub const INTENT_DUE_INDEX_ID: &str = "all_vf_intents";
+pub const INTENT_DUE_INDEX_BUCKET_LINK_TYPE: &str = "vf_intents_due_bucket";
+pub const INTENT_DUE_INDEX_ENTRY_LINK_TYPE: &str = "vf_intent_due_index";
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
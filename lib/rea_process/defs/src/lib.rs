@@ -115,3 +115,121 @@ pub fn base_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn search_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROCESS_SEARCH_INDEX_ENTRY_TYPE,
+        description: "Anchors for the words in the names & notes of all Processes stored in this zome, for finding them by text search.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROCESS_BASE_ENTRY_TYPE,
+                link_type: PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn classification_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE,
+        description: "Anchors for each classification URL of all Processes stored in this zome, for finding them by classification.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROCESS_BASE_ENTRY_TYPE,
+                link_type: PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn scope_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROCESS_SCOPE_INDEX_ENTRY_TYPE,
+        description: "Anchors for each scope which Processes stored in this zome are `in_scope_of`, for listing the contents of a scope.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROCESS_BASE_ENTRY_TYPE,
+                link_type: PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn time_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROCESS_TIME_INDEX_ENTRY_TYPE,
+        description: "Root & time bucket anchors which connect to all Processes stored in this zome, ordered by their beginning or end time.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROCESS_TIME_INDEX_ENTRY_TYPE,
+                link_type: PROCESS_TIME_INDEX_BUCKET_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                PROCESS_BASE_ENTRY_TYPE,
+                link_type: PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
@@ -8,28 +8,46 @@
  */
 use std::borrow::Cow;
 use hdk::{
-    error::{ ZomeApiResult, ZomeApiError },
+    THIS_INSTANCE,
+    error::ZomeApiResult,
 };
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     records::{
         create_record,
         read_record_entry,
+        read_active_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
-        delete_record,
+        check_not_deleted,
+        tombstone_record,
+        restore_record,
+        exclude_deleted_records,
     },
     links::{
         get_linked_addresses_as_type,
         get_linked_addresses_with_foreign_key_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_index,
     },
     remote_indexes::{
         RemoteEntryLinkResponse,
         handle_sync_direct_remote_index_destination,
     },
+    search::{ create_search_index, update_search_index },
+    anchors::{
+        create_category_index, update_category_index,
+        create_time_index, update_time_index,
+    },
+    integrity::{ IndexDefinition, check_record_indexes },
+    delete_policies::{ ReferencePolicy, delete_record_with_policies },
+    entries::{ build_records_response, get_entry_revision },
+    schema::{ migrate_entries, reindex_records, MigrationReport },
+    rpc::RemoteCapability,
 };
 
 use vf_core::type_aliases::{
@@ -59,6 +77,26 @@ use hc_zome_rea_intent_storage_consts::{
     INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
 };
 
+/// Zome API methods of other zomes & DNAs which the process zome calls, requested upon `init`
+///
+pub const PROCESS_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: THIS_INSTANCE,
+        zome: "economic_event",
+        functions: &["delete_event", "update_event"],
+    },
+    RemoteCapability {
+        dna: BRIDGED_PLANNING_DHT,
+        zome: "commitment",
+        functions: &["delete_commitment", "update_commitment"],
+    },
+    RemoteCapability {
+        dna: BRIDGED_PLANNING_DHT,
+        zome: "intent",
+        functions: &["delete_intent", "update_intent"],
+    },
+];
+
 pub fn receive_create_process(process: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_process(&process)
 }
@@ -67,19 +105,68 @@ pub fn receive_get_process(address: ProcessAddress) -> ZomeApiResult<ResponseDat
     handle_get_process(&address)
 }
 
+pub fn receive_get_process_history(address: ProcessAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_process_history(&address)
+}
+
 pub fn receive_update_process(process: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_process(&process)
 }
 
-pub fn receive_delete_process(address: ProcessAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+pub fn receive_delete_process(address: ProcessAddress) -> ZomeApiResult<DeleteReport> {
+    handle_delete_process(&address)
+}
+
+pub fn receive_restore_process(address: ProcessAddress) -> ZomeApiResult<ResponseData> {
+    handle_restore_process(&address)
 }
 
-pub fn receive_query_processes(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_processes(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_processes(&params)
 }
 
-pub fn receive_link_committed_inputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
+pub fn receive_get_processes_in_scope(scope: String) -> ZomeApiResult<QueryResponse> {
+    handle_query_processes(&QueryParams { in_scope_of: Some(scope), ..QueryParams::default() })
+}
+
+pub fn receive_check_process_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(PROCESS_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
+            EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
+            EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+            COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+            COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+            INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+            INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
+pub fn receive_migrate_process_entries() -> ZomeApiResult<MigrationReport> {
+    let report = migrate_entries::<Entry, _>(PROCESS_ENTRY_TYPE)?;
+    // processes created before their end time was indexed separately are added to the end time index
+    Ok(reindex_records(PROCESS_BASE_ENTRY_TYPE, PROCESS_INITIAL_ENTRY_LINK_TYPE, report, |address: &ProcessAddress, entry: &Entry| {
+        create_time_indexes(entry, address)
+    })?)
+}
+
+pub fn receive_link_committed_inputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> GraphAPIResult<RemoteEntryLinkResponse> {
     handle_sync_direct_remote_index_destination(
         COMMITMENT_BASE_ENTRY_TYPE,
         COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
@@ -88,7 +175,7 @@ pub fn receive_link_committed_inputs(base_entry: CommitmentAddress, target_entri
     )
 }
 
-pub fn receive_link_committed_outputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
+pub fn receive_link_committed_outputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> GraphAPIResult<RemoteEntryLinkResponse> {
     handle_sync_direct_remote_index_destination(
         COMMITMENT_BASE_ENTRY_TYPE,
         COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
@@ -97,7 +184,7 @@ pub fn receive_link_committed_outputs(base_entry: CommitmentAddress, target_entr
     )
 }
 
-pub fn receive_link_intended_inputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
+pub fn receive_link_intended_inputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> GraphAPIResult<RemoteEntryLinkResponse> {
     handle_sync_direct_remote_index_destination(
         INTENT_BASE_ENTRY_TYPE,
         INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
@@ -106,7 +193,7 @@ pub fn receive_link_intended_inputs(base_entry: IntentAddress, target_entries: V
     )
 }
 
-pub fn receive_link_intended_outputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
+pub fn receive_link_intended_outputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> GraphAPIResult<RemoteEntryLinkResponse> {
     handle_sync_direct_remote_index_destination(
         INTENT_BASE_ENTRY_TYPE,
         INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
@@ -118,7 +205,23 @@ pub fn receive_link_intended_outputs(base_entry: IntentAddress, target_entries:
 // :TODO: move to hdk_graph_helpers module
 
 fn handle_get_process(address: &ProcessAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(address, &read_record_entry(address)?, get_link_fields(address)))
+    Ok(construct_response(address, &read_active_record_entry(address, PROCESS_INITIAL_ENTRY_LINK_TYPE)?, get_link_fields(address)))
+}
+
+fn handle_get_process_history(address: &ProcessAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, PROCESS_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (
+            None, None,
+            None,
+            None, None,
+            None, None,
+            None, None,
+            None,
+            None, None,
+        ))))
+        .collect())
 }
 
 fn handle_create_process(process: &CreateRequest) -> ZomeApiResult<ResponseData> {
@@ -127,89 +230,257 @@ fn handle_create_process(process: &CreateRequest) -> ZomeApiResult<ResponseData>
         PROCESS_INITIAL_ENTRY_LINK_TYPE,
         process.to_owned(),
     )?;
+    create_search_index(
+        &PROCESS_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROCESS_SEARCH_INDEX_ID, PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), PROCESS_CLASSIFICATION_INDEX_ID, PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &PROCESS_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROCESS_SCOPE_INDEX_ID, PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry_resp), base_address.as_ref(),
+    )?;
+    create_time_indexes(&entry_resp, &base_address)?;
     Ok(construct_response(&base_address, &entry_resp, get_link_fields(&base_address)))
 }
 
 fn handle_update_process(process: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = process.get_id();
+    check_not_deleted(base_address.as_ref(), PROCESS_INITIAL_ENTRY_LINK_TYPE)?;
+    let prev_entry: Entry = read_record_entry(base_address)?;
     let new_entry = update_record(PROCESS_ENTRY_TYPE, base_address, process)?;
+    update_search_index(
+        &PROCESS_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROCESS_SEARCH_INDEX_ID, PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&prev_entry), &get_search_text(&new_entry), base_address.as_ref(),
+    )?;
+    update_category_index(
+        &PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), PROCESS_CLASSIFICATION_INDEX_ID, PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+        &get_classifications(&prev_entry), &get_classifications(&new_entry), base_address.as_ref(),
+    )?;
+    update_category_index(
+        &PROCESS_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROCESS_SCOPE_INDEX_ID, PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&prev_entry), &get_scopes(&new_entry), base_address.as_ref(),
+    )?;
+    update_time_index(
+        &PROCESS_TIME_INDEX_ENTRY_TYPE.to_string(), PROCESS_TIME_INDEX_ID,
+        PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+        get_process_time(&prev_entry).as_ref().map(String::as_str), get_process_time(&new_entry).as_ref().map(String::as_str), base_address.as_ref(),
+    )?;
+    update_time_index(
+        &PROCESS_TIME_INDEX_ENTRY_TYPE.to_string(), PROCESS_END_TIME_INDEX_ID,
+        PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+        get_process_end_time(&prev_entry).as_ref().map(String::as_str), get_process_end_time(&new_entry).as_ref().map(String::as_str), base_address.as_ref(),
+    )?;
     Ok(construct_response(&base_address, &new_entry, get_link_fields(base_address)))
 }
 
-fn handle_query_processes(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProcessAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_delete_process(address: &ProcessAddress) -> ZomeApiResult<DeleteReport> {
+    // :NOTE: references cleared by `Nullify` policies are not reinstated by `restore_process`
+    Ok(delete_record_with_policies(address.as_ref(), &[
+        ReferencePolicy::local(PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG, PROCESS_EVENT_INPUTS_DELETE_POLICY)
+            .with_handler(THIS_INSTANCE, "economic_event", "delete_event", "update_event", "event", "inputOf"),
+        ReferencePolicy::local(PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG, PROCESS_EVENT_OUTPUTS_DELETE_POLICY)
+            .with_handler(THIS_INSTANCE, "economic_event", "delete_event", "update_event", "event", "outputOf"),
+        ReferencePolicy::remote(PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG, PROCESS_COMMITMENT_INPUTS_DELETE_POLICY)
+            .with_handler(BRIDGED_PLANNING_DHT, "commitment", "delete_commitment", "update_commitment", "commitment", "inputOf"),
+        ReferencePolicy::remote(PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG, PROCESS_COMMITMENT_OUTPUTS_DELETE_POLICY)
+            .with_handler(BRIDGED_PLANNING_DHT, "commitment", "delete_commitment", "update_commitment", "commitment", "outputOf"),
+        ReferencePolicy::remote(PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG, PROCESS_INTENT_INPUTS_DELETE_POLICY)
+            .with_handler(BRIDGED_PLANNING_DHT, "intent", "delete_intent", "update_intent", "intent", "inputOf"),
+        ReferencePolicy::remote(PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG, PROCESS_INTENT_OUTPUTS_DELETE_POLICY)
+            .with_handler(BRIDGED_PLANNING_DHT, "intent", "delete_intent", "update_intent", "intent", "outputOf"),
+    ], || {
+        // indexes are retained, so that deleted processes can still be queried with `include_deleted`
+        tombstone_record::<Entry, _>(address, PROCESS_INITIAL_ENTRY_LINK_TYPE)
+    })?)
+}
+
+fn handle_restore_process(address: &ProcessAddress) -> ZomeApiResult<ResponseData> {
+    let (restored, entry): (bool, Entry) = restore_record(address, PROCESS_INITIAL_ENTRY_LINK_TYPE)?;
+
+    // reinstate any indexes removed when the process was deleted by earlier versions
+    if restored {
+        create_search_index(
+            &PROCESS_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROCESS_SEARCH_INDEX_ID, PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(&entry), address.as_ref(),
+        )?;
+        create_category_index(
+            &PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE.to_string(), PROCESS_CLASSIFICATION_INDEX_ID, PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+            &get_classifications(&entry), address.as_ref(),
+        )?;
+        create_category_index(
+            &PROCESS_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROCESS_SCOPE_INDEX_ID, PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE,
+            &get_scopes(&entry), address.as_ref(),
+        )?;
+        create_time_indexes(&entry, address)?;
+    }
+    Ok(construct_response(address, &entry, get_link_fields(address)))
+}
 
-    // :TODO: proper search logic, not mutually exclusive ID filters
+fn handle_query_processes(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.inputs {
         Some(inputs) => {
-            entries_result = query_direct_index_with_foreign_key(inputs, EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG);
+            filters.push(IndexQuery::direct(inputs, EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG));
         },
         _ => (),
     };
     match &params.outputs {
         Some(outputs) => {
-            entries_result = query_direct_index_with_foreign_key(outputs, EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG);
+            filters.push(IndexQuery::direct(outputs, EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG));
         },
         _ => (),
     };
     match &params.committed_inputs {
         Some(committed_inputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 committed_inputs, COMMITMENT_BASE_ENTRY_TYPE,
                 COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.committed_outputs {
         Some(committed_outputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 committed_outputs, COMMITMENT_BASE_ENTRY_TYPE,
                 COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.intended_inputs {
         Some(intended_inputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 intended_inputs, INTENT_BASE_ENTRY_TYPE,
                 INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
     match &params.intended_outputs {
         Some(intended_outputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
+            filters.push(IndexQuery::direct_remote(
                 intended_outputs, INTENT_BASE_ENTRY_TYPE,
                 INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
 
+    match &params.search {
+        Some(search) => {
+            filters.push(IndexQuery::search(
+                PROCESS_SEARCH_INDEX_ENTRY_TYPE, PROCESS_SEARCH_INDEX_ID, PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                search,
+            ));
+        },
+        _ => (),
+    };
+    match &params.classified_as {
+        Some(classified_as) => {
+            filters.push(IndexQuery::category(
+                PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE, PROCESS_CLASSIFICATION_INDEX_ID, PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE,
+                &String::from(classified_as.to_owned()),
+            ));
+        },
+        _ => (),
+    };
+    match &params.in_scope_of {
+        Some(in_scope_of) => {
+            filters.push(IndexQuery::category(
+                PROCESS_SCOPE_INDEX_ENTRY_TYPE, PROCESS_SCOPE_INDEX_ID, PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                in_scope_of,
+            ));
+        },
+        _ => (),
+    };
+    // processes overlapping the requested range began before its end and ended after its start
+    if params.end_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            PROCESS_TIME_INDEX_ENTRY_TYPE, PROCESS_TIME_INDEX_ID,
+            PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+            None, params.end_date.to_owned(),
+        ));
+    }
+    if params.start_date.is_some() {
+        filters.push(IndexQuery::time_range(
+            PROCESS_TIME_INDEX_ENTRY_TYPE, PROCESS_END_TIME_INDEX_ID,
+            PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+            params.start_date.to_owned(), None,
+        ));
+    }
+
     // :TODO: unplanned_economic_events, working_agents
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                            get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+    let entries_result: GraphAPIResult<Vec<(ProcessAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    let mut entries = entries_result?;
+
+    // omit records which have been marked as deleted, unless requested
+    if !params.include_deleted.unwrap_or(false) {
+        entries = exclude_deleted_records(entries, PROCESS_INITIAL_ENTRY_LINK_TYPE);
+    }
+
+    Ok(build_records_response(entries, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
+}
+
+/// Text of a process which is indexed for searching
+fn get_search_text(e: &Entry) -> String {
+    match &e.note {
+        Some(note) => format!("{} {}", e.name, note),
+        None => e.name.to_owned(),
+    }
+}
+
+/// Classification URLs of a process which are indexed for querying
+fn get_classifications(e: &Entry) -> Vec<String> {
+    e.classified_as.to_owned().unwrap_or_default().into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Scopes which a process is indexed under for querying
+fn get_scopes(e: &Entry) -> Vec<String> {
+    e.in_scope_of.to_owned().unwrap_or_default()
+}
+
+/// Time which a process is indexed under for querying by the end of a date range,
+/// being its `has_beginning`, or `has_end` if no beginning is set
+fn get_process_time(e: &Entry) -> Option<String> {
+    e.has_beginning.as_ref()
+        .or(e.has_end.as_ref())
+        .map(|time| time.as_ref().to_string())
+}
+
+/// Time which a process is indexed under for querying by the start of a date range,
+/// being its `has_end`, or `has_beginning` if no end is set
+fn get_process_end_time(e: &Entry) -> Option<String> {
+    e.has_end.as_ref()
+        .or(e.has_beginning.as_ref())
+        .map(|time| time.as_ref().to_string())
+}
+
+/// Add a process to the time indexes for its beginning & end
+fn create_time_indexes(e: &Entry, address: &ProcessAddress) -> GraphAPIResult<()> {
+    if let Some(time) = get_process_time(e) {
+        create_time_index(
+            &PROCESS_TIME_INDEX_ENTRY_TYPE.to_string(), PROCESS_TIME_INDEX_ID,
+            PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+            &time, address.as_ref(),
+        )?;
+    }
+    if let Some(time) = get_process_end_time(e) {
+        create_time_index(
+            &PROCESS_TIME_INDEX_ENTRY_TYPE.to_string(), PROCESS_END_TIME_INDEX_ID,
+            PROCESS_TIME_INDEX_BUCKET_LINK_TYPE, PROCESS_TIME_INDEX_ENTRY_LINK_TYPE,
+            &time, address.as_ref(),
+        )?;
     }
+    Ok(())
 }
 
 /// Create response from input DHT primitives
@@ -236,6 +507,7 @@ pub fn construct_response<'a>(
         process: Response {
             // entry fields
             id: address.to_owned(),
+            revision_id: get_entry_revision(PROCESS_ENTRY_TYPE, e),
             name: e.name.to_owned(),
             has_beginning: e.has_beginning.to_owned(),
             has_end: e.has_end.to_owned(),
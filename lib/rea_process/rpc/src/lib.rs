@@ -12,6 +12,9 @@ use holochain_json_derive::{ DefaultJson };
 use hdk_graph_helpers::{
     MaybeUndefined,
     maybe_undefined::{ default_false },
+    records::RecordRevision,
+    entries::RecordsResponse,
+    record_interface::RevisionedUpdate,
 };
 
 use vf_core::type_aliases::{
@@ -23,6 +26,7 @@ use vf_core::type_aliases::{
     CommitmentAddress,
     IntentAddress,
     AgentAddress,
+    Address,
 };
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
@@ -35,6 +39,8 @@ pub use vf_core::type_aliases::ProcessAddress;
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ProcessAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_beginning: Option<Timestamp>,
@@ -91,6 +97,15 @@ pub struct ResponseData {
     pub process: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<ProcessAddress, ResponseData>;
+
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+pub use hdk_graph_helpers::delete_policies::DeleteReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -132,6 +147,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: ProcessAddress,
     #[serde(default)]
+    pub revision_id: Option<Address>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub has_beginning: MaybeUndefined<Timestamp>,
@@ -163,9 +180,15 @@ impl<'a> UpdateRequest {
     // :TODO: accessors for other field data
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 //---------------- QUERY FILTER REQUEST ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
     pub inputs: Option<EventAddress>,
@@ -176,4 +199,20 @@ pub struct QueryParams {
     pub intended_inputs: Option<IntentAddress>,
     pub intended_outputs: Option<IntentAddress>,
     pub working_agents: Option<AgentAddress>,
+    /// Classification URL which processes must be `classified_as`
+    pub classified_as: Option<ExternalURL>,
+    /// Scope which processes must be `in_scope_of`
+    pub in_scope_of: Option<String>,
+    /// Only return processes still running at or after this time, i.e. those whose `has_end` is
+    /// not earlier. Processes without an end are timed by their `has_beginning`.
+    pub start_date: Option<String>,
+    /// Only return processes begun at or before this time, i.e. those whose `has_beginning` is not
+    /// later. Processes without a beginning are timed by their `has_end`. A date without a time
+    /// includes the entire day.
+    pub end_date: Option<String>,
+    /// Text to find in the `name` or `note` of processes. Terms match words by prefix,
+    /// or as whole words if enclosed in double quotes.
+    pub search: Option<String>,
+    /// Set to include records which have been marked as deleted
+    pub include_deleted: Option<bool>,
 }
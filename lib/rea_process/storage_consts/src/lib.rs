@@ -5,6 +5,8 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::delete_policies::DeletePolicy;
+
 pub const PROCESS_BASE_ENTRY_TYPE: &str = "vf_process_baseurl";
 pub const PROCESS_INITIAL_ENTRY_LINK_TYPE: &str = "vf_process_entry";
 pub const PROCESS_ENTRY_TYPE: &str = "vf_process";
@@ -20,3 +22,31 @@ pub const PROCESS_INTENT_INPUTS_LINK_TYPE: &str = "vf_process_intended_inputs";
 pub const PROCESS_INTENT_INPUTS_LINK_TAG: &str = "intended_inputs";
 pub const PROCESS_INTENT_OUTPUTS_LINK_TYPE: &str = "vf_process_intended_outputs";
 pub const PROCESS_INTENT_OUTPUTS_LINK_TAG: &str = "intended_outputs";
+
+pub const PROCESS_SEARCH_INDEX_ENTRY_TYPE: &str = "vf_processes_search_index";
+pub const PROCESS_SEARCH_INDEX_ID: &str = "all_vf_processes";
+pub const PROCESS_SEARCH_INDEX_ENTRY_LINK_TYPE: &str = "vf_process_search_index";
+
+pub const PROCESS_CLASSIFICATION_INDEX_ENTRY_TYPE: &str = "vf_processes_classification_index";
+pub const PROCESS_CLASSIFICATION_INDEX_ID: &str = "all_vf_processes";
+pub const PROCESS_CLASSIFICATION_INDEX_ENTRY_LINK_TYPE: &str = "vf_process_classification_index";
+
+pub const PROCESS_SCOPE_INDEX_ENTRY_TYPE: &str = "vf_processes_scope_index";
+pub const PROCESS_SCOPE_INDEX_ID: &str = "all_vf_processes";
+pub const PROCESS_SCOPE_INDEX_ENTRY_LINK_TYPE: &str = "vf_process_scope_index";
+
+pub const PROCESS_TIME_INDEX_ENTRY_TYPE: &str = "vf_processes_time_index";
+pub const PROCESS_TIME_INDEX_ID: &str = "all_vf_processes";
+pub const PROCESS_END_TIME_INDEX_ID: &str = "all_vf_process_ends";
+pub const PROCESS_TIME_INDEX_BUCKET_LINK_TYPE: &str = "vf_processes_time_bucket";
+pub const PROCESS_TIME_INDEX_ENTRY_LINK_TYPE: &str = "vf_process_time_index";
+
+// handling of referencing records when a process is deleted
+pub const PROCESS_EVENT_INPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Restrict;
+pub const PROCESS_EVENT_OUTPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Restrict;
+pub const PROCESS_COMMITMENT_INPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Nullify;
+pub const PROCESS_COMMITMENT_OUTPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Nullify;
+pub const PROCESS_INTENT_INPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Nullify;
+pub const PROCESS_INTENT_OUTPUTS_DELETE_POLICY: DeletePolicy = DeletePolicy::Nullify;
+
+pub const BRIDGED_PLANNING_DHT: &str = "vf_planning";
@@ -6,15 +6,21 @@
  *
  * @package Holo-REA
  */
-use hdk::error::{ ZomeApiResult, ZomeApiError };
+use hdk::error::ZomeApiResult;
 
 use hdk_graph_helpers::{
+    GraphAPIResult, GraphHelperError,
     records::{
         create_record,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
+    integrity::check_record_indexes,
+    schema::{ migrate_entries, MigrationReport },
+    entries::build_records_response,
 };
 
 use hc_zome_rea_process_specification_storage_consts::*;
@@ -33,63 +39,51 @@ pub fn receive_create_process_specification(process_specification: CreateRequest
 pub fn receive_get_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<ResponseData> {
     Ok(construct_response(&address, &read_record_entry(&address)?))
 }
+pub fn receive_get_process_specification_history(address: ProcessSpecificationAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_process_specification_history(&address)
+}
 pub fn receive_update_process_specification(process_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_process_specification(&process_specification)
 }
 pub fn receive_delete_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
-pub fn receive_query_process_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_process_specifications(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_process_specifications(&params)
 }
 
+pub fn receive_check_process_specification_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(PROCESS_SPECIFICATION_BASE_ENTRY_TYPE, &[], repair)?)
+}
+
+pub fn receive_migrate_process_specification_entries() -> ZomeApiResult<MigrationReport> {
+    Ok(migrate_entries::<Entry, _>(PROCESS_SPECIFICATION_ENTRY_TYPE)?)
+}
+
+fn handle_get_process_specification_history(address: &ProcessSpecificationAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, PROCESS_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_update_process_specification(process_specification: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = process_specification.get_id();
     let new_entry = update_record(PROCESS_SPECIFICATION_ENTRY_TYPE, &address, process_specification)?;
     Ok(construct_response(address, &new_entry))
 }
 
-fn handle_query_process_specifications(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(ProcessSpecificationAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_process_specifications(_params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let entries_result: GraphAPIResult<Vec<(ProcessSpecificationAddress, GraphAPIResult<Entry>)>> = Err(GraphHelperError::Internal("No results found".to_string()));
 
     // :TODO: implement "all" query and filters
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        Err(e) => Err(e)
-    }
-}
-
-
-/// Create response from input DHT primitives
-pub fn construct_response<'a>(
-    address: &ProcessSpecificationAddress, e: &Entry,
-) -> ResponseData {
-    ResponseData {
-        process_specification: Response {
-            id: address.to_owned(),
-            name: e.name.to_owned(),
-            note: e.note.to_owned(),
-        }
-    }
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
 
 //---------------- READ ----------------
 
-// @see construct_response
+// @see hc_zome_rea_process_specification_rpc::construct_response
 // pub fn get_link_fields<'a>(process_specification: &ProcessSpecificationAddress) -> (
 //     // :TODO:
 // ) {
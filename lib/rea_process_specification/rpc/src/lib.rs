@@ -14,66 +14,34 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    records::RecordRevision,
+    entries::RecordsResponse,
+};
+use hdk_graph_helpers_derive::VfRecord;
 
-//---------------- EXTERNAL RECORD STRUCTURE ----------------
+//---------------- RECORD STRUCTURE ----------------
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_core::type_aliases::{ ProcessSpecificationAddress };
 
-/// I/O struct to describe the complete output record, including all managed link fields
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Response {
-    pub id: ProcessSpecificationAddress,
+/// Field definitions for the record. `Entry`, `Response`, `ResponseData`, `CreateRequest`,
+/// `UpdateRequest` and `construct_response` are generated from this struct.
+///
+#[derive(VfRecord)]
+#[vf_record(entry_type = "hc_zome_rea_process_specification_storage_consts::PROCESS_SPECIFICATION_ENTRY_TYPE", derive_default)]
+pub struct ProcessSpecification {
     pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
 }
 
-/// I/O struct to describe what is returned outside the gateway
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ResponseData {
-    pub process_specification: Response,
-}
-
-//---------------- CREATE REQUEST ----------------
-
-/// I/O struct to describe the complete input record, including all managed links
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Default, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CreateRequest {
-    pub name: String,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-}
-
-impl<'a> CreateRequest {
-    // :TODO: accessors for field data
-}
-
-//---------------- UPDATE REQUEST ----------------
-
-/// I/O struct to describe the complete input record, including all managed links
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct UpdateRequest {
-    pub id: ProcessSpecificationAddress,
-    #[serde(default)]
-    pub name: MaybeUndefined<String>,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-}
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
 
-impl<'a> UpdateRequest {
-    pub fn get_id(&'a self) -> &ProcessSpecificationAddress {
-        &self.id
-    }
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<ProcessSpecificationAddress, ResponseData>;
 
-    // :TODO: accessors for other field data
-}
+pub use hdk_graph_helpers::integrity::IntegrityReport;
 
 //---------------- QUERY FILTER REQUEST ----------------
 
@@ -6,48 +6,8 @@
  *
  * @package Holo-REA
  */
-extern crate serde;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde_json;
-
-use holochain_json_api::{ json::JsonString, error::JsonError };
-use holochain_json_derive::{ DefaultJson };
-
-use hdk_graph_helpers::{
-    record_interface::Updateable,
-};
-
-use hc_zome_rea_process_specification_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Default, Clone)]
-pub struct Entry {
-    pub name: String,
-    pub note: Option<String>,
-}
-
-//---------------- CREATE ----------------
-
-/// Pick relevant fields out of I/O record into underlying DHT entry
-impl From<CreateRequest> for Entry {
-    fn from(e: CreateRequest) -> Entry {
-        Entry {
-            name: e.name.into(),
-            note: e.note.into(),
-        }
-    }
-}
-
-//---------------- UPDATE ----------------
-
-/// Handles update operations by merging any newly provided fields
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            name: if !e.name.is_some() { self.name.to_owned() } else { e.name.to_owned().unwrap() },
-            note: if e.note.is_undefined() { self.note.to_owned() } else { e.note.to_owned().into() },
-        }
-    }
-}
+// entry structure & create / update behaviour are derived from the record definition
+pub use hc_zome_rea_process_specification_rpc::Entry;
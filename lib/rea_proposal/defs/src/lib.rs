@@ -74,3 +74,55 @@ pub fn base_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn search_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROPOSAL_SEARCH_INDEX_ENTRY_TYPE,
+        description: "Anchors for the words in the names & notes of all Proposals stored in this zome, for finding them by text search.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROPOSAL_BASE_ENTRY_TYPE,
+                link_type: PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+pub fn scope_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: PROPOSAL_SCOPE_INDEX_ENTRY_TYPE,
+        description: "Anchors for each scope which Proposals stored in this zome are `in_scope_of`, for listing the contents of a scope.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                PROPOSAL_BASE_ENTRY_TYPE,
+                link_type: PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
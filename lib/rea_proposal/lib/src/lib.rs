@@ -11,13 +11,23 @@ use hdk::error::ZomeApiResult;
 use std::borrow::Cow;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     links::get_linked_addresses_as_type,
-    // local_indexes::query_direct_index_with_foreign_key,
+    local_indexes::{ IndexQuery, query_index, detach_direct_index, reattach_direct_index },
     // remote_indexes::{
     //   RemoteEntryLinkResponse,
     //   handle_sync_direct_remote_index_destination,
     // },
-    records::{create_record, delete_record, read_record_entry, update_record},
+    records::{
+        create_record, read_record_entry, read_active_record_entry, read_record_revisions, update_record,
+        check_not_deleted, tombstone_record, restore_record, RecordRevision,
+        exclude_deleted_records,
+    },
+    search::{ create_search_index, update_search_index },
+    anchors::{ create_category_index, update_category_index },
+    integrity::{ IndexDefinition, check_active_record_indexes },
+    schema::{ migrate_entries, reindex_records, MigrationReport },
+    entries::build_records_response,
 };
 
 use vf_core::type_aliases::{ProposedIntentAddress, ProposedToAddress};
@@ -25,6 +35,8 @@ use vf_core::type_aliases::{ProposedIntentAddress, ProposedToAddress};
 use hc_zome_rea_proposal_rpc::*;
 use hc_zome_rea_proposal_storage::*;
 use hc_zome_rea_proposal_storage_consts::*;
+use hc_zome_rea_proposed_intent_storage_consts::{ PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG };
+use hc_zome_rea_proposed_to_storage_consts::{ PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG };
 
 pub fn receive_create_proposal(proposal: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_proposal(&proposal)
@@ -34,26 +46,70 @@ pub fn receive_get_proposal(address: ProposalAddress) -> ZomeApiResult<ResponseD
     handle_get_proposal(&address)
 }
 
+pub fn receive_get_proposal_history(address: ProposalAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_proposal_history(&address)
+}
+
 pub fn receive_update_proposal(proposal: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_proposal(&proposal)
 }
 
 pub fn receive_delete_proposal(address: ProposalAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    handle_delete_proposal(&address)
+}
+
+pub fn receive_restore_proposal(address: ProposalAddress) -> ZomeApiResult<ResponseData> {
+    handle_restore_proposal(&address)
+}
+
+pub fn receive_check_proposal_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_active_record_indexes(PROPOSAL_BASE_ENTRY_TYPE, PROPOSAL_INITIAL_ENTRY_LINK_TYPE, &[
+        IndexDefinition::local(
+            PROPOSAL_PUBLISHES_LINK_TYPE, PROPOSAL_PUBLISHES_LINK_TAG,
+            PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
+        ),
+        IndexDefinition::local(
+            PROPOSAL_PUBLISHED_TO_LINK_TYPE, PROPOSAL_PUBLISHED_TO_LINK_TAG,
+            PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
+pub fn receive_migrate_proposal_entries() -> ZomeApiResult<MigrationReport> {
+    let report = migrate_entries::<Entry, _>(PROPOSAL_ENTRY_TYPE)?;
+    // proposals created before search indexing was introduced are added to the index
+    Ok(reindex_records(PROPOSAL_BASE_ENTRY_TYPE, PROPOSAL_INITIAL_ENTRY_LINK_TYPE, report, |address: &ProposalAddress, entry: &Entry| {
+        create_search_index(
+            &PROPOSAL_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SEARCH_INDEX_ID, PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(entry), address.as_ref(),
+        )
+    })?)
+}
+
+pub fn receive_query_proposals(params: QueryParams) -> ZomeApiResult<QueryResponse> {
+    handle_query_proposals(&params)
 }
 
-// pub fn receive_query_proposals(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-//     handle_query_proposals(&params)
-// }
+pub fn receive_get_proposals_in_scope(scope: String) -> ZomeApiResult<QueryResponse> {
+    handle_query_proposals(&QueryParams { in_scope_of: Some(scope), ..QueryParams::default() })
+}
 
 fn handle_get_proposal(address: &ProposalAddress) -> ZomeApiResult<ResponseData> {
     Ok(construct_response(
         address,
-        &read_record_entry(address)?,
+        &read_active_record_entry(address, PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?,
         get_link_fields(address),
     ))
 }
 
+fn handle_get_proposal_history(address: &ProposalAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry, (None, None))))
+        .collect())
+}
+
 fn handle_create_proposal(proposal: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ProposalAddress, Entry) = create_record(
         PROPOSAL_BASE_ENTRY_TYPE,
@@ -61,6 +117,14 @@ fn handle_create_proposal(proposal: &CreateRequest) -> ZomeApiResult<ResponseDat
         PROPOSAL_INITIAL_ENTRY_LINK_TYPE,
         proposal.to_owned(),
     )?;
+    create_search_index(
+        &PROPOSAL_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SEARCH_INDEX_ID, PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&entry_resp), base_address.as_ref(),
+    )?;
+    create_category_index(
+        &PROPOSAL_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SCOPE_INDEX_ID, PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&entry_resp), base_address.as_ref(),
+    )?;
     Ok(construct_response(
         &base_address,
         &entry_resp,
@@ -70,7 +134,17 @@ fn handle_create_proposal(proposal: &CreateRequest) -> ZomeApiResult<ResponseDat
 
 fn handle_update_proposal(proposal: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = proposal.get_id();
+    check_not_deleted(base_address.as_ref(), PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?;
+    let prev_entry: Entry = read_record_entry(base_address)?;
     let new_entry = update_record(PROPOSAL_ENTRY_TYPE, base_address, proposal)?;
+    update_search_index(
+        &PROPOSAL_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SEARCH_INDEX_ID, PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&prev_entry), &get_search_text(&new_entry), base_address.as_ref(),
+    )?;
+    update_category_index(
+        &PROPOSAL_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SCOPE_INDEX_ID, PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE,
+        &get_scopes(&prev_entry), &get_scopes(&new_entry), base_address.as_ref(),
+    )?;
     Ok(construct_response(
         base_address,
         &new_entry,
@@ -78,82 +152,104 @@ fn handle_update_proposal(proposal: &UpdateRequest) -> ZomeApiResult<ResponseDat
     ))
 }
 
-/*
-fn handle_query_proposals(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProposalAddress, Option<Entry>)>> =
-        Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_delete_proposal(address: &ProposalAddress) -> ZomeApiResult<bool> {
+    // deleting a proposal twice is an error
+    let _entry: Entry = read_active_record_entry(address, PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?;
+
+    // indexes are retained, so that deleted proposals can still be queried with `include_deleted`.
+    // Links from the proposal are retained so that the proposed intents & recipients can be relinked upon restore
+    detach_direct_index(
+        address.as_ref(),
+        PROPOSAL_PUBLISHES_LINK_TYPE, PROPOSAL_PUBLISHES_LINK_TAG,
+        PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
+    )?;
+    detach_direct_index(
+        address.as_ref(),
+        PROPOSAL_PUBLISHED_TO_LINK_TYPE, PROPOSAL_PUBLISHED_TO_LINK_TAG,
+        PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG,
+    )?;
+
+    Ok(tombstone_record::<Entry, _>(address, PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?)
+}
+
+fn handle_restore_proposal(address: &ProposalAddress) -> ZomeApiResult<ResponseData> {
+    let (restored, entry): (bool, Entry) = restore_record(address, PROPOSAL_INITIAL_ENTRY_LINK_TYPE)?;
+
+    // reinstate any indexes removed when the proposal was deleted by earlier versions
+    if restored {
+        create_search_index(
+            &PROPOSAL_SEARCH_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SEARCH_INDEX_ID, PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(&entry), address.as_ref(),
+        )?;
+        create_category_index(
+            &PROPOSAL_SCOPE_INDEX_ENTRY_TYPE.to_string(), PROPOSAL_SCOPE_INDEX_ID, PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE,
+            &get_scopes(&entry), address.as_ref(),
+        )?;
+        reattach_direct_index(
+            address.as_ref(),
+            PROPOSAL_PUBLISHES_LINK_TYPE, PROPOSAL_PUBLISHES_LINK_TAG,
+            PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
+        )?;
+        reattach_direct_index(
+            address.as_ref(),
+            PROPOSAL_PUBLISHED_TO_LINK_TYPE, PROPOSAL_PUBLISHED_TO_LINK_TAG,
+            PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG,
+        )?;
+    }
+    Ok(construct_response(
+        address,
+        &entry,
+        get_link_fields(address),
+    ))
+}
+
+fn handle_query_proposals(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
-    match &params.publishes {
-        Some(publishes) => {
-            entries_result = query_direct_index_with_foreign_key(
-                publishes,
-                PROPOSAL_PUBLISHES_LINK_TYPE,
-                PROPOSAL_PUBLISHES_LINK_TAG,
-            );
+    match &params.in_scope_of {
+        Some(in_scope_of) => {
+            filters.push(IndexQuery::category(
+                PROPOSAL_SCOPE_INDEX_ENTRY_TYPE, PROPOSAL_SCOPE_INDEX_ID, PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE,
+                in_scope_of,
+            ));
         }
         _ => (),
     };
-
-    match &params.published_to {
-        Some(published_to) => {
-            entries_result = query_direct_index_with_foreign_key(
-                published_to,
-                PROPOSAL_PUBLISHED_TO_LINK_TYPE,
-                PROPOSAL_PUBLISHED_TO_LINK_TAG,
-            );
+    match &params.search {
+        Some(search) => {
+            filters.push(IndexQuery::search(
+                PROPOSAL_SEARCH_INDEX_ENTRY_TYPE, PROPOSAL_SEARCH_INDEX_ID, PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                search,
+            ));
         }
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(entries
-            .iter()
-            .map(|(entry_base_address, maybe_entry)| match maybe_entry {
-                Some(entry) => Ok(construct_response(
-                    entry_base_address,
-                    &entry,
-                    get_link_fields(entry_base_address),
-                )),
-                None => Err(ZomeApiError::Internal(
-                    "referenced entry not found".to_string(),
-                )),
-            })
-            .filter_map(Result::ok)
-            .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
+    // :TODO: publishes, published_to
+
+    let entries_result: GraphAPIResult<Vec<(ProposalAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    let mut entries = entries_result?;
+
+    // omit records which have been marked as deleted, unless requested
+    if !params.include_deleted.unwrap_or(false) {
+        entries = exclude_deleted_records(entries, PROPOSAL_INITIAL_ENTRY_LINK_TYPE);
     }
+
+    Ok(build_records_response(entries, |entry_base_address, entry| construct_response(entry_base_address, entry, get_link_fields(entry_base_address))))
 }
-*/
 
-/// Create response from input DHT primitives
-pub fn construct_response<'a>(
-    address: &ProposalAddress,
-    e: &Entry,
-    (publishes, published_to): (
-        Option<Cow<'a, Vec<ProposedIntentAddress>>>,
-        Option<Cow<'a, Vec<ProposedToAddress>>>,
-    ),
-) -> ResponseData {
-    ResponseData {
-        proposal: Response {
-            // entry fields
-            id: address.to_owned(),
-            name: e.name.to_owned(),
-            has_beginning: e.has_beginning.to_owned(),
-            has_end: e.has_end.to_owned(),
-            unit_based: e.unit_based.to_owned(),
-            created: e.created.to_owned(),
-            note: e.note.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
-            // link fields
-            publishes: publishes.map(Cow::into_owned),
-            published_to: published_to.map(Cow::into_owned),
-        },
-    }
+/// Text of a proposal which is indexed for searching
+fn get_search_text(e: &Entry) -> String {
+    vec![e.name.to_owned(), e.note.to_owned()].into_iter().flatten().collect::<Vec<String>>().join(" ")
+}
+
+/// Scopes which a proposal is indexed under for querying
+fn get_scopes(e: &Entry) -> Vec<String> {
+    e.in_scope_of.to_owned().unwrap_or_default()
 }
 
+/// Read link fields for passing to `construct_response`
 pub fn get_link_fields<'a>(
     proposal: &ProposalAddress,
 ) -> (
@@ -14,112 +14,59 @@ extern crate serde_json;
 use holochain_json_api::{error::JsonError, json::JsonString};
 use holochain_json_derive::DefaultJson;
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    records::RecordRevision,
+    entries::RecordsResponse,
+};
+use hdk_graph_helpers_derive::VfRecord;
 use vf_core::type_aliases::{ProposedIntentAddress, ProposedToAddress, Timestamp};
 
-//---------------- EXTERNAL RECORD STRUCTURE ----------------
+//---------------- RECORD STRUCTURE ----------------
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_core::type_aliases::ProposalAddress;
 
-/// I/O struct to describe the complete record, including all managed link fields
+/// Field definitions for the record. `Entry`, `Response`, `ResponseData`, `CreateRequest`,
+/// `UpdateRequest` and `construct_response` are generated from this struct.
 ///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Response {
-    pub id: ProposalAddress,
+#[derive(VfRecord)]
+#[vf_record(entry_type = "hc_zome_rea_proposal_storage_consts::PROPOSAL_ENTRY_TYPE")]
+pub struct Proposal {
     pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub has_beginning: Option<Timestamp>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub has_end: Option<Timestamp>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub unit_based: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[vf_record(immutable)]
     pub created: Option<Timestamp>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub in_scope_of: Option<Vec<String>>,
-    // links:
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub publishes: Option<Vec<ProposedIntentAddress>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub published_to: Option<Vec<ProposedToAddress>>,
+    //[TODO]:
+    //eligibleLocation: SpatialThing
+    #[vf_record(link)]
+    pub publishes: Vec<ProposedIntentAddress>,
+    #[vf_record(link)]
+    pub published_to: Vec<ProposedToAddress>,
 }
 
-/// I/O struct to describe what is returned outside the gateway.
-/// Responses are usually returned as named attributes in order to leave space
-/// for future additional return values.
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ResponseData {
-    pub proposal: Response,
-}
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
 
-//---------------- CREATE REQUEST ----------------
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<ProposalAddress, ResponseData>;
 
-/// I/O struct to describe the complete input record, including all managed links
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CreateRequest {
-    #[serde(default)]
-    pub name: MaybeUndefined<String>,
-    #[serde(default)]
-    pub has_beginning: MaybeUndefined<Timestamp>,
-    #[serde(default)]
-    pub has_end: MaybeUndefined<Timestamp>,
-    #[serde(default)]
-    pub unit_based: MaybeUndefined<bool>,
-    #[serde(default)]
-    pub created: MaybeUndefined<Timestamp>,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-    #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
-}
-
-impl<'a> CreateRequest {
-    // :TODO: accessors for field data
-}
+pub use hdk_graph_helpers::integrity::IntegrityReport;
 
-//---------------- UPDATE REQUEST ----------------
+//---------------- QUERY FILTER REQUEST ----------------
 
-/// I/O struct to describe the complete input record, including all managed links
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct UpdateRequest {
-    pub id: ProposalAddress,
-    #[serde(default)]
-    pub name: MaybeUndefined<String>,
-    #[serde(default)]
-    pub has_beginning: MaybeUndefined<Timestamp>,
-    #[serde(default)]
-    pub has_end: MaybeUndefined<Timestamp>,
-    #[serde(default)]
-    pub unit_based: MaybeUndefined<bool>,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-    #[serde(default)]
-    pub in_scope_of: MaybeUndefined<Vec<String>>,
-}
-
-impl<'a> UpdateRequest {
-    pub fn get_id(&'a self) -> &ProposalAddress {
-        &self.id
-    }
-
-    // :TODO: accessors for other field data
+pub struct QueryParams {
+    // :TODO: publishes, published_to
+    /// Scope which proposals must be `in_scope_of`
+    pub in_scope_of: Option<String>,
+    /// Text to find in the `name` or `note` of proposals. Terms match words by prefix,
+    /// or as whole words if enclosed in double quotes.
+    pub search: Option<String>,
+    /// Set to include records which have been marked as deleted
+    pub include_deleted: Option<bool>,
 }
-
-//---------------- QUERY FILTER REQUEST ----------------
-
-// #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-// #[serde(rename_all = "camelCase")]
-// pub struct QueryParams {
-//     pub publishes: Option<ProposedIntentAddress>,
-//     pub published_to: Option<ProposedToAddress>,
-// }
@@ -14,3 +14,11 @@ pub const PROPOSAL_PUBLISHES_LINK_TAG: &str = "publishes";
 
 pub const PROPOSAL_PUBLISHED_TO_LINK_TYPE: &str = "vf_proposal_published_to";
 pub const PROPOSAL_PUBLISHED_TO_LINK_TAG: &str = "published_to";
+
+pub const PROPOSAL_SEARCH_INDEX_ENTRY_TYPE: &str = "vf_proposals_search_index";
+pub const PROPOSAL_SEARCH_INDEX_ID: &str = "all_vf_proposals";
+pub const PROPOSAL_SEARCH_INDEX_ENTRY_LINK_TYPE: &str = "vf_proposal_search_index";
+
+pub const PROPOSAL_SCOPE_INDEX_ENTRY_TYPE: &str = "vf_proposals_scope_index";
+pub const PROPOSAL_SCOPE_INDEX_ID: &str = "all_vf_proposals";
+pub const PROPOSAL_SCOPE_INDEX_ENTRY_LINK_TYPE: &str = "vf_proposal_scope_index";
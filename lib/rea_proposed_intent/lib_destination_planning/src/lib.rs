@@ -6,13 +6,18 @@
  *
  * @package Holo-REA
  */
-use hdk::error::{ZomeApiError, ZomeApiResult};
+use hdk::error::ZomeApiResult;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     local_indexes::{
-        create_direct_index, delete_direct_index, query_direct_index_with_foreign_key
+        create_direct_index, delete_direct_index, IndexQuery, query_index
     },
-    records::{create_record, delete_record, read_record_entry},
+    records::{
+        create_record, delete_record, read_record_entry, read_record_revisions,
+        RecordRevision,
+    },
+    entries::{ build_records_response, get_entry_revision },
 };
 
 use hc_zome_rea_intent_storage_consts::*;
@@ -30,6 +35,10 @@ pub fn receive_get_proposed_intent(address: ProposedIntentAddress) -> ZomeApiRes
     handle_get_proposed_intent(&address)
 }
 
+pub fn receive_get_proposed_intent_history(address: ProposedIntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_proposed_intent_history(&address)
+}
+
 pub fn receive_delete_proposed_intent(address: ProposedIntentAddress) -> ZomeApiResult<bool> {
     let entry: Entry = read_record_entry(&address)?;
 
@@ -41,10 +50,10 @@ pub fn receive_delete_proposed_intent(address: ProposedIntentAddress) -> ZomeApi
         INTENT_PUBLISHED_IN_LINK_TYPE,
         INTENT_PUBLISHED_IN_LINK_TAG,
     );
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-pub fn receive_query_proposed_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_proposed_intents(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_proposed_intents(&params)
 }
 
@@ -52,6 +61,13 @@ fn handle_get_proposed_intent(address: &ProposedIntentAddress) -> ZomeApiResult<
     Ok(construct_response(address, &read_record_entry(address)?))
 }
 
+fn handle_get_proposed_intent_history(address: &ProposedIntentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, PROPOSED_INTENT_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_create_proposed_intent(proposed_intent: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ProposedIntentAddress, Entry) = create_record(
         PROPOSED_INTENT_BASE_ENTRY_TYPE,
@@ -71,37 +87,23 @@ fn handle_create_proposed_intent(proposed_intent: &CreateRequest) -> ZomeApiResu
     Ok(construct_response(&base_address, &entry_resp))
 }
 
-fn handle_query_proposed_intents(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProposedIntentAddress, Option<Entry>)>> =
-        Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_proposed_intents(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
-    // :TODO: replace with real query filter logic
     match &params.published_in {
         Some(published_in) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 published_in,
                 PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
                 PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
-            );
+            ));
         }
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(entries
-            .iter()
-            .map(|(entry_base_address, maybe_entry)| match maybe_entry {
-                Some(entry) => Ok(construct_response(entry_base_address, &entry)),
-                None => Err(ZomeApiError::Internal(
-                    "referenced entry not found".to_string(),
-                )),
-            })
-            .filter_map(Result::ok)
-            .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
-    }
+    let entries_result: GraphAPIResult<Vec<(ProposedIntentAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
 
 /// Create response from input DHT primitives
@@ -110,6 +112,7 @@ pub fn construct_response<'a>(address: &ProposedIntentAddress, e: &Entry) -> Res
         proposed_intent: Response {
             // entry fields
             id: address.to_owned(),
+            revision_id: get_entry_revision(PROPOSED_INTENT_ENTRY_TYPE, e),
             reciprocal: e.reciprocal,
             // link field
             published_in: e.published_in.to_owned(),
@@ -6,21 +6,27 @@
 *
 * @package Holo-REA
 */
-use hdk::error::{ZomeApiError, ZomeApiResult};
+use hdk::error::ZomeApiResult;
 
 use std::borrow::Cow;
 
 use vf_core::type_aliases::{ProposalAddress, ProposedToAddress};
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     links::get_linked_addresses_with_foreign_key_as_type,
     // remote_indexes::{
     // RemoteEntryLinkResponse,
     // handle_sync_direct_remote_index_destination,
     // },
     local_indexes::create_direct_index,
-    local_indexes::query_direct_index_with_foreign_key,
-    records::{create_record, delete_record, read_record_entry},
+    local_indexes::{ IndexQuery, query_index },
+    records::{
+        create_record, delete_record, read_record_entry, read_record_revisions,
+        RecordRevision,
+    },
+    integrity::{ IndexDefinition, check_record_indexes },
+    entries::{ build_records_response, get_entry_revision },
 };
 
 use hc_zome_rea_proposed_to_rpc::*;
@@ -37,18 +43,38 @@ pub fn receive_get_proposed_to(address: ProposedToAddress) -> ZomeApiResult<Resp
     handle_get_proposed_to(&address)
 }
 
+pub fn receive_get_proposed_to_history(address: ProposedToAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_proposed_to_history(&address)
+}
+
 pub fn receive_delete_proposed_to(address: ProposedToAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-pub fn receive_query_proposed_to(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_proposed_to(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_proposed_to(&params)
 }
 
+pub fn receive_check_proposed_to_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(PROPOSED_TO_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG,
+            PROPOSAL_PUBLISHED_TO_LINK_TYPE, PROPOSAL_PUBLISHED_TO_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
 fn handle_get_proposed_to(address: &ProposedToAddress) -> ZomeApiResult<ResponseData> {
     Ok(construct_response(address, &read_record_entry(address)?))
 }
 
+fn handle_get_proposed_to_history(address: &ProposedToAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, PROPOSED_TO_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_create_proposed_to(proposed_to: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ProposedToAddress, Entry) = create_record(
         PROPOSED_TO_BASE_ENTRY_TYPE,
@@ -67,36 +93,23 @@ fn handle_create_proposed_to(proposed_to: &CreateRequest) -> ZomeApiResult<Respo
     Ok(construct_response(&base_address, &entry_resp))
 }
 
-fn handle_query_proposed_to(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProposedToAddress, Option<Entry>)>> =
-        Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_proposed_to(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.proposed {
         Some(proposed) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 proposed,
                 PROPOSED_TO_PROPOSED_LINK_TYPE,
                 PROPOSED_TO_PROPOSED_LINK_TAG,
-            );
+            ));
         }
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(entries
-            .iter()
-            .map(|(entry_base_address, maybe_entry)| match maybe_entry {
-                Some(entry) => Ok(construct_response(entry_base_address, &entry)),
-                None => Err(ZomeApiError::Internal(
-                    "referenced entry not found".to_string(),
-                )),
-            })
-            .filter_map(Result::ok)
-            .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
-    }
+    let entries_result: GraphAPIResult<Vec<(ProposedToAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
 
 /// Create response from input DHT primitives
@@ -104,6 +117,7 @@ pub fn construct_response<'a>(address: &ProposedToAddress, e: &Entry) -> Respons
     ResponseData {
         proposed_to: Response {
             id: address.to_owned(),
+            revision_id: get_entry_revision(PROPOSED_TO_ENTRY_TYPE, e),
             proposed_to: e.proposed_to.to_owned(),
             proposed: e.proposed.to_owned(),
         },
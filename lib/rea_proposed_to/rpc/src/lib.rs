@@ -14,7 +14,12 @@ extern crate serde_json;
 use holochain_json_api::{error::JsonError, json::JsonString};
 use holochain_json_derive::DefaultJson;
 
-use vf_core::type_aliases::{AgentAddress, ProposalAddress};
+use hdk_graph_helpers::{
+    records::RecordRevision,
+    entries::RecordsResponse,
+};
+
+use vf_core::type_aliases::{Address, AgentAddress, ProposalAddress};
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
 
@@ -27,6 +32,8 @@ pub use vf_core::type_aliases::ProposedToAddress;
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ProposedToAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub proposed_to: AgentAddress,
     pub proposed: ProposalAddress,
 }
@@ -41,6 +48,14 @@ pub struct ResponseData {
     pub proposed_to: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<ProposedToAddress, ResponseData>;
+
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -11,21 +11,20 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-use holochain_json_api::{error::JsonError, json::JsonString};
-use holochain_json_derive::DefaultJson;
-
 use vf_core::type_aliases::{AgentAddress, ProposalAddress};
 
 use hc_zome_rea_proposed_to_rpc::CreateRequest;
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Entry {
     pub proposed_to: AgentAddress,
     pub proposed: ProposalAddress,
 }
 
+hdk_graph_helpers::versioned_entry!(Entry);
+
 //---------------- CREATE ----------------
 
 /// Pick relevant fields out of I/O record into underlying DHT entry
@@ -54,3 +54,29 @@ pub fn base_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn search_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE,
+        description: "Anchors for the words in the names & notes of all Resource Specifications stored in this zome, for finding them by text search.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+                link_type: RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
@@ -6,26 +6,43 @@
  *
  * @package Holo-REA
  */
-use std::borrow::Cow;
-use hdk::error::{ ZomeApiResult, ZomeApiError };
+use hdk::{
+    error::ZomeApiResult,
+};
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     records::{
         create_record,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
-};
-
-use vf_core::type_aliases::{
-    ResourceAddress,
+    local_indexes::{ IndexQuery, query_index },
+    search::{ create_search_index, update_search_index, delete_search_index },
+    integrity::check_record_indexes,
+    schema::{ migrate_entries, reindex_records, MigrationReport },
+    delete_policies::{ ReferencePolicy, delete_record_with_policies },
+    rpc::RemoteCapability,
+    entries::build_records_response,
 };
 
 use hc_zome_rea_resource_specification_storage_consts::*;
 use hc_zome_rea_resource_specification_storage::*;
 use hc_zome_rea_resource_specification_rpc::*;
 
+/// Zome API methods of other DNAs which the resource specification zome calls, requested upon `init`
+///
+pub const RESOURCE_SPECIFICATION_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "resource_specification",
+        functions: &["get_conforming_resource_ids"],
+    },
+];
+
 pub fn receive_create_resource_specification(resource_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ResourceSpecificationAddress, Entry) = create_record(
         ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
@@ -33,77 +50,111 @@ pub fn receive_create_resource_specification(resource_specification: CreateReque
         ECONOMIC_RESOURCE_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE,
         resource_specification.to_owned(),
     )?;
-    Ok(construct_response(&base_address, &entry_resp, None))
+    create_search_index(
+        &RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE.to_string(), RESOURCE_SPECIFICATION_SEARCH_INDEX_ID, RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&entry_resp), base_address.as_ref(),
+    )?;
+    Ok(construct_response(&base_address, &entry_resp))
 }
 pub fn receive_get_resource_specification(address: ResourceSpecificationAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(&address, &read_record_entry(&address)?, None))
+    Ok(construct_response(&address, &read_record_entry(&address)?))
+}
+pub fn receive_get_resource_specification_history(address: ResourceSpecificationAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_resource_specification_history(&address)
 }
 
 pub fn receive_update_resource_specification(resource_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_resource_specification(&resource_specification)
 }
-pub fn receive_delete_resource_specification(id: ResourceSpecificationAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&id)
+pub fn receive_delete_resource_specification(id: ResourceSpecificationAddress) -> ZomeApiResult<DeleteReport> {
+    // conforming resources are only indexed by the observation DNA
+    Ok(delete_record_with_policies(id.as_ref(), &[
+        ReferencePolicy::remote_query(
+            RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
+            RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_DELETE_POLICY,
+            BRIDGED_OBSERVATION_DHT, "resource_specification", "get_conforming_resource_ids",
+        ),
+    ], || {
+        let entry: Entry = read_record_entry(&id)?;
+        delete_search_index(
+            &RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE.to_string(), RESOURCE_SPECIFICATION_SEARCH_INDEX_ID, RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+            &get_search_text(&entry), id.as_ref(),
+        )?;
+        delete_record::<Entry>(&id)
+    })?)
 }
-pub fn receive_query_resource_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_resource_specifications(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_resource_specifications(&params)
 }
 
+pub fn receive_check_resource_specification_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, &[], repair)?)
+}
+
+pub fn receive_migrate_resource_specification_entries() -> ZomeApiResult<MigrationReport> {
+    let report = migrate_entries::<Entry, _>(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE)?;
+    // resource specifications created before search indexing was introduced are added to the index
+    Ok(reindex_records(
+        ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, ECONOMIC_RESOURCE_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE, report,
+        |address: &ResourceSpecificationAddress, entry: &Entry| {
+            create_search_index(
+                &RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE.to_string(), RESOURCE_SPECIFICATION_SEARCH_INDEX_ID, RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                &get_search_text(entry), address.as_ref(),
+            )
+        },
+    )?)
+}
+
+fn handle_get_resource_specification_history(address: &ResourceSpecificationAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, ECONOMIC_RESOURCE_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE)?;
+    // :NOTE: link fields are not versioned, and so are omitted from historical revisions
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_update_resource_specification(resource_specification: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = resource_specification.get_id();
+    let prev_entry: Entry = read_record_entry(address)?;
     let new_entry = update_record(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, &address, resource_specification)?;
-    Ok(construct_response(address, &new_entry, None))
+    update_search_index(
+        &RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE.to_string(), RESOURCE_SPECIFICATION_SEARCH_INDEX_ID, RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+        &get_search_text(&prev_entry), &get_search_text(&new_entry), address.as_ref(),
+    )?;
+    Ok(construct_response(address, &new_entry))
 }
 
-fn handle_query_resource_specifications(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(ResourceSpecificationAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: implement "all" query and filters
-
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                            None,
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        Err(e) => Err(e)
-    }
+fn handle_query_resource_specifications(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
+
+    match &params.search {
+        Some(search) => {
+            filters.push(IndexQuery::search(
+                RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE, RESOURCE_SPECIFICATION_SEARCH_INDEX_ID, RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE,
+                search,
+            ));
+        },
+        _ => (),
+    };
+
+    // :TODO: implement "all" query
+
+    let entries_result: GraphAPIResult<Vec<(ResourceSpecificationAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
 
-/// Create response from input DHT primitives
-pub fn construct_response<'a>(
-    address: &ResourceSpecificationAddress,
-    e: &Entry,
-    // :TODO: link conforming resources in associated link registry DNA module
-    _conforming_resources : Option<Cow<'a, Vec<ResourceAddress>>>
-) -> ResponseData {
-    ResponseData {
-        resource_specification: Response {
-            // entry fields
-            id: address.to_owned(),
-            name: e.name.to_owned(),
-            image: e.image.to_owned(),
-            note: e.note.to_owned(),
-            default_unit_of_effort: e.default_unit_of_effort.to_owned(),
-
-            // conforming_resources: conforming_resources.map(Cow::into_owned),
-        }
+/// Text of a resource specification which is indexed for searching
+fn get_search_text(e: &Entry) -> String {
+    match &e.note {
+        Some(note) => format!("{} {}", e.name, note),
+        None => e.name.to_owned(),
     }
 }
 
 //---------------- READ ----------------
 
-// @see construct_response
+// @see hc_zome_rea_resource_specification_rpc::construct_response
 // pub fn get_link_fields<'a>(resource_specification: &ResourceSpecificationAddress) -> (
 //     // :TODO:
 // ) {
@@ -14,92 +14,50 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    records::RecordRevision,
+    entries::RecordsResponse,
+};
+use hdk_graph_helpers_derive::VfRecord;
 use vf_core::type_aliases::{
     ExternalURL,
     UnitId,
 };
 
-//---------------- EXTERNAL RECORD STRUCTURE ----------------
+//---------------- RECORD STRUCTURE ----------------
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_core::type_aliases::{ ResourceSpecificationAddress };
 
-/// I/O struct to describe the complete record, including all managed link fields
+/// Field definitions for the record. `Entry`, `Response`, `ResponseData`, `CreateRequest`,
+/// `UpdateRequest` and `construct_response` are generated from this struct.
 ///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Response {
-    pub id: ResourceSpecificationAddress,
+/// :TODO: link conforming resources in associated link registry DNA module
+///
+#[derive(VfRecord)]
+#[vf_record(entry_type = "hc_zome_rea_resource_specification_storage_consts::ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE", derive_default)]
+pub struct ResourceSpecification {
     pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<ExternalURL>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub default_unit_of_effort: Option<UnitId>,
 }
 
-/// I/O struct to describe what is returned outside the gateway.
-/// Responses are usually returned as named attributes in order to leave space
-/// for future additional return values.
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ResponseData {
-    pub resource_specification: Response,
-}
-
-//---------------- CREATE REQUEST ----------------
-
-/// I/O struct to describe the complete input record, including all managed links
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CreateRequest {
-    pub name: String,
-    #[serde(default)]
-    pub image: MaybeUndefined<ExternalURL>,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-    #[serde(default)]
-    pub default_unit_of_effort: MaybeUndefined<UnitId>,
-}
-
-impl<'a> CreateRequest {
-    // :TODO: accessors for field data
-}
-
-//---------------- UPDATE REQUEST ----------------
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
 
-/// I/O struct to describe the complete input record, including all managed links
-///
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct UpdateRequest {
-    pub id: ResourceSpecificationAddress,
-    #[serde(default)]
-    pub name: MaybeUndefined<String>,
-    #[serde(default)]
-    pub image: MaybeUndefined<ExternalURL>,
-    #[serde(default)]
-    pub note: MaybeUndefined<String>,
-    #[serde(default)]
-    pub default_unit_of_effort: MaybeUndefined<UnitId>,
-}
-
-impl<'a> UpdateRequest {
-    pub fn get_id(&'a self) -> &ResourceSpecificationAddress {
-        &self.id
-    }
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<ResourceSpecificationAddress, ResponseData>;
 
-    // :TODO: accessors for other field data
-}
+pub use hdk_graph_helpers::integrity::IntegrityReport;
+pub use hdk_graph_helpers::delete_policies::DeleteReport;
 
 //---------------- QUERY FILTER REQUEST ----------------
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryParams {
-    // :TODO:
+    /// Text to find in the `name` or `note` of resource specifications. Terms match words
+    /// by prefix, or as whole words if enclosed in double quotes.
+    pub search: Option<String>,
 }
@@ -5,8 +5,19 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::delete_policies::DeletePolicy;
+
 pub const ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE: &str = "vf_resource_specification";
 pub const ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE: &str = "vf_resource_specification_baseurl";
 pub const ECONOMIC_RESOURCE_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE: &str = "vf_resource_specification_entry";
 pub const RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE: &str = "vf_resource_specification_conforming_resource";
 pub const RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG: &str = "conforming_resource";
+
+pub const RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_TYPE: &str = "vf_resource_specifications_search_index";
+pub const RESOURCE_SPECIFICATION_SEARCH_INDEX_ID: &str = "all_vf_resource_specifications";
+pub const RESOURCE_SPECIFICATION_SEARCH_INDEX_ENTRY_LINK_TYPE: &str = "vf_resource_specification_search_index";
+
+// handling of referencing records when a resource specification is deleted
+pub const RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_DELETE_POLICY: DeletePolicy = DeletePolicy::Restrict;
+
+pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
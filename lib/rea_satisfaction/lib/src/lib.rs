@@ -10,6 +10,8 @@
  * @package Holo-REA
  */
 use vf_core::type_aliases::SatisfactionAddress;
+use hdk_graph_helpers::entries::get_entry_revision;
+use hc_zome_rea_satisfaction_storage_consts::SATISFACTION_ENTRY_TYPE;
 use hc_zome_rea_satisfaction_storage::Entry;
 use hc_zome_rea_satisfaction_rpc::*;
 
@@ -18,6 +20,7 @@ pub fn construct_response(address: &SatisfactionAddress, e: &Entry) -> ResponseD
     ResponseData {
         satisfaction: Response {
             id: address.to_owned().into(),
+            revision_id: get_entry_revision(SATISFACTION_ENTRY_TYPE, e),
             satisfied_by: e.satisfied_by.to_owned(),
             satisfies: e.satisfies.to_owned(),
             resource_quantity: e.resource_quantity.to_owned(),
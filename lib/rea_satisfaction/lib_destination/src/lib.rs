@@ -12,16 +12,21 @@
 use hdk::prelude::*;
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
     records::{
-        create_record,
+        with_unit_of_work,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        create_direct_index,
+        IndexQuery,
+        query_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    entries::build_records_response,
 };
 
 use hc_zome_rea_economic_event_storage_consts::{EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG};
@@ -38,32 +43,50 @@ pub fn receive_get_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<R
     handle_get_satisfaction(&address)
 }
 
+pub fn receive_get_satisfaction_history(address: SatisfactionAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_satisfaction_history(&address)
+}
+
 pub fn receive_update_satisfaction(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_satisfaction(&satisfaction)
 }
 
 pub fn receive_delete_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_satisfactions(&params)
 }
 
+pub fn receive_check_satisfaction_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(SATISFACTION_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
+            EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
 fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = create_record(
-        SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
-        SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
-        satisfaction.to_owned()
-    )?;
-
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        satisfaction_address.as_ref(),
-        satisfaction.get_satisfied_by().as_ref(),
-        SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-        EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
-    );
+    // record & local index writes are reverted if any of them fail
+    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = with_unit_of_work(|unit| {
+        let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = unit.create_record(
+            SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
+            SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
+            satisfaction.to_owned()
+        )?;
+
+        // link entries in the local DNA
+        unit.create_direct_index(
+            satisfaction_address.as_ref(),
+            satisfaction.get_satisfied_by().as_ref(),
+            SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
+            EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
+        )?;
+
+        Ok((satisfaction_address, entry_resp))
+    })?;
 
     // register in the associated foreign DNA as well
     // :TODO: probably need to remove this and rethink to use a message broadcast / respond flow
@@ -90,31 +113,26 @@ fn handle_get_satisfaction(base_address: &SatisfactionAddress) -> ZomeApiResult<
     Ok(construct_response(&base_address, &entry))
 }
 
-fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(SatisfactionAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_get_satisfaction_history(address: &SatisfactionAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, SATISFACTION_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
+fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
     match &params.satisfied_by {
         Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(
+            filters.push(IndexQuery::direct(
                 satisfied_by, EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
-            );
+            ));
         },
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    // :TODO: avoid cloning entry
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(&entry_base_address, &entry)),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
-    }
+    let entries_result: GraphAPIResult<Vec<(SatisfactionAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
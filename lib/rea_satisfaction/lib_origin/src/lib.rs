@@ -10,25 +10,29 @@
  * @package Holo-REA
  */
 use hdk::{
-    PUBLIC_TOKEN,
     THIS_INSTANCE,
-    holochain_persistence_api::cas::content::Address,
-    error::{ZomeApiResult, ZomeApiError},
-    call,
+    error::ZomeApiResult,
 };
 
 use hdk_graph_helpers::{
+    GraphAPIResult,
+    storage::call,
     records::{
-        create_record,
+        with_unit_of_work,
         read_record_entry,
+        read_record_revisions,
+        RecordRevision,
         update_record,
         delete_record,
     },
-    rpc::read_from_zome,
+    rpc::{ read_with_capability, RemoteCapability, capability_token },
     local_indexes::{
-        query_direct_index_with_foreign_key,
+        IndexQuery,
+        query_index,
         create_direct_index,
     },
+    integrity::{ IndexDefinition, check_record_indexes },
+    entries::build_records_response,
 };
 
 use hc_zome_rea_intent_storage_consts::{INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG};
@@ -39,6 +43,21 @@ use hc_zome_rea_satisfaction_storage::*;
 use hc_zome_rea_satisfaction_rpc::*;
 use hc_zome_rea_satisfaction_lib::construct_response;
 
+/// Zome API methods of other DNAs which the satisfaction zome calls, requested upon `init`
+///
+pub const SATISFACTION_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "satisfaction",
+        functions: &["satisfaction_created", "satisfaction_updated", "satisfaction_deleted"],
+    },
+    RemoteCapability {
+        dna: THIS_INSTANCE,
+        zome: "commitment",
+        functions: &["get_commitment"],
+    },
+];
+
 pub fn receive_create_satisfaction(satisfaction: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_satisfaction(&satisfaction)
 }
@@ -47,6 +66,10 @@ pub fn receive_get_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<R
     handle_get_satisfaction(&address)
 }
 
+pub fn receive_get_satisfaction_history(address: SatisfactionAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_satisfaction_history(&address)
+}
+
 pub fn receive_update_satisfaction(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_satisfaction(&satisfaction)
 }
@@ -55,24 +78,38 @@ pub fn receive_delete_satisfaction(address: SatisfactionAddress) -> ZomeApiResul
     handle_delete_satisfaction(&address)
 }
 
-pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_satisfactions(&params)
 }
 
+pub fn receive_check_satisfaction_indexes(repair: bool) -> ZomeApiResult<IntegrityReport> {
+    Ok(check_record_indexes(SATISFACTION_BASE_ENTRY_TYPE, &[
+        IndexDefinition::local(
+            SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
+            INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
+        ),
+    ], repair)?)
+}
+
 fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = create_record(
-        SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
-        SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
-        satisfaction.to_owned(),
-    )?;
-
-    // link entries in the local DNA
-    let _results1 = create_direct_index(
-        satisfaction_address.as_ref(),
-        satisfaction.get_satisfies().as_ref(),
-        SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
-        INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
-    );
+    // record & local index writes are reverted if any of them fail
+    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = with_unit_of_work(|unit| {
+        let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = unit.create_record(
+            SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
+            SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
+            satisfaction.to_owned(),
+        )?;
+
+        // link entries in the local DNA
+        unit.create_direct_index(
+            satisfaction_address.as_ref(),
+            satisfaction.get_satisfies().as_ref(),
+            SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
+            INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
+        )?;
+
+        Ok((satisfaction_address, entry_resp))
+    })?;
 
     // link entries which may be local or remote
     // :TODO: Should not have to do this-
@@ -81,10 +118,9 @@ fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<Res
     //        (Alternative: every link has to get a successful pingback from the destination object with its trait signature intact.)
     // :TODO: use of URIs and a Holochain protocol resolver would also make this type of logic entirely unnecessary
     let event_or_commitment = satisfaction.get_satisfied_by();
-    let satisfying_commitment: ZomeApiResult<CommitmentResponse> = read_from_zome(
+    let satisfying_commitment: GraphAPIResult<CommitmentResponse> = read_with_capability(
         THIS_INSTANCE,
         "commitment",
-        Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
         "get_commitment",
         CheckCommitmentRequest { address: event_or_commitment.to_owned().into() }.into(),
     );
@@ -104,7 +140,7 @@ fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<Res
             let _pingback = call(
                 BRIDGED_OBSERVATION_DHT,
                 "satisfaction",
-                Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
+                capability_token(BRIDGED_OBSERVATION_DHT, "satisfaction", "satisfaction_created")?,
                 "satisfaction_created",
                 FwdCreateRequest { satisfaction: satisfaction.to_owned() }.into()
             );
@@ -120,6 +156,13 @@ fn handle_get_satisfaction(base_address: &SatisfactionAddress) -> ZomeApiResult<
     Ok(construct_response(&base_address, &entry))
 }
 
+fn handle_get_satisfaction_history(address: &SatisfactionAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_record_revisions(address, SATISFACTION_INITIAL_ENTRY_LINK_TYPE)?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(address, &entry)))
+        .collect())
+}
+
 fn handle_update_satisfaction(satisfaction: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = satisfaction.get_id();
     let new_entry = update_record(SATISFACTION_ENTRY_TYPE, &base_address, satisfaction)?;
@@ -128,7 +171,7 @@ fn handle_update_satisfaction(satisfaction: &UpdateRequest) -> ZomeApiResult<Res
     let _pingback = call(
         BRIDGED_OBSERVATION_DHT,
         "satisfaction",
-        Address::from(PUBLIC_TOKEN.to_string()),
+        capability_token(BRIDGED_OBSERVATION_DHT, "satisfaction", "satisfaction_updated")?,
         "satisfaction_updated",
         FwdUpdateRequest { satisfaction: satisfaction.clone() }.into()
     );
@@ -143,44 +186,31 @@ fn handle_delete_satisfaction(address: &SatisfactionAddress) -> ZomeApiResult<bo
     let _pingback = call(
         BRIDGED_OBSERVATION_DHT,
         "satisfaction",
-        Address::from(PUBLIC_TOKEN.to_string()),
+        capability_token(BRIDGED_OBSERVATION_DHT, "satisfaction", "satisfaction_deleted")?,
         "satisfaction_deleted",
         address.into(),
     );
 
-    result
+    Ok(result?)
 }
 
-fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(SatisfactionAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let mut filters = vec![];
 
-    // :TODO: implement proper AND search rather than exclusive operations
     match &params.satisfies {
         Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(satisfies, INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG);
+            filters.push(IndexQuery::direct(satisfies, INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG));
         },
         _ => (),
     };
     match &params.satisfied_by {
         Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(satisfied_by, COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG);
+            filters.push(IndexQuery::direct(satisfied_by, COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG));
         },
         _ => (),
     };
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    // :TODO: avoid cloning entry
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, entry)),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
-    }
+    let entries_result: GraphAPIResult<Vec<(SatisfactionAddress, GraphAPIResult<Entry>)>> = query_index(&IndexQuery::And(filters));
+
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
@@ -8,6 +8,7 @@
  * @package Holo-REA
  */
 use hdk::prelude::*;
+use hdk_graph_helpers::anchors::validate_anchor_link;
 
 use hc_zome_rea_unit_storage_consts::*;
 use hc_zome_rea_unit_storage::Entry;
@@ -47,8 +48,8 @@ pub fn id_anchor_entry_def() -> ValidatingEntryType {
                 validation_package: || {
                     hdk::ValidationPackageDefinition::Entry
                 },
-                validation: | _validation_data: hdk::LinkValidationData| {
-                    Ok(())
+                validation: | validation_data: hdk::LinkValidationData| {
+                    validate_anchor_link(&validation_data)
                 }
             )
         ]
@@ -6,20 +6,39 @@
  *
  * @package Holo-REA
  */
-use hdk::error::{ ZomeApiResult, ZomeApiError };
+use hdk::{
+    error::ZomeApiResult,
+    holochain_persistence_api::cas::content::Address,
+};
 
 use hdk_graph_helpers::{
+    GraphAPIResult, GraphHelperError,
     records::{
         create_anchored_record,
         read_anchored_record_entry,
+        read_anchored_record_revisions,
+        RecordRevision,
         update_anchored_record,
         delete_anchored_record,
     },
+    entries::{ build_records_response, get_entry_revision },
+    delete_policies::{ ReferencePolicy, delete_record_with_policies },
+    rpc::RemoteCapability,
 };
 
 use hc_zome_rea_unit_storage_consts::*;
 use hc_zome_rea_unit_storage::*;
 use hc_zome_rea_unit_rpc::*;
+use hc_zome_rea_economic_resource_storage_consts::RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE;
+
+/// Zome API methods of other DNAs called by this zome. @see `rpc::register_remote_capabilities`
+pub const UNIT_REMOTE_CAPABILITIES: &[RemoteCapability<'static>] = &[
+    RemoteCapability {
+        dna: BRIDGED_OBSERVATION_DHT,
+        zome: "economic_resource",
+        functions: &["get_unit_resource_ids"],
+    },
+];
 
 pub fn receive_create_unit(unit: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_unit(&unit)
@@ -27,13 +46,16 @@ pub fn receive_create_unit(unit: CreateRequest) -> ZomeApiResult<ResponseData> {
 pub fn receive_get_unit(id: UnitId) -> ZomeApiResult<ResponseData> {
     handle_get_unit(&id)
 }
+pub fn receive_get_unit_history(id: UnitId) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_unit_history(&id)
+}
 pub fn receive_update_unit(unit: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_unit(&unit)
 }
-pub fn receive_delete_unit(id: UnitId) -> ZomeApiResult<bool> {
+pub fn receive_delete_unit(id: UnitId) -> ZomeApiResult<DeleteReport> {
     handle_delete_unit(&id)
 }
-pub fn receive_query_units(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+pub fn receive_query_units(params: QueryParams) -> ZomeApiResult<QueryResponse> {
     handle_query_units(&params)
 }
 
@@ -47,37 +69,38 @@ fn handle_get_unit(id: &UnitId) -> ZomeApiResult<ResponseData> {
     Ok(construct_response(id, &entry))
 }
 
+fn handle_get_unit_history(id: &UnitId) -> ZomeApiResult<Vec<RevisionResponse>> {
+    let revisions: Vec<RecordRevision<Entry>> = read_anchored_record_revisions(&UNIT_ID_ENTRY_TYPE.to_string(), UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())?;
+    Ok(revisions.into_iter()
+        .map(|revision| revision.map(|entry| construct_response(id, &entry)))
+        .collect())
+}
+
 fn handle_update_unit(unit: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let (new_id, new_entry) = update_anchored_record(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, UNIT_ENTRY_TYPE, unit)?;
     Ok(construct_response(&new_id.into(), &new_entry))
 }
 
-fn handle_delete_unit(id: &UnitId) -> ZomeApiResult<bool> {
-    delete_anchored_record::<Entry>(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())
+fn handle_delete_unit(id: &UnitId) -> ZomeApiResult<DeleteReport> {
+    // resources are indexed by unit in the observation DNA, under the unit ID
+    // :TODO: also restrict deletion of units referenced by events & specifications
+    Ok(delete_record_with_policies(&Address::from(id.as_ref().to_owned()), &[
+        ReferencePolicy::remote_query(
+            RESOURCE_UNIT_INDEX_ENTRY_LINK_TYPE, id.as_ref(),
+            UNIT_MEASURED_RESOURCE_DELETE_POLICY,
+            BRIDGED_OBSERVATION_DHT, "economic_resource", "get_unit_resource_ids",
+        ),
+    ], || {
+        delete_anchored_record::<Entry>(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())
+    })?)
 }
 
-fn handle_query_units(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(UnitId, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+fn handle_query_units(_params: &QueryParams) -> ZomeApiResult<QueryResponse> {
+    let entries_result: GraphAPIResult<Vec<(UnitId, GraphAPIResult<Entry>)>> = Err(GraphHelperError::Internal("No results found".to_string()));
 
     // :TODO: implement "all" query and filters
 
-    match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        Err(e) => Err(e)
-    }
+    Ok(build_records_response(entries_result?, |entry_base_address, entry| construct_response(entry_base_address, entry)))
 }
 
 pub fn construct_response<'a>(
@@ -87,6 +110,7 @@ pub fn construct_response<'a>(
         unit: Response {
             // entry fields
             id: id.to_owned(),
+            revision_id: get_entry_revision(UNIT_ENTRY_TYPE, e),
             label: e.label.to_owned(),
             symbol: e.symbol.to_owned(),
         }
@@ -16,8 +16,11 @@ use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::{
     MaybeUndefined,
-    record_interface::{ UniquelyIdentifiable, UpdateableIdentifier },
+    record_interface::{ UniquelyIdentifiable, UpdateableIdentifier, RevisionedUpdate },
+    records::RecordRevision,
+    entries::RecordsResponse,
 };
+use vf_core::type_aliases::Address;
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
 
@@ -30,6 +33,8 @@ pub use vf_core::type_aliases::{ UnitId };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: UnitId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<Address>,
     pub label: String,
     pub symbol: String,
 }
@@ -44,6 +49,14 @@ pub struct ResponseData {
     pub unit: Response,
 }
 
+/// I/O struct to describe a historical revision of a record, as returned by `get_*_history` endpoints
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
+/// I/O struct to describe the records matched by `query_*` endpoints, and any which could not be read
+pub type QueryResponse = RecordsResponse<UnitId, ResponseData>;
+
+pub use hdk_graph_helpers::delete_policies::DeleteReport;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -75,6 +88,8 @@ impl UniquelyIdentifiable for CreateRequest {
 #[serde(rename_all = "camelCase")]
 pub struct UpdateRequest {
     pub id: UnitId,
+    #[serde(default)]
+    pub revision_id: Option<Address>,
     pub label: MaybeUndefined<String>,
     pub symbol: MaybeUndefined<String>,
 }
@@ -89,6 +104,12 @@ impl<'a> UpdateRequest {
     }
 }
 
+impl RevisionedUpdate for UpdateRequest {
+    fn get_base_revision(&self) -> Option<Address> {
+        self.revision_id.to_owned()
+    }
+}
+
 impl UniquelyIdentifiable for UpdateRequest {
     fn get_anchor_key(&self) -> String {
         self.get_id().as_ref().to_string()
@@ -11,9 +11,6 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-use holochain_json_api::{ json::JsonString, error::JsonError };
-use holochain_json_derive::{ DefaultJson };
-
 use hdk_graph_helpers::{
     record_interface::Updateable,
 };
@@ -22,12 +19,14 @@ use hc_zome_rea_unit_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Entry {
     pub label: String,
     pub symbol: String,
 }
 
+hdk_graph_helpers::versioned_entry!(Entry);
+
 impl<'a> Entry {
     pub fn get_symbol(&'a self) -> String {
         self.symbol.to_owned()
@@ -5,6 +5,13 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::delete_policies::DeletePolicy;
+
 pub const UNIT_ENTRY_TYPE: &str = "vf_unit";
 pub const UNIT_ID_ENTRY_TYPE: &str = "vf_unit_id";
 pub const UNIT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_unit_entry";
+
+// handling of referencing records when a unit is deleted
+pub const UNIT_MEASURED_RESOURCE_DELETE_POLICY: DeletePolicy = DeletePolicy::Restrict;
+
+pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
@@ -29,6 +29,44 @@ impl<'a> QuantityValue {
     }
 }
 
+/// Count, sum, minimum & maximum of a set of quantities measured in the same unit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantityAggregate {
+    pub has_unit: Option<UnitId>,
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Aggregates `quantities` by unit, in order of each unit's first appearance.
+/// Quantities in different units are kept separate, since unit conversions are not yet supported.
+pub fn aggregate_by_unit<I>(quantities: I) -> Vec<QuantityAggregate>
+    where I: IntoIterator<Item = QuantityValue>
+{
+    let mut aggregates: Vec<QuantityAggregate> = vec![];
+    for q in quantities {
+        let value = q.has_numerical_value;
+        match aggregates.iter_mut().find(|a| a.has_unit == q.has_unit) {
+            Some(a) => {
+                a.count += 1;
+                a.sum += value;
+                a.min = a.min.min(value);
+                a.max = a.max.max(value);
+            },
+            None => aggregates.push(QuantityAggregate {
+                has_unit: q.has_unit,
+                count: 1,
+                sum: value,
+                min: value,
+                max: value,
+            }),
+        }
+    }
+    aggregates
+}
+
 pub fn add(q1: QuantityValue, q2: QuantityValue) -> QuantityValue {
     if q1.has_unit != q2.has_unit {
         panic!("Unimplemented! Need to enable unit conversions in QuantityValue math");
@@ -57,6 +57,42 @@ const BASE_CONNECTION = (socketURI: ConnURI = undefined) => {
   return CONNECTION_CACHE[connId]
 }
 
+/**
+ * Error thrown when a zome call fails. Where the failure originated in the graph helper
+ * library, `graphError` contains the decoded `GraphHelperError` structure- including the
+ * `cause` of any failure which occurred in another DNA or zome.
+ */
+export class ZomeError extends Error {
+  graphError: any
+
+  constructor (message: string, graphError: any = undefined) {
+    super(message)
+    this.graphError = graphError
+  }
+}
+
+/**
+ * Decode structured errors returned by zome API methods. These may be returned directly,
+ * or JSON-encoded within an HDK `Internal` error.
+ */
+function decodeZomeError (error: any): ZomeError {
+  let graphError = error
+
+  if (graphError && graphError['Internal']) {
+    try {
+      graphError = JSON.parse(graphError['Internal'])
+    } catch (e) {
+      return new ZomeError(graphError['Internal'])
+    }
+  }
+
+  if (typeof graphError === 'string' || graphError instanceof String) {
+    return new ZomeError(graphError as string)
+  }
+
+  return new ZomeError(JSON.stringify(graphError), graphError)
+}
+
 export interface ZomeFnOpts {
   resultParser?: (resp: any) => any
 }
@@ -70,15 +106,61 @@ export const zomeFunction = (instance: string, zome: string, fn: string, socketU
 
   const rawResult = await zomeCall(args)
   const jsonResult = JSON.parse(rawResult)
-  let error = jsonResult['Err'] || jsonResult['SerializationError']
+  const error = jsonResult['Err'] || jsonResult['SerializationError']
 
   // deal with complex error responses
-  if (!(typeof error === 'string' || error instanceof String)) {
-    error = JSON.stringify(error)
-  }
-
-  if (error) throw new Error(error)
+  if (error) throw decodeZomeError(error)
 
   const rawOk = jsonResult['Ok']
   return opts.resultParser ? opts.resultParser(rawOk) : rawOk
 }
+
+/**
+ * Extract the readable records from the response of a `query_*` or `get_all_*` zome API method.
+ * Records which could not be read are logged and omitted, rather than failing the whole query.
+ */
+function readableResults (response: any): any[] {
+  (response.errors || []).forEach(({ id, error }) => {
+    console.warn(`Could not read record ${id}:`, decodeZomeError(error))
+  })
+  return response.results
+}
+
+/**
+ * Higher-order function to generate async functions for calling `query_*` zome RPC methods,
+ * which resolve to the list of readable records matched by the query.
+ */
+export const queryFunction = (instance: string, zome: string, fn: string, socketURI: ConnURI = undefined) => {
+  const call = zomeFunction(instance, zome, fn, socketURI)
+  return async (args: any) => call(args, { resultParser: readableResults })
+}
+
+/**
+ * Maximum number of pages loaded by `readPages` when no `limit` is given
+ */
+export const MAX_UNLIMITED_PAGES = 20
+
+/**
+ * Read consecutive pages from a paginated `get_all_*` zome API method, beginning at `cursor`,
+ * until `limit` results have been loaded or no further pages remain. If no `limit` is given,
+ * remaining results are read up to `MAX_UNLIMITED_PAGES` pages of the zome's default size.
+ */
+export async function readPages (readPage: (args: any) => Promise<any>, cursor?: string, limit?: number): Promise<any[]> {
+  let results: any[] = []
+  if (limit != null && limit <= 0) {
+    return results
+  }
+
+  let pagesRead = 0
+  do {
+    const page = await readPage({ params: { cursor, limit: limit == null ? undefined : limit - results.length } })
+    results = results.concat(readableResults(page))
+    cursor = page.nextCursor
+    pagesRead += 1
+  } while (cursor && (limit == null ? pagesRead < MAX_UNLIMITED_PAGES : results.length < limit))
+
+  if (cursor && limit == null) {
+    console.warn(`Stopped reading after ${MAX_UNLIMITED_PAGES} pages; pass a limit & cursor to read further results`)
+  }
+  return results
+}
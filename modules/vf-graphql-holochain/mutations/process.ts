@@ -41,5 +41,6 @@ export const updateProcess: updateHandler = async (root, args) => {
 type deleteHandler = (root: any, args: { id: string }) => Promise<boolean>
 
 export const deleteProcess: deleteHandler = async (root, args) => {
-  return deleteHandler({ address: args.id })
+  const report = await deleteHandler({ address: args.id })
+  return report.deleted
 }
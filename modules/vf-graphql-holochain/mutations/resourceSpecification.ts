@@ -47,5 +47,6 @@ export const updateResourceSpecification: updateHandler = async (root, args) =>
 type deleteHandler = (root: any, args: { id: string }) => Promise<boolean>
 
 export const deleteResourceSpecification: deleteHandler = async (root, args) => {
-  return deleteHandler({ address: args.id })
+  const report = await deleteHandler({ address: args.id })
+  return report.deleted
 }
@@ -41,5 +41,6 @@ export const updateUnit: updateHandler = async (root, args) => {
 type deleteHandler = (root: any, args: { id: string }) => Promise<boolean>
 
 export const deleteUnit: deleteHandler = async (root, args) => {
-  return deleteHandler({ id: args.id })
+  const report = await deleteHandler({ id: args.id })
+  return report.deleted
 }
@@ -5,7 +5,7 @@
  * @since:   2019-05-27
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, readPages } from '../connection'
 import { injectTypename, addTypename } from '../types'
 
 import {
@@ -23,6 +23,9 @@ export const economicEvent = injectTypename('EconomicEvent', async (root, args):
   return (await readOne({ address: args.id })).economicEvent
 })
 
+// Read events in chronological order, from the cursor `start` (if given) until `limit` events (or all
+// remaining events) have been read. Events are loaded from as many pages as are needed.
 export const allEconomicEvents = async (root, args): Promise<EconomicEvent[]> => {
-  return (await readAll({})).map(e => withTypename(e.economicEvent))
+  const { start, limit } = args
+  return (await readPages(readAll, start, limit)).map(e => withTypename(e.economicEvent))
 }
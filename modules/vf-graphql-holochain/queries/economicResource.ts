@@ -5,7 +5,7 @@
  * @since:   2019-10-31
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, readPages } from '../connection'
 
 import {
   EconomicResource,
@@ -20,6 +20,9 @@ export const economicResource = async (root, args): Promise<EconomicResource> =>
   return (await readOne({ address: args.id })).economicResource
 }
 
+// Read resources ordered by the time of their creating events, from the cursor `start` (if given) until
+// `limit` resources (or all remaining resources) have been read. Resources are loaded from as many pages as are needed.
 export const allEconomicResources = async (root, args): Promise<EconomicResource[]> => {
-  return (await readAll({})).map(e => e.economicResource)
+  const { start, limit } = args
+  return (await readPages(readAll, start, limit)).map(e => e.economicResource)
 }
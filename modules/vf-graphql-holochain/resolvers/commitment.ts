@@ -5,7 +5,7 @@
  * @since:   2019-08-28
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 
 import {
   Commitment,
@@ -17,9 +17,9 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readFulfillments = zomeFunction('planning', 'fulfillment', 'query_fulfillments')
-const readSatisfactions = zomeFunction('planning', 'satisfaction', 'query_satisfactions')
-const readProcesses = zomeFunction('observation', 'process', 'query_processes')
+const readFulfillments = queryFunction('planning', 'fulfillment', 'query_fulfillments')
+const readSatisfactions = queryFunction('planning', 'satisfaction', 'query_satisfactions')
+const readProcesses = queryFunction('observation', 'process', 'query_processes')
 const readResourceSpecification = zomeFunction('specification', 'resource_specification', 'get_resource_specification')
 const readAction = zomeFunction('specification', 'action', 'get_action')
 
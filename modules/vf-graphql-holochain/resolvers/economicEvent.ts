@@ -5,7 +5,7 @@
  * @since:   2019-08-27
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 
 import {
   EconomicEvent,
@@ -17,9 +17,9 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readFulfillments = zomeFunction('observation', 'fulfillment', 'query_fulfillments')
-const readSatisfactions = zomeFunction('observation', 'satisfaction', 'query_satisfactions')
-const readProcesses = zomeFunction('observation', 'process', 'query_processes')
+const readFulfillments = queryFunction('observation', 'fulfillment', 'query_fulfillments')
+const readSatisfactions = queryFunction('observation', 'satisfaction', 'query_satisfactions')
+const readProcesses = queryFunction('observation', 'process', 'query_processes')
 const readAction = zomeFunction('specification', 'action', 'get_action')
 const readResourceSpecification = zomeFunction('specification', 'resource_specification', 'get_resource_specification')
 
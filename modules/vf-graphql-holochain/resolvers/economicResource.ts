@@ -5,7 +5,7 @@
  * @since:   2019-10-31
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 
 import {
   EconomicResource,
@@ -16,7 +16,7 @@ import {
   Maybe,
 } from '@valueflows/vf-graphql'
 
-const readResources = zomeFunction('observation', 'economic_resource', 'query_resources')
+const readResources = queryFunction('observation', 'economic_resource', 'query_resources')
 const readUnit = zomeFunction('specification', 'unit', 'get_unit')
 const readProcessSpecification = zomeFunction('specification', 'process_specification', 'get_process_specification')
 const readAction = zomeFunction('specification', 'action', 'get_action')
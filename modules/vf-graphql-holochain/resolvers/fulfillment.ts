@@ -5,7 +5,7 @@
  * @since:   2019-08-27
  */
 
-import { zomeFunction } from '../connection'
+import { queryFunction } from '../connection'
 import { injectTypename } from '../types'
 
 import {
@@ -15,8 +15,8 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readEvents = zomeFunction('observation', 'economic_event', 'query_events')
-const readCommitments = zomeFunction('planning', 'commitment', 'query_commitments')
+const readEvents = queryFunction('observation', 'economic_event', 'query_events')
+const readCommitments = queryFunction('planning', 'commitment', 'query_commitments')
 
 export const fulfilledBy = injectTypename('EconomicEvent', async (record: Fulfillment): Promise<EconomicEvent> => {
   return (await readEvents({ params: { fulfills: record.id } })).pop()['economicEvent']
@@ -5,7 +5,7 @@
  * @since:   2019-08-31
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 
 import {
   Intent,
@@ -17,8 +17,8 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readSatisfactions = zomeFunction('planning', 'satisfaction', 'query_satisfactions')
-const readProcesses = zomeFunction('observation', 'process', 'query_processes')
+const readSatisfactions = queryFunction('planning', 'satisfaction', 'query_satisfactions')
+const readProcesses = queryFunction('observation', 'process', 'query_processes')
 const readProposedIntent = zomeFunction('proposal', 'proposed_intent', 'get_proposed_intent')
 const readResourceSpecification = zomeFunction('specification', 'resource_specification', 'get_resource_specification')
 const readAction = zomeFunction('specification', 'action', 'get_action')
@@ -5,7 +5,7 @@
  * @since:   2019-09-12
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 import { injectTypename } from '../types'
 
 import {
@@ -17,9 +17,9 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readEvents = zomeFunction('observation', 'economic_event', 'query_events')
-const readCommitments = zomeFunction('planning', 'commitment', 'query_commitments')
-const readIntents = zomeFunction('planning', 'intent', 'query_intents')
+const readEvents = queryFunction('observation', 'economic_event', 'query_events')
+const readCommitments = queryFunction('planning', 'commitment', 'query_commitments')
+const readIntents = queryFunction('planning', 'intent', 'query_intents')
 const readProcessBasedOn = zomeFunction('specification', 'process_specification', 'get_process_specification')
 
 export const inputs = injectTypename('EconomicEvent', async (record: Process): Promise<EconomicEvent[]> => {
@@ -5,7 +5,7 @@
  * @since:   2019-08-27
  */
 
-import { zomeFunction } from '../connection'
+import { zomeFunction, queryFunction } from '../connection'
 
 import {
   Maybe,
@@ -15,7 +15,7 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const queryResources = zomeFunction('observation', 'economic_resource', 'query_resources')
+const queryResources = queryFunction('observation', 'economic_resource', 'query_resources')
 const readUnit = zomeFunction('specification', 'unit', 'get_unit')
 
 export const conformingResources = async (record: ResourceSpecification): Promise<EconomicResource[]> => {
@@ -5,7 +5,7 @@
  * @since:   2019-08-31
  */
 
-import { zomeFunction } from '../connection'
+import { queryFunction } from '../connection'
 import { addTypename } from '../types'
 
 import {
@@ -15,9 +15,9 @@ import {
 } from '@valueflows/vf-graphql'
 
 // :TODO: how to inject DNA identifier?
-const readEvents = zomeFunction('observation', 'economic_event', 'query_events')
-const readCommitments = zomeFunction('planning', 'commitment', 'query_commitments')
-const readIntents = zomeFunction('planning', 'intent', 'query_intents')
+const readEvents = queryFunction('observation', 'economic_event', 'query_events')
+const readCommitments = queryFunction('planning', 'commitment', 'query_commitments')
+const readIntents = queryFunction('planning', 'intent', 'query_intents')
 
 async function extractRecordsOrFail (query, subfieldId: string): Promise<any> {
   const val = await query
@@ -0,0 +1,49 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  proposal: getDNA('proposal'),
+})
+
+runner.registerScenario('index integrity checks report and repair links to deleted records', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const pResp = await alice.call('proposal', 'proposal', 'create_proposal', { proposal: { name: 'proposal with dangling recipients' } })
+  t.ok(pResp.Ok.proposal && pResp.Ok.proposal.id, 'proposal created successfully')
+  await s.consistency()
+  const proposalId = pResp.Ok.proposal.id
+
+  const ptResp = await alice.call('proposal', 'proposed_to', 'create_proposed_to', { proposed_to: { proposed: proposalId, proposedTo: 'agentid-1-todo' } })
+  t.ok(ptResp.Ok.proposedTo && ptResp.Ok.proposedTo.id, 'recipient created successfully')
+  await s.consistency()
+  const proposedToId = ptResp.Ok.proposedTo.id
+
+  let checkResp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: false })
+  t.ok(checkResp.Ok, 'integrity check OK')
+  t.equal(checkResp.Ok.recordsChecked, 1, 'all records checked')
+  t.deepEqual(checkResp.Ok.problems, [], 'no problems reported for consistent indexes')
+
+  // deleting the recipient leaves the proposal's `publishedTo` index dangling
+  await alice.call('proposal', 'proposed_to', 'delete_proposed_to', { address: proposedToId })
+  await s.consistency()
+
+  checkResp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: false })
+  t.equal(checkResp.Ok.problems.length, 1, 'broken index detected')
+  t.equal(checkResp.Ok.problems[0].targetDeleted && checkResp.Ok.problems[0].targetDeleted.base, proposalId, 'link to deleted record reported')
+  t.deepEqual(checkResp.Ok.repairs, [], 'nothing repaired unless requested')
+
+  checkResp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: true })
+  t.equal(checkResp.Ok.repairs.length, 1, 'repair attempted')
+  t.ok(checkResp.Ok.repairs[0].Ok !== undefined, 'repair succeeded')
+  await s.consistency()
+
+  checkResp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: false })
+  t.deepEqual(checkResp.Ok.problems, [], 'no problems remain after repair')
+})
+
+runner.run()
@@ -0,0 +1,73 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const testEventProps = {
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('records can be counted & aggregated without listing them', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  let resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Baking' } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const processId = resp.Ok.process.id
+
+  // SCENARIO: events into a process
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, action: 'consume', inputOf: processId, resourceQuantity: { hasNumericalValue: 3, hasUnit: 'kg' } } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, action: 'consume', inputOf: processId, resourceQuantity: { hasNumericalValue: 1.5, hasUnit: 'kg' } } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, action: 'consume', inputOf: processId, resourceQuantity: { hasNumericalValue: 2, hasUnit: 'l' } } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, action: 'use', inputOf: processId, effortQuantity: { hasNumericalValue: 1, hasUnit: 'hour' } } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_event', 'count_events', { params: { inputOf: processId } })
+  t.equal(resp.Ok.count, 4, 'events counted')
+  resp = await alice.call('observation', 'economic_event', 'count_events', { params: { inputOf: processId, action: 'consume' } })
+  t.equal(resp.Ok.count, 3, 'events counted by action via process links')
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: processId, action: 'use' } })
+  t.equal(resp.Ok.results.length, 1, 'events queried by action via process links')
+  t.equal(resp.Ok.results[0].economicEvent.action, 'use', 'correct event returned')
+
+  resp = await alice.call('observation', 'economic_event', 'aggregate_events', { params: { inputOf: processId, action: 'consume' } })
+  t.equal(resp.Ok.count, 3, 'aggregates count matching events')
+  t.deepEqual(resp.Ok.resourceQuantity, [
+    { hasUnit: 'kg', count: 2, sum: 4.5, min: 1.5, max: 3 },
+    { hasUnit: 'l', count: 1, sum: 2, min: 2, max: 2 },
+  ], 'quantities totalled separately for each unit')
+  t.deepEqual(resp.Ok.effortQuantity, [], 'quantities only totalled for matching events')
+
+  // SCENARIO: open commitments for a process
+  const commitmentProps = { action: 'produce', provider: 'agentid-1-todo', receiver: 'agentid-2-todo', outputOf: processId, due: '2019-11-19T04:29:55.056Z' }
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: { ...commitmentProps, resourceQuantity: { hasNumericalValue: 10, hasUnit: 'loaf' } } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: { ...commitmentProps, resourceQuantity: { hasNumericalValue: 5, hasUnit: 'loaf' }, finished: true } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  await s.consistency()
+
+  resp = await alice.call('planning', 'commitment', 'count_commitments', { params: { outputOf: processId } })
+  t.equal(resp.Ok.count, 2, 'commitments counted')
+  resp = await alice.call('planning', 'commitment', 'count_commitments', { params: { outputOf: processId, finished: false } })
+  t.equal(resp.Ok.count, 1, 'open commitments counted')
+
+  resp = await alice.call('planning', 'commitment', 'aggregate_commitments', { params: { outputOf: processId, finished: false } })
+  t.deepEqual(resp.Ok.resourceQuantity, [{ hasUnit: 'loaf', count: 1, sum: 10, min: 10, max: 10 }], 'open commitment quantities totalled')
+})
+
+runner.run()
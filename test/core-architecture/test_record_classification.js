@@ -0,0 +1,109 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const ids = (resp, field) => resp.Ok.results.map(r => r[field].id).sort()
+
+const BEEF = 'https://example.com/classifications/beef-carcass'
+const PORK = 'https://example.com/classifications/pork-carcass'
+const ORGANIC = 'https://example.com/classifications/organic'
+
+const testEventProps = {
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+}
+
+runner.registerScenario('records can be queried by classification', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: resources classified via events
+  let resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'raise', resourceClassifiedAs: [BEEF], ...testEventProps },
+    new_inventoried_resource: { conformsTo: 'dangling-resource-specification-todo-tidy-up' },
+  })
+  t.ok(resp.Ok.economicResource && resp.Ok.economicResource.id, 'resource created successfully')
+  const beefEventId = resp.Ok.economicEvent.id
+  const beefId = resp.Ok.economicResource.id
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'raise', resourceClassifiedAs: [PORK], ...testEventProps },
+    new_inventoried_resource: { conformsTo: 'dangling-resource-specification-todo-tidy-up' },
+  })
+  t.ok(resp.Ok.economicResource && resp.Ok.economicResource.id, 'resource created successfully')
+  const porkId = resp.Ok.economicResource.id
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { classifiedAs: BEEF } })
+  t.deepEqual(ids(resp, 'economicResource'), [beefId], 'resources queryable by classification')
+
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { classifiedAs: BEEF } })
+  t.deepEqual(ids(resp, 'economicEvent'), [beefEventId], 'events queryable by resource classification')
+
+  // SCENARIO: classifications merged from events
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'raise', resourceInventoriedAs: porkId, resourceClassifiedAs: [ORGANIC], ...testEventProps },
+  })
+  t.ok(resp.Ok.economicEvent, 'resource updated via event')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { classifiedAs: ORGANIC } })
+  t.deepEqual(ids(resp, 'economicResource'), [porkId], 'classifications added by events are indexed')
+  resp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { classifiedAs: PORK } })
+  t.deepEqual(ids(resp, 'economicResource'), [porkId], 'existing classifications retained after merge')
+
+  // SCENARIO: reclassified resources
+  resp = await alice.call('observation', 'economic_resource', 'update_resource', { resource: { id: beefId, classifiedAs: [ORGANIC] } })
+  t.ok(resp.Ok, 'resource updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { classifiedAs: BEEF } })
+  t.deepEqual(resp.Ok.results, [], 'removed classifications no longer matched')
+  resp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { classifiedAs: ORGANIC } })
+  t.deepEqual(ids(resp, 'economicResource'), [beefId, porkId].sort(), 'added classifications matched')
+
+  // SCENARIO: planning records & processes
+  resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Butchery', classifiedAs: [BEEF] } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const processId = resp.Ok.process.id
+
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: {
+    action: 'raise', provider: 'agentid-1-todo', receiver: 'agentid-2-todo',
+    resourceClassifiedAs: [BEEF, ORGANIC], due: '2019-11-19T04:29:55.056Z',
+  } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  const commitmentId = resp.Ok.commitment.id
+
+  resp = await alice.call('planning', 'intent', 'create_intent', { intent: { action: 'raise', provider: 'agentid-1-todo', resourceClassifiedAs: [BEEF] } })
+  t.ok(resp.Ok.intent && resp.Ok.intent.id, 'intent created successfully')
+  const intentId = resp.Ok.intent.id
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { classifiedAs: BEEF } })
+  t.deepEqual(ids(resp, 'process'), [processId], 'processes queryable by classification')
+  resp = await alice.call('planning', 'commitment', 'query_commitments', { params: { classifiedAs: ORGANIC } })
+  t.deepEqual(ids(resp, 'commitment'), [commitmentId], 'commitments queryable by classification')
+  resp = await alice.call('planning', 'intent', 'query_intents', { params: { classifiedAs: BEEF } })
+  t.deepEqual(ids(resp, 'intent'), [intentId], 'intents queryable by classification')
+
+  resp = await alice.call('planning', 'intent', 'update_intent', { intent: { id: intentId, resourceClassifiedAs: [PORK] } })
+  t.ok(resp.Ok, 'intent updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('planning', 'intent', 'query_intents', { params: { classifiedAs: BEEF } })
+  t.deepEqual(resp.Ok.results, [], 'intent classification index updated')
+})
+
+runner.run()
@@ -43,7 +43,7 @@ runner.registerScenario('record deletion API', async (s, t) => {
 
   // attempt retrieval
   readResp = await planning.call('planning', 'commitment', 'get_commitment', { address: commitmentId })
-  t.equal(readResp.Err.Internal, 'No entry at this address', 'record not retrievable once deleted')
+  t.equal(JSON.parse(readResp.Err.Internal).Deleted, commitmentId, 'record not retrievable once deleted')
 })
 
 runner.registerScenario('Cannot delete records of a different type via zome API deletion handlers', async (s, t) => {
@@ -0,0 +1,105 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+  vf_planning: ['observation', 'planning'],
+})
+
+const testEventProps = {
+  action: 'consume',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+}
+
+runner.registerScenario('process deletion is restricted by referencing events', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'observed process' } })
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  const eResp = await alice.call('observation', 'economic_event', 'create_event', { event: { inputOf: processId, hasPointInTime: '2019-11-19T04:29:55.056Z', ...testEventProps } })
+  t.ok(eResp.Ok.economicEvent && eResp.Ok.economicEvent.id, 'event created successfully')
+  await s.consistency()
+  const eventId = eResp.Ok.economicEvent.id
+
+  const delResp = await alice.call('observation', 'process', 'delete_process', { address: processId })
+  const err = JSON.parse(delResp.Err.Internal).DeleteRestricted
+  t.ok(err, 'deletion rejected')
+  t.deepEqual(err.referenced_by, [eventId], 'referencing records reported')
+
+  const readResp = await alice.call('observation', 'process', 'get_process', { address: processId })
+  t.equal(readResp.Ok.process.id, processId, 'record not deleted')
+})
+
+runner.registerScenario('process deletion clears references from commitments in other DNAs', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'planned process' } })
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  const cResp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: { inputOf: processId, due: '2019-11-19T04:29:55.056Z', ...testEventProps } })
+  t.ok(cResp.Ok.commitment && cResp.Ok.commitment.id, 'commitment created successfully')
+  await s.consistency()
+  const commitmentId = cResp.Ok.commitment.id
+
+  const delResp = await alice.call('observation', 'process', 'delete_process', { address: processId })
+  t.equal(delResp.Ok.deleted, true, 'record deleted')
+  t.deepEqual(delResp.Ok.nullified, [commitmentId], 'referencing record nullified')
+  t.deepEqual(delResp.Ok.cascaded, [], 'nothing cascaded')
+  t.deepEqual(delResp.Ok.failed, [], 'no failures reported')
+  await s.consistency()
+
+  const readResp = await alice.call('planning', 'commitment', 'get_commitment', { address: commitmentId })
+  t.equal(readResp.Ok.commitment.inputOf, undefined, 'reference cleared in remote record')
+})
+
+runner.registerScenario('unit deletion is restricted by resources measured in the unit', async (s, t) => {
+  const unitConfig = buildConfig({
+    observation: getDNA('observation'),
+    specification: getDNA('specification'),
+  }, {
+    vf_observation: ['specification', 'observation'],
+    vf_specification: ['observation', 'specification'],
+  })
+  const { alice } = await s.players({ alice: unitConfig }, true)
+
+  let resp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'kilograms', symbol: 'kg' } })
+  t.equal(resp.Ok.unit.id, 'kg', 'unit created successfully')
+  resp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'litres', symbol: 'l' } })
+  t.equal(resp.Ok.unit.id, 'l', 'unused unit created successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { ...testEventProps, action: 'raise', hasPointInTime: '2019-11-19T04:29:55.056Z', resourceQuantity: { hasNumericalValue: 1, hasUnit: 'kg' } },
+    new_inventoried_resource: { conformsTo: 'dangling-resource-specification-todo-tidy-up' },
+  })
+  t.ok(resp.Ok.economicResource && resp.Ok.economicResource.id, 'resource created successfully')
+  const resourceId = resp.Ok.economicResource.id
+  await s.consistency()
+
+  resp = await alice.call('specification', 'unit', 'delete_unit', { id: 'kg' })
+  const err = JSON.parse(resp.Err.Internal).DeleteRestricted
+  t.ok(err, 'deletion of unit in use rejected')
+  t.deepEqual(err.referenced_by, [resourceId], 'referencing resources reported')
+
+  resp = await alice.call('specification', 'unit', 'get_unit', { id: 'kg' })
+  t.equal(resp.Ok.unit.id, 'kg', 'unit in use not deleted')
+
+  resp = await alice.call('specification', 'unit', 'delete_unit', { id: 'l' })
+  t.equal(resp.Ok.deleted, true, 'unused unit deleted')
+})
+
+runner.run()
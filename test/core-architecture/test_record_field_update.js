@@ -68,4 +68,50 @@ runner.registerScenario('updates with fields nulled remove original value', asyn
   t.equal(readResponse.Ok.economicEvent.note, undefined, 'field removed if nulled')
 })
 
+runner.registerScenario('updates based on a superseded revision are rejected', async (s, t) => {
+  const { observation } = await s.players({ observation: config }, true)
+
+  const event = {
+    note: 'test event 3',
+    action: 'raise',
+    ...testEventProps,
+  }
+
+  const createEventResponse = await observation.call('observation', 'economic_event', 'create_event', { event })
+  t.ok(createEventResponse.Ok.economicEvent && createEventResponse.Ok.economicEvent.id, 'record created successfully')
+  await s.consistency()
+
+  const eventId = createEventResponse.Ok.economicEvent.id
+  const initialRevision = createEventResponse.Ok.economicEvent.revisionId
+  t.ok(initialRevision, 'revision ID returned on creation')
+  const historyResponse = await observation.call('observation', 'economic_event', 'get_event_history', { address: eventId })
+  t.equal(historyResponse.Ok[0].revisionId, initialRevision, 'revision ID matches record history')
+
+  const firstUpdate = await observation.call('observation', 'economic_event', 'update_event', {
+    event: { id: eventId, revisionId: initialRevision, note: 'first edit' },
+  })
+  t.ok(firstUpdate.Ok, 'update against current revision succeeds')
+  t.notEqual(firstUpdate.Ok.economicEvent.revisionId, initialRevision, 'new revision ID returned on update')
+  await s.consistency()
+
+  const secondUpdate = await observation.call('observation', 'economic_event', 'update_event', {
+    event: { id: eventId, revisionId: initialRevision, note: 'conflicting edit' },
+  })
+  t.ok(secondUpdate.Err, 'update against superseded revision fails')
+  t.ok(JSON.stringify(secondUpdate.Err).indexOf('RevisionConflict') !== -1, 'conflict error returned')
+
+  let readResponse = await observation.call('observation', 'economic_event', 'get_event', { address: eventId })
+  t.equal(readResponse.Ok.economicEvent.note, 'first edit', 'conflicting edit not applied')
+  t.equal(readResponse.Ok.economicEvent.revisionId, firstUpdate.Ok.economicEvent.revisionId, 'current revision ID returned on read')
+
+  const thirdUpdate = await observation.call('observation', 'economic_event', 'update_event', {
+    event: { id: eventId, revisionId: readResponse.Ok.economicEvent.revisionId, note: 'second edit' },
+  })
+  t.ok(thirdUpdate.Ok, 'update against revision ID from read succeeds')
+  await s.consistency()
+
+  readResponse = await observation.call('observation', 'economic_event', 'get_event', { address: eventId })
+  t.equal(readResponse.Ok.economicEvent.note, 'second edit', 'edit based on read revision applied')
+})
+
 runner.run()
@@ -0,0 +1,50 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+})
+
+const testEventProps = {
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('record history returns all revisions in order', async (s, t) => {
+  const { observation } = await s.players({ observation: config }, true)
+
+  const event = {
+    note: 'original note',
+    action: 'raise',
+    ...testEventProps,
+  }
+
+  const createEventResponse = await observation.call('observation', 'economic_event', 'create_event', { event })
+  t.ok(createEventResponse.Ok.economicEvent && createEventResponse.Ok.economicEvent.id, 'record created successfully')
+  await s.consistency()
+
+  const eventId = createEventResponse.Ok.economicEvent.id
+
+  await observation.call('observation', 'economic_event', 'update_event', { event: { id: eventId, note: 'updated note' } })
+  await s.consistency()
+  await observation.call('observation', 'economic_event', 'update_event', { event: { id: eventId, note: 'final note' } })
+  await s.consistency()
+
+  const historyResponse = await observation.call('observation', 'economic_event', 'get_event_history', { address: eventId })
+  t.ok(historyResponse.Ok, 'history read OK')
+  t.equal(historyResponse.Ok.length, 3, 'all revisions returned')
+  t.deepEqual(historyResponse.Ok.map(r => r.record.economicEvent.note), ['original note', 'updated note', 'final note'], 'revisions returned oldest first')
+  t.ok(historyResponse.Ok.every(r => r.record.economicEvent.id === eventId), 'revisions reference the record ID')
+  t.ok(historyResponse.Ok.every(r => r.revisionId && r.timestamp && r.author), 'revision metadata returned')
+  t.notEqual(historyResponse.Ok[0].revisionId, historyResponse.Ok[2].revisionId, 'revisions have distinct IDs')
+})
+
+runner.run()
@@ -65,13 +65,13 @@ runner.registerScenario('updating remote link fields syncs fields and associated
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'field query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].commitment && readResponse.Ok[0].commitment.id, iCommitmentId, 'query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'field query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].commitment && readResponse.Ok.results[0].commitment.id, iCommitmentId, 'query index OK')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { committedInputs: iCommitmentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'reciprocal query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'reciprocal query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'reciprocal query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'reciprocal query index OK')
 
 
 
@@ -91,20 +91,20 @@ runner.registerScenario('updating remote link fields syncs fields and associated
 
   // ASSERT: test new commitment input query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { inputOf: differentProcessId } })
-  t.equal(readResponse.Ok && readResponse.Ok[0]
-    && readResponse.Ok[0].commitment
-    && readResponse.Ok[0].commitment.id, iCommitmentId, 'new field query index applied')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0]
+    && readResponse.Ok.results[0].commitment
+    && readResponse.Ok.results[0].commitment.id, iCommitmentId, 'new field query index applied')
 
   // ASSERT: test stale commitment input query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'stale field query index removed')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'stale field query index removed')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { committedInputs: iCommitmentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'reciprocal query index count ok')
-  t.equal(readResponse.Ok && readResponse.Ok[0]
-    && readResponse.Ok[0].process
-    && readResponse.Ok[0].process.id, differentProcessId, 'new reciprocal query index applied')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'reciprocal query index count ok')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0]
+    && readResponse.Ok.results[0].process
+    && readResponse.Ok.results[0].process.id, differentProcessId, 'new reciprocal query index applied')
 
 
 
@@ -135,11 +135,11 @@ runner.registerScenario('updating remote link fields syncs fields and associated
 
   // ASSERT: test event input query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { inputOf: differentProcessId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'field query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'field query index updated')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { committedInputs: iCommitmentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'reciprocal field query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'reciprocal field query index updated')
 
 
 
@@ -182,13 +182,13 @@ runner.registerScenario('removing records with linked remote indexes clears them
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'field query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].intent && readResponse.Ok[0].intent.id, iIntentId, 'query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'field query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].intent && readResponse.Ok.results[0].intent.id, iIntentId, 'query index OK')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { intendedInputs: iIntentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'reciprocal query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'reciprocal query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'reciprocal query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'reciprocal query index OK')
 
 
 
@@ -199,7 +199,7 @@ runner.registerScenario('removing records with linked remote indexes clears them
 
   // ASSERT: test forward link field
   readResponse = await alice.call('planning', 'intent', 'get_intent', { address: iIntentId })
-  t.equal(readResponse.Err && readResponse.Err.Internal, 'No entry at this address', 'record deletion OK')
+  t.equal(readResponse.Err && JSON.parse(readResponse.Err.Internal).Deleted, iIntentId, 'record deletion OK')
 
   // ASSERT: test reciprocal link field
   readResponse = await alice.call('observation', 'process', 'get_process', { address: processId })
@@ -208,11 +208,11 @@ runner.registerScenario('removing records with linked remote indexes clears them
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'field query index removed')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'field query index removed')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { intendedInputs: iIntentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'reciprocal query index removed')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'reciprocal query index removed')
 })
 
 runner.run()
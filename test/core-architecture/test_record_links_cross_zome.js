@@ -61,13 +61,13 @@ runner.registerScenario('updating local link fields syncs fields and associated
 
   // ASSERT: test event input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'field query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].economicEvent && readResponse.Ok[0].economicEvent.id, iEventId, 'query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'field query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].economicEvent && readResponse.Ok.results[0].economicEvent.id, iEventId, 'query index OK')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'reciprocal query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'reciprocal query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'reciprocal query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'reciprocal query index OK')
 
 // :TODO: need to find a new record with a local zome link to test...  maybe EconomicResource.containedIn?
 /*
@@ -87,13 +87,13 @@ runner.registerScenario('updating local link fields syncs fields and associated
 
   // ASSERT: test event input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: differentProcessId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'field query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].economicEvent && readResponse.Ok[0].economicEvent.id, iEventId, 'field query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'field query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].economicEvent && readResponse.Ok.results[0].economicEvent.id, iEventId, 'field query index updated')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, differentProcessId, 'process query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, differentProcessId, 'process query index updated')
 
 
 
@@ -123,11 +123,11 @@ runner.registerScenario('updating local link fields syncs fields and associated
 
   // ASSERT: test event input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: differentProcessId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'field query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'field query index updated')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'process query index updated')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'process query index updated')
 */
 
 
@@ -183,13 +183,13 @@ runner.registerScenario('removing records with linked local indexes clears them
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'field query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].economicEvent && readResponse.Ok[0].economicEvent.id, iEventId, 'query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'field query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].economicEvent && readResponse.Ok.results[0].economicEvent.id, iEventId, 'query index OK')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'reciprocal query index present')
-  t.equal(readResponse.Ok && readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'reciprocal query index OK')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'reciprocal query index present')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'reciprocal query index OK')
 
 
 
@@ -200,7 +200,7 @@ runner.registerScenario('removing records with linked local indexes clears them
 
   // ASSERT: test forward link field
   readResponse = await alice.call('observation', 'economic_event', 'get_event', { address: iEventId })
-  t.equal(readResponse.Err && readResponse.Err.Internal, 'No entry at this address', 'record deletion OK')
+  t.equal(readResponse.Err && JSON.parse(readResponse.Err.Internal).Deleted, iEventId, 'record deletion OK')
 
   // ASSERT: test reciprocal link field
   readResponse = await alice.call('observation', 'process', 'get_process', { address: processId })
@@ -209,11 +209,11 @@ runner.registerScenario('removing records with linked local indexes clears them
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'field query index removed')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'field query index removed')
 
   // ASSERT: test process input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 0, 'reciprocal query index removed')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 0, 'reciprocal query index removed')
 })
 
 runner.run()
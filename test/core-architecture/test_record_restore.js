@@ -0,0 +1,150 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+  proposal: getDNA('proposal'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const testEventProps = {
+  action: 'raise',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('deleted records are hidden until restored', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: write records
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'test process' } })
+  t.ok(pResp.Ok.process && pResp.Ok.process.id, 'process created successfully')
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  const iResp = await alice.call('planning', 'intent', 'create_intent', { intent: { note: 'intent to be deleted', inputOf: processId, ...testEventProps } })
+  t.ok(iResp.Ok.intent && iResp.Ok.intent.id, 'intent created successfully')
+  await s.consistency()
+  const intentId = iResp.Ok.intent.id
+
+  const eResp = await alice.call('observation', 'economic_event', 'create_event', { event: { note: 'satisfying event', ...testEventProps } })
+  t.ok(eResp.Ok.economicEvent && eResp.Ok.economicEvent.id, 'event created successfully')
+  await s.consistency()
+  const eventId = eResp.Ok.economicEvent.id
+
+  const sResp = await alice.call('planning', 'satisfaction', 'create_satisfaction', { satisfaction: { satisfies: intentId, satisfiedBy: eventId } })
+  t.ok(sResp.Ok.satisfaction && sResp.Ok.satisfaction.id, 'satisfaction created successfully')
+  await s.consistency()
+  const satisfactionId = sResp.Ok.satisfaction.id
+
+  // SCENARIO: delete record
+  const delResp = await alice.call('planning', 'intent', 'delete_intent', { address: intentId })
+  t.equal(delResp.Ok, true, 'record deleted successfully')
+  await s.consistency()
+
+  let readResp = await alice.call('planning', 'intent', 'get_intent', { address: intentId })
+  t.equal(JSON.parse(readResp.Err.Internal).Deleted, intentId, 'record not retrievable once deleted')
+
+  readResp = await alice.call('planning', 'intent', 'query_intents', { params: { satisfiedBy: satisfactionId } })
+  t.deepEqual(readResp.Ok.results, [], 'deleted record excluded from queries')
+
+  readResp = await alice.call('planning', 'intent', 'query_intents', { params: { satisfiedBy: satisfactionId, includeDeleted: true } })
+  t.equal(readResp.Ok.results.length, 1, 'deleted record queryable on request')
+  t.equal(readResp.Ok.results[0].intent.id, intentId, 'deleted record query OK')
+
+  readResp = await alice.call('observation', 'process', 'get_process', { address: processId })
+  t.deepEqual(readResp.Ok.process.intendedInputs, [], 'remote index removed upon deletion')
+
+  const updResp = await alice.call('planning', 'intent', 'update_intent', { intent: { id: intentId, note: 'updated' } })
+  t.equal(JSON.parse(updResp.Err.Internal).Deleted, intentId, 'deleted record cannot be updated')
+
+  // SCENARIO: restore record
+  const restoreResp = await alice.call('planning', 'intent', 'restore_intent', { address: intentId })
+  t.equal(restoreResp.Ok.intent.id, intentId, 'record restored')
+  t.equal(restoreResp.Ok.intent.note, 'intent to be deleted', 'restored record data intact')
+  await s.consistency()
+
+  readResp = await alice.call('planning', 'intent', 'get_intent', { address: intentId })
+  t.equal(readResp.Ok.intent.id, intentId, 'record retrievable once restored')
+
+  readResp = await alice.call('planning', 'intent', 'query_intents', { params: { satisfiedBy: satisfactionId } })
+  t.equal(readResp.Ok.results.length, 1, 'restored record included in queries')
+
+  readResp = await alice.call('observation', 'process', 'get_process', { address: processId })
+  t.deepEqual(readResp.Ok.process.intendedInputs, [intentId], 'remote index reinstated upon restore')
+})
+
+runner.registerScenario('deleted records are excluded from indexed queries until restored', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  let resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Milling', classifiedAs: ['some-process-type'], inScopeOf: ['scope-mill'], hasBeginning: '2020-03-01T09:00:00.000Z' } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const processId = resp.Ok.process.id
+
+  resp = await alice.call('proposal', 'proposal', 'create_proposal', { proposal: { name: 'Flour share', inScopeOf: ['scope-mill'] } })
+  t.ok(resp.Ok.proposal && resp.Ok.proposal.id, 'proposal created successfully')
+  const proposalId = resp.Ok.proposal.id
+
+  resp = await alice.call('proposal', 'proposed_to', 'create_proposed_to', { proposed_to: { proposed: proposalId, proposedTo: 'agentid-2-todo' } })
+  t.ok(resp.Ok.proposedTo && resp.Ok.proposedTo.id, 'proposed_to created successfully')
+  const proposedToId = resp.Ok.proposedTo.id
+  await s.consistency()
+
+  // SCENARIO: delete records
+  resp = await alice.call('observation', 'process', 'delete_process', { address: processId })
+  t.equal(resp.Ok.deleted, true, 'process deleted successfully')
+  resp = await alice.call('proposal', 'proposal', 'delete_proposal', { address: proposalId })
+  t.equal(resp.Ok, true, 'proposal deleted successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { classifiedAs: 'some-process-type' } })
+  t.deepEqual(resp.Ok.results, [], 'deleted process excluded from classification queries')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { inScopeOf: 'scope-mill' } })
+  t.deepEqual(resp.Ok.results, [], 'deleted process excluded from scope queries')
+  resp = await alice.call('proposal', 'proposal', 'query_proposals', { params: { inScopeOf: 'scope-mill' } })
+  t.deepEqual(resp.Ok.results, [], 'deleted proposal excluded from scope queries')
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { classifiedAs: 'some-process-type', includeDeleted: true } })
+  t.equal(resp.Ok.results[0].process.id, processId, 'deleted process found by classification on request')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { inScopeOf: 'scope-mill', includeDeleted: true } })
+  t.equal(resp.Ok.results[0].process.id, processId, 'deleted process found by scope on request')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-03-01', endDate: '2020-03-01', includeDeleted: true } })
+  t.equal(resp.Ok.results[0].process.id, processId, 'deleted process found by date range on request')
+  resp = await alice.call('proposal', 'proposal', 'query_proposals', { params: { inScopeOf: 'scope-mill', includeDeleted: true } })
+  t.equal(resp.Ok.results[0].proposal.id, proposalId, 'deleted proposal found by scope on request')
+
+  resp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: false })
+  t.deepEqual(resp.Ok.problems, [], 'links detached from deleted proposal not reported as missing')
+
+  // SCENARIO: restore records
+  resp = await alice.call('observation', 'process', 'restore_process', { address: processId })
+  t.equal(resp.Ok.process.id, processId, 'process restored')
+  resp = await alice.call('proposal', 'proposal', 'restore_proposal', { address: proposalId })
+  t.equal(resp.Ok.proposal.id, proposalId, 'proposal restored')
+  t.deepEqual(resp.Ok.proposal.publishedTo, [proposedToId], 'proposal links retained')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { classifiedAs: 'some-process-type' } })
+  t.deepEqual(resp.Ok.results.map(r => r.process.id), [processId], 'restored process found by classification')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { inScopeOf: 'scope-mill' } })
+  t.deepEqual(resp.Ok.results.map(r => r.process.id), [processId], 'restored process found by scope')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-03-01', endDate: '2020-03-01' } })
+  t.deepEqual(resp.Ok.results.map(r => r.process.id), [processId], 'restored process found by date range')
+  resp = await alice.call('proposal', 'proposal', 'query_proposals', { params: { inScopeOf: 'scope-mill' } })
+  t.deepEqual(resp.Ok.results.map(r => r.proposal.id), [proposalId], 'restored proposal found by scope, without duplicates')
+
+  resp = await alice.call('proposal', 'proposal', 'check_index_integrity', { repair: false })
+  t.deepEqual(resp.Ok.problems, [], 'reciprocal links reattached upon restore')
+})
+
+runner.run()
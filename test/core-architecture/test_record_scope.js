@@ -0,0 +1,109 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+  proposal: getDNA('proposal'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const ids = (resp, field) => resp.Ok.results.map(r => r[field].id).sort()
+
+const FARM = 'scope-farm'
+const BAKERY = 'scope-bakery'
+
+const testEventProps = {
+  action: 'consume',
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+}
+
+runner.registerScenario('records can be listed and queried by scope', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: write records in scopes
+  let resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Harvest', inScopeOf: [FARM] } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const farmProcessId = resp.Ok.process.id
+
+  resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Baking', inScopeOf: [BAKERY] } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const bakeryProcessId = resp.Ok.process.id
+
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: {
+    action: 'produce', provider: 'agentid-1-todo', receiver: 'agentid-2-todo',
+    inScopeOf: [FARM, BAKERY], due: '2019-11-19T04:29:55.056Z',
+  } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  const commitmentId = resp.Ok.commitment.id
+
+  resp = await alice.call('planning', 'intent', 'create_intent', { intent: { action: 'produce', provider: 'agentid-1-todo', inScopeOf: [BAKERY] } })
+  t.ok(resp.Ok.intent && resp.Ok.intent.id, 'intent created successfully')
+  const intentId = resp.Ok.intent.id
+
+  resp = await alice.call('proposal', 'proposal', 'create_proposal', { proposal: { name: 'Bread share', inScopeOf: [BAKERY] } })
+  t.ok(resp.Ok.proposal && resp.Ok.proposal.id, 'proposal created successfully')
+  const proposalId = resp.Ok.proposal.id
+  await s.consistency()
+
+  // SCENARIO: per-scope listings
+  resp = await alice.call('observation', 'process', 'get_processes_in_scope', { scope: FARM })
+  t.deepEqual(ids(resp, 'process'), [farmProcessId], 'processes listed by scope')
+  resp = await alice.call('planning', 'commitment', 'get_commitments_in_scope', { scope: BAKERY })
+  t.deepEqual(ids(resp, 'commitment'), [commitmentId], 'records listed in all of their scopes')
+  resp = await alice.call('planning', 'intent', 'get_intents_in_scope', { scope: BAKERY })
+  t.deepEqual(ids(resp, 'intent'), [intentId], 'intents listed by scope')
+  resp = await alice.call('proposal', 'proposal', 'get_proposals_in_scope', { scope: BAKERY })
+  t.deepEqual(ids(resp, 'proposal'), [proposalId], 'proposals listed by scope')
+  resp = await alice.call('planning', 'intent', 'get_intents_in_scope', { scope: FARM })
+  t.deepEqual(resp.Ok.results, [], 'records outside a scope not listed')
+
+  // SCENARIO: events inherit the scope of their process
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { inputOf: farmProcessId, ...testEventProps } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  const inheritedEventId = resp.Ok.economicEvent.id
+  t.deepEqual(resp.Ok.economicEvent.inScopeOf, [FARM], 'event scope inherited from process')
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { inputOf: farmProcessId, inScopeOf: [BAKERY], ...testEventProps } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  const explicitEventId = resp.Ok.economicEvent.id
+  t.deepEqual(resp.Ok.economicEvent.inScopeOf, [BAKERY], 'explicit event scope retained')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_event', 'get_events_in_scope', { scope: FARM })
+  t.deepEqual(ids(resp, 'economicEvent'), [inheritedEventId], 'events listed by inherited scope')
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: farmProcessId, inScopeOf: BAKERY } })
+  t.deepEqual(ids(resp, 'economicEvent'), [explicitEventId], 'scope filter combined with other query filters')
+  resp = await alice.call('observation', 'economic_event', 'get_all_events', { params: { inScopeOf: BAKERY } })
+  t.deepEqual(resp.Ok.results.map(r => r.economicEvent.id), [explicitEventId], 'event listings filtered by scope')
+  resp = await alice.call('observation', 'economic_event', 'get_all_events', { params: { inScopeOf: BAKERY, limit: 1 } })
+  t.deepEqual(resp.Ok.results.map(r => r.economicEvent.id), [explicitEventId], 'scoped event listing pages filled despite preceding events outside the scope')
+
+  // SCENARIO: index maintained upon update & delete
+  resp = await alice.call('observation', 'process', 'update_process', { process: { id: bakeryProcessId, inScopeOf: [FARM] } })
+  t.ok(resp.Ok, 'process updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { inScopeOf: FARM } })
+  t.deepEqual(ids(resp, 'process'), [farmProcessId, bakeryProcessId].sort(), 'added scopes matched')
+  resp = await alice.call('observation', 'process', 'get_processes_in_scope', { scope: BAKERY })
+  t.deepEqual(resp.Ok.results, [], 'removed scopes no longer matched')
+
+  resp = await alice.call('planning', 'intent', 'delete_intent', { address: intentId })
+  t.ok(resp.Ok, 'intent deleted successfully')
+  await s.consistency()
+
+  resp = await alice.call('planning', 'intent', 'get_intents_in_scope', { scope: BAKERY })
+  t.deepEqual(resp.Ok.results, [], 'deleted records not listed')
+})
+
+runner.run()
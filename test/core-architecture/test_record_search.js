@@ -0,0 +1,96 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+  specification: getDNA('specification'),
+  proposal: getDNA('proposal'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const ids = (resp, field) => resp.Ok.results.map(r => r[field].id).sort()
+
+runner.registerScenario('records can be found by words in their text fields', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: write records
+  let resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Bread baking', note: 'Sourdough loaves' } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const breadId = resp.Ok.process.id
+  resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'Bakery cleaning' } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const cleaningId = resp.Ok.process.id
+  await s.consistency()
+
+  // SCENARIO: prefix & word matching
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'bak' } })
+  t.deepEqual(ids(resp, 'process'), [breadId, cleaningId].sort(), 'words matched by prefix')
+  t.deepEqual(resp.Ok.errors, [], 'no read errors reported for readable records')
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: '"baking"' } })
+  t.deepEqual(ids(resp, 'process'), [breadId], 'quoted terms matched as whole words')
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'BAK sourdough' } })
+  t.deepEqual(ids(resp, 'process'), [breadId], 'all terms must match, in any case, including notes')
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'bread cleaning' } })
+  t.deepEqual(resp.Ok.results, [], 'no results where terms match different records')
+
+  // SCENARIO: index updated upon update & delete
+  resp = await alice.call('observation', 'process', 'update_process', { process: { id: breadId, name: 'Pastry making' } })
+  t.ok(resp.Ok, 'process updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'bread' } })
+  t.deepEqual(resp.Ok.results, [], 'removed words no longer matched')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'pastry' } })
+  t.deepEqual(ids(resp, 'process'), [breadId], 'added words matched')
+
+  resp = await alice.call('observation', 'process', 'delete_process', { address: cleaningId })
+  t.equal(resp.Ok.deleted, true, 'process deleted successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'bakery' } })
+  t.deepEqual(resp.Ok.results, [], 'deleted records excluded from search')
+
+  resp = await alice.call('observation', 'process', 'restore_process', { address: cleaningId })
+  t.ok(resp.Ok, 'process restored successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { search: 'bakery' } })
+  t.deepEqual(ids(resp, 'process'), [cleaningId], 'restored records found by search')
+
+  // SCENARIO: other searchable record types
+  resp = await alice.call('planning', 'intent', 'create_intent', { intent: {
+    action: 'raise', provider: 'agentid-1-todo', note: 'Offering sourdough starter',
+  } })
+  t.ok(resp.Ok.intent && resp.Ok.intent.id, 'intent created successfully')
+  const intentId = resp.Ok.intent.id
+
+  resp = await alice.call('specification', 'resource_specification', 'create_resource_specification', { resource_specification: { name: 'Flour', note: 'Stoneground wholemeal' } })
+  t.ok(resp.Ok.resourceSpecification && resp.Ok.resourceSpecification.id, 'resource specification created successfully')
+  const rsId = resp.Ok.resourceSpecification.id
+
+  resp = await alice.call('proposal', 'proposal', 'create_proposal', { proposal: { name: 'Weekly bread share', unitBased: false } })
+  t.ok(resp.Ok.proposal && resp.Ok.proposal.id, 'proposal created successfully')
+  const proposalId = resp.Ok.proposal.id
+  await s.consistency()
+
+  resp = await alice.call('planning', 'intent', 'query_intents', { params: { search: 'sour' } })
+  t.deepEqual(ids(resp, 'intent'), [intentId], 'intents searchable by note')
+
+  resp = await alice.call('specification', 'resource_specification', 'query_resource_specifications', { params: { search: 'whole' } })
+  t.deepEqual(ids(resp, 'resourceSpecification'), [rsId], 'resource specifications searchable')
+
+  resp = await alice.call('proposal', 'proposal', 'query_proposals', { params: { search: 'share' } })
+  t.deepEqual(ids(resp, 'proposal'), [proposalId], 'proposals searchable')
+})
+
+runner.run()
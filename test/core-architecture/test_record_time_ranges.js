@@ -0,0 +1,114 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const ids = (resp, field) => resp.Ok.results.map(r => r[field].id).sort()
+
+const testEventProps = {
+  action: 'consume',
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+}
+
+runner.registerScenario('records can be queried by date range', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: events
+  let resp = await alice.call('observation', 'process', 'create_process', { process: { name: 'March production', hasBeginning: '2020-03-01T09:00:00.000Z' } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const marchProcessId = resp.Ok.process.id
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, inputOf: marchProcessId, hasPointInTime: '2020-03-01T00:00:00.000Z' } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  const firstEventId = resp.Ok.economicEvent.id
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, hasPointInTime: '2020-03-31T23:30:00.000Z' } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  const lastEventId = resp.Ok.economicEvent.id
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', { event: { ...testEventProps, hasPointInTime: '2020-04-01T00:00:00.000Z' } })
+  t.ok(resp.Ok.economicEvent, 'event created successfully')
+  const aprilEventId = resp.Ok.economicEvent.id
+  await s.consistency()
+
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { startDate: '2020-03-01', endDate: '2020-03-31' } })
+  t.deepEqual(ids(resp, 'economicEvent'), [firstEventId, lastEventId].sort(), 'date ranges are inclusive of whole days')
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { startDate: '2020-03-02' } })
+  t.deepEqual(ids(resp, 'economicEvent'), [lastEventId, aprilEventId].sort(), 'open-ended date ranges')
+  resp = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: marchProcessId, endDate: '2020-03-31T00:00:00Z' } })
+  t.deepEqual(ids(resp, 'economicEvent'), [firstEventId], 'date ranges combined with other filters')
+
+  // SCENARIO: processes, updated
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-03-01', endDate: '2020-03-07' } })
+  t.deepEqual(ids(resp, 'process'), [marchProcessId], 'processes queryable by beginning')
+
+  resp = await alice.call('observation', 'process', 'update_process', { process: { id: marchProcessId, hasBeginning: '2020-05-01T09:00:00.000Z' } })
+  t.ok(resp.Ok, 'process updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-03-01', endDate: '2020-03-07' } })
+  t.deepEqual(resp.Ok.results, [], 'process removed from previous time range')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-05-01' } })
+  t.deepEqual(ids(resp, 'process'), [marchProcessId], 'process moved to new time range')
+
+  resp = await alice.call('observation', 'process', 'create_process', { process: {
+    name: 'Q2 production', hasBeginning: '2020-04-01T09:00:00.000Z', hasEnd: '2020-06-30T17:00:00.000Z',
+  } })
+  t.ok(resp.Ok.process && resp.Ok.process.id, 'process created successfully')
+  const quarterProcessId = resp.Ok.process.id
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-06-01', endDate: '2020-06-07' } })
+  t.deepEqual(ids(resp, 'process'), [quarterProcessId], 'processes queryable by any time they are running')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { startDate: '2020-06-30' } })
+  t.deepEqual(ids(resp, 'process'), [quarterProcessId], 'processes queryable by end')
+  resp = await alice.call('observation', 'process', 'query_processes', { params: { endDate: '2020-03-31' } })
+  t.deepEqual(resp.Ok.results, [], 'processes not yet begun are excluded')
+
+  // SCENARIO: commitments & intents due
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: {
+    action: 'produce', provider: 'agentid-1-todo', receiver: 'agentid-2-todo', due: '2020-03-04T12:00:00.000Z',
+  } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  const commitmentId = resp.Ok.commitment.id
+
+  resp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: {
+    action: 'produce', provider: 'agentid-1-todo', receiver: 'agentid-2-todo',
+    hasBeginning: '2020-03-04T12:00:00.000Z', due: '2020-03-20T12:00:00.000Z',
+  } })
+  t.ok(resp.Ok.commitment && resp.Ok.commitment.id, 'commitment created successfully')
+  const laterCommitmentId = resp.Ok.commitment.id
+
+  resp = await alice.call('planning', 'intent', 'create_intent', { intent: { action: 'produce', provider: 'agentid-1-todo', due: '2020-03-05T12:00:00.000Z' } })
+  t.ok(resp.Ok.intent && resp.Ok.intent.id, 'intent created successfully')
+  const intentId = resp.Ok.intent.id
+  await s.consistency()
+
+  resp = await alice.call('planning', 'commitment', 'query_commitments', { params: { dueStartDate: '2020-03-02', dueEndDate: '2020-03-08' } })
+  t.deepEqual(ids(resp, 'commitment'), [commitmentId], 'commitments queryable by due date')
+  resp = await alice.call('planning', 'commitment', 'query_commitments', { params: { startDate: '2020-03-02', endDate: '2020-03-08' } })
+  t.deepEqual(ids(resp, 'commitment'), [laterCommitmentId], 'commitments queryable by planned time')
+  resp = await alice.call('planning', 'intent', 'query_intents', { params: { dueStartDate: '2020-03-02', dueEndDate: '2020-03-08' } })
+  t.deepEqual(ids(resp, 'intent'), [intentId], 'intents queryable by due date')
+
+  resp = await alice.call('planning', 'intent', 'update_intent', { intent: { id: intentId, due: '2020-03-12T12:00:00.000Z' } })
+  t.ok(resp.Ok, 'intent updated successfully')
+  await s.consistency()
+
+  resp = await alice.call('planning', 'intent', 'query_intents', { params: { dueStartDate: '2020-03-02', dueEndDate: '2020-03-08' } })
+  t.deepEqual(resp.Ok.results, [], 'intent due date index updated')
+})
+
+runner.run()
@@ -0,0 +1,51 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const testEventProps = {
+  action: 'consume',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  due: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('remote index methods are only accessible via granted capabilities', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // ASSERT: zomes grant only their remote index methods
+  let resp = await alice.call('observation', 'process', 'request_capability', { request: { functions: ['index_committed_inputs'] } })
+  t.ok(resp.Ok, 'capability granted for remote index method')
+
+  const repeated = await alice.call('observation', 'process', 'request_capability', { request: { functions: ['index_committed_inputs'] } })
+  t.equal(repeated.Ok, resp.Ok, 'repeated requests reuse the existing grant')
+
+  resp = await alice.call('observation', 'process', 'request_capability', { request: { functions: ['index_committed_inputs', 'delete_process'] } })
+  t.ok(resp.Err, 'capability refused for other methods')
+
+  // ASSERT: bridged zomes obtain capabilities in order to update remote indexes
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'test process' } })
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  const cResp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: { inputOf: processId, ...testEventProps } })
+  t.ok(cResp.Ok.commitment && cResp.Ok.commitment.id, 'record created successfully')
+  await s.consistency()
+
+  resp = await alice.call('observation', 'process', 'get_process', { address: processId })
+  t.deepEqual(resp.Ok.process.committedInputs, [cResp.Ok.commitment.id], 'remote index updated via granted capability')
+})
+
+runner.run()
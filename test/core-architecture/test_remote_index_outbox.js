@@ -0,0 +1,96 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+// no bridge to the observation DNA, so remote index requests cannot be delivered
+const config = buildConfig({
+  planning: getDNA('planning'),
+}, {})
+
+const bridgedConfig = buildConfig({
+  observation: getDNA('observation'),
+  planning: getDNA('planning'),
+}, {
+  vf_observation: ['planning', 'observation'],
+})
+
+const testEventProps = {
+  action: 'consume',
+  resourceClassifiedAs: ['some-resource-type'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  due: '2019-11-19T04:29:55.056Z',
+}
+
+runner.registerScenario('undeliverable remote index requests are kept in the outbox for retry', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const processId = 'QmUnreachableProcessId'
+  const commitment = {
+    note: 'commitment referencing an unreachable process',
+    inputOf: processId,
+    ...testEventProps,
+  }
+  const cResp = await alice.call('planning', 'commitment', 'create_commitment', { commitment })
+  t.ok(cResp.Ok && cResp.Ok.commitment.id, 'record created whilst remote index is unavailable')
+  t.equal(cResp.Ok.commitment.inputOf, processId, 'reference to unreachable record retained')
+  await s.consistency()
+
+  // ASSERT: failed request is stored
+  let readResponse = await alice.call('planning', 'commitment', 'get_pending_remote_index_operations', {})
+  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'failed remote index request stored in outbox')
+  t.equal(readResponse.Ok[0].operation.attempts, 1, 'failed attempt recorded')
+  t.ok(readResponse.Ok[0].operation.lastError, 'error of failed attempt recorded')
+
+  // ASSERT: failed retries remain pending
+  readResponse = await alice.call('planning', 'commitment', 'retry_remote_index_sync', {})
+  t.deepEqual(readResponse.Ok && readResponse.Ok.synced, [], 'nothing synced whilst remote DNA unavailable')
+  t.equal(readResponse.Ok.pending.length, 1, 'request remains in outbox after failed retry')
+  t.equal(readResponse.Ok.pending[0].operation.attempts, 2, 'retry attempt recorded')
+  t.deepEqual(readResponse.Ok.failed, [], 'unavailable remote DNA not treated as a rejection')
+  await s.consistency()
+
+  readResponse = await alice.call('planning', 'commitment', 'get_pending_remote_index_operations', {})
+  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'outbox contains a single entry after retry')
+})
+
+runner.registerScenario('stored remote index requests are delivered once the remote DNA returns', async (s, t) => {
+  const { alice } = await s.players({ alice: bridgedConfig }, true)
+
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'test process' } })
+  t.ok(pResp.Ok.process && pResp.Ok.process.id, 'process created successfully')
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  // SCENARIO: remote DNA goes offline whilst the commitment is created
+  await alice.admin('admin/instance/stop', { id: 'observation' })
+
+  const cResp = await alice.call('planning', 'commitment', 'create_commitment', { commitment: { inputOf: processId, ...testEventProps } })
+  t.ok(cResp.Ok && cResp.Ok.commitment.id, 'record created whilst remote DNA is offline')
+  await s.consistency()
+  const commitmentId = cResp.Ok.commitment.id
+
+  let readResponse = await alice.call('planning', 'commitment', 'get_pending_remote_index_operations', {})
+  t.equal(readResponse.Ok.length, 1, 'failed remote index request stored in outbox')
+
+  // ASSERT: retry succeeds once remote DNA is back
+  await alice.admin('admin/instance/start', { id: 'observation' })
+
+  readResponse = await alice.call('planning', 'commitment', 'retry_remote_index_sync', {})
+  t.equal(readResponse.Ok.synced.length, 1, 'pending request delivered upon retry')
+  t.deepEqual(readResponse.Ok.pending, [], 'no requests remain pending')
+  await s.consistency()
+
+  readResponse = await alice.call('planning', 'commitment', 'get_pending_remote_index_operations', {})
+  t.deepEqual(readResponse.Ok, [], 'outbox cleared after successful retry')
+
+  readResponse = await alice.call('observation', 'process', 'get_process', { address: processId })
+  t.deepEqual(readResponse.Ok.process.committedInputs, [commitmentId], 'remote index created by retry')
+})
+
+runner.run()
@@ -137,6 +137,30 @@ runner.registerScenario('Event/Resource list APIs', async (s, t) => {
     [{ id: resource1Id }, { id: resource2Id }],
     'resource IDs OK'
   )
+
+  let page = await alice.call('observation', 'economic_event', 'get_all_events', { params: { limit: 2 } })
+  t.deepEqual(page.Ok.results.map(r => r.economicEvent.id), [event1Id, event2Id], 'first page of events OK')
+  t.ok(page.Ok.nextCursor, 'cursor returned for next page')
+
+  page = await alice.call('observation', 'economic_event', 'get_all_events', { params: { limit: 2, cursor: page.Ok.nextCursor } })
+  t.deepEqual(page.Ok.results.map(r => r.economicEvent.id), [event3Id, event4Id], 'second page of events OK')
+
+  page = await alice.call('observation', 'economic_event', 'get_all_events', { params: { limit: 2, cursor: page.Ok.nextCursor } })
+  t.deepEqual(page.Ok.results.map(r => r.economicEvent.id), [event5Id], 'last page of events OK')
+  t.notOk(page.Ok.nextCursor, 'no cursor returned after last page')
+
+  page = await alice.call('observation', 'economic_event', 'get_all_events', { params: {
+    after: '2019-11-19T04:29:55.000Z',
+    before: '2019-11-22T00:00:00.000Z',
+  } })
+  t.deepEqual(page.Ok.results.map(r => r.economicEvent.id), [event2Id, event3Id, event4Id], 'events filterable by time range')
+
+  resp = await alice.graphQL(`{
+    allEconomicEvents(limit: 3) {
+      id
+    }
+  }`)
+  t.deepEqual(resp.data.allEconomicEvents, [{ id: event1Id }, { id: event2Id }, { id: event3Id }], 'GraphQL listings limited to requested size')
 })
 
 runner.run()
@@ -79,13 +79,13 @@ runner.registerScenario('links can be written and read between DNAs', async (s,
 
   // ASSERT: check forward query indexes
   readResponse = await alice.call('planning', 'fulfillment', 'query_fulfillments', { params: { fulfills: commitmentId } })
-  t.equal(readResponse.Ok.length, 1, 'read fulfillments by commitment OK')
-  t.equal(readResponse.Ok[0].fulfillment.id, fulfillmentId, 'Fulfillment.fulfills indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'read fulfillments by commitment OK')
+  t.equal(readResponse.Ok.results[0].fulfillment.id, fulfillmentId, 'Fulfillment.fulfills indexed correctly')
 
   // ASSERT: check reverse query indexes
   readResponse = await alice.call('observation', 'fulfillment', 'query_fulfillments', { params: { fulfilledBy: eventId } })
-  t.equal(readResponse.Ok.length, 1, 'read fulfillments by event OK')
-  t.equal(readResponse.Ok[0].fulfillment.id, fulfillmentId, 'Fulfillment.fulfilledBy indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'read fulfillments by event OK')
+  t.equal(readResponse.Ok.results[0].fulfillment.id, fulfillmentId, 'Fulfillment.fulfilledBy indexed correctly')
 
 
 
@@ -102,9 +102,9 @@ runner.registerScenario('links can be written and read between DNAs', async (s,
 
   // ASSERT: check forward query indices
   readResponse = await alice.call('planning', 'fulfillment', 'query_fulfillments', { params: { fulfills: commitmentId } })
-  t.equal(readResponse.Ok.length, 2, 'appending fulfillments for read OK')
-  t.equal(readResponse.Ok[0].fulfillment.id, fulfillmentId, 'fulfillment 1 indexed correctly')
-  t.equal(readResponse.Ok[1].fulfillment.id, fulfillmentId2, 'fulfillment 2 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 2, 'appending fulfillments for read OK')
+  t.equal(readResponse.Ok.results[0].fulfillment.id, fulfillmentId, 'fulfillment 1 indexed correctly')
+  t.equal(readResponse.Ok.results[1].fulfillment.id, fulfillmentId2, 'fulfillment 2 indexed correctly')
 
   // ASSERT: ensure append is working on the event read side
   readResponse = await alice.call('observation', 'economic_event', 'get_event', { address: eventId })
@@ -114,8 +114,8 @@ runner.registerScenario('links can be written and read between DNAs', async (s,
 
   // ASSERT: ensure query indices on the event read side
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { fulfills: fulfillmentId } })
-  t.equal(readResponse.Ok.length, 1, 'appending fulfillments for event query OK')
-  t.equal(readResponse.Ok[0].economicEvent.id, eventId, 'event query indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'appending fulfillments for event query OK')
+  t.equal(readResponse.Ok.results[0].economicEvent.id, eventId, 'event query indexed correctly')
 
   // ASSERT: ensure append is working on the commitment read side
   readResponse = await alice.call('planning', 'commitment', 'get_commitment', { address: commitmentId })
@@ -125,14 +125,14 @@ runner.registerScenario('links can be written and read between DNAs', async (s,
 
   // ASSERT: ensure query indices on the commitment read side
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { fulfilledBy: fulfillmentId } })
-  t.equal(readResponse.Ok.length, 1, 'appending fulfillments for commitment query OK')
-  t.equal(readResponse.Ok[0].commitment.id, commitmentId, 'commitment query indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'appending fulfillments for commitment query OK')
+  t.equal(readResponse.Ok.results[0].commitment.id, commitmentId, 'commitment query indexed correctly')
 
   // ASSERT: check reciprocal query indexes
   readResponse = await alice.call('observation', 'fulfillment', 'query_fulfillments', { params: { fulfilledBy: eventId } })
-  t.equal(readResponse.Ok.length, 2, 'read fulfillments by event OK')
-  t.equal(readResponse.Ok[0].fulfillment.id, fulfillmentId, 'fulfillment 1 indexed correctly')
-  t.equal(readResponse.Ok[1].fulfillment.id, fulfillmentId2, 'fulfillment 2 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 2, 'read fulfillments by event OK')
+  t.equal(readResponse.Ok.results[0].fulfillment.id, fulfillmentId, 'fulfillment 1 indexed correctly')
+  t.equal(readResponse.Ok.results[1].fulfillment.id, fulfillmentId2, 'fulfillment 2 indexed correctly')
 })
 
 runner.run()
@@ -119,23 +119,23 @@ runner.registerScenario('process query indexes and relationships', async (s, t)
 
   // ASSERT: test event input query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'event input query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].economicEvent && readResponse.Ok[0].economicEvent.id, iEventId, 'event input query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'event input query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].economicEvent && readResponse.Ok.results[0].economicEvent.id, iEventId, 'event input query index created')
 
   // ASSERT: test event output query edge
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { outputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'event output query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].economicEvent && readResponse.Ok[0].economicEvent.id, oEventId, 'event output query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'event output query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].economicEvent && readResponse.Ok.results[0].economicEvent.id, oEventId, 'event output query index created')
 
   // ASSERT: check process event input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { inputs: iEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.inputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.inputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.inputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.inputs query index created')
 
   // ASSERT: check process event output query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { outputs: oEventId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.outputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.outputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.outputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.outputs query index created')
 
 
 
@@ -151,23 +151,23 @@ runner.registerScenario('process query indexes and relationships', async (s, t)
 
   // ASSERT: test commitment input query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'commitment input query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].commitment && readResponse.Ok[0].commitment.id, iCommitmentId, 'commitment input query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'commitment input query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].commitment && readResponse.Ok.results[0].commitment.id, iCommitmentId, 'commitment input query index created')
 
   // ASSERT: test commitment output query edge
   readResponse = await alice.call('planning', 'commitment', 'query_commitments', { params: { outputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'commitment output query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].commitment && readResponse.Ok[0].commitment.id, oCommitmentId, 'commitment output query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'commitment output query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].commitment && readResponse.Ok.results[0].commitment.id, oCommitmentId, 'commitment output query index created')
 
   // ASSERT: check process commitment input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { committedInputs: iCommitmentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.committedInputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.committedInputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.committedInputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.committedInputs query index created')
 
   // ASSERT: check process commitment output query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { committedOutputs: oCommitmentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.committedOutputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.committedOutputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.committedOutputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.committedOutputs query index created')
 
 
 
@@ -183,23 +183,23 @@ runner.registerScenario('process query indexes and relationships', async (s, t)
 
   // ASSERT: test intent input query edge
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { inputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'intent input query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].intent && readResponse.Ok[0].intent.id, iIntentId, 'intent input query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'intent input query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].intent && readResponse.Ok.results[0].intent.id, iIntentId, 'intent input query index created')
 
   // ASSERT: test intent output query edge
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { outputOf: processId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'intent output query index present')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].intent && readResponse.Ok[0].intent.id, oIntentId, 'intent output query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'intent output query index present')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].intent && readResponse.Ok.results[0].intent.id, oIntentId, 'intent output query index created')
 
   // ASSERT: check process intent input query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { intendedInputs: iIntentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.intendedInputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.intendedInputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.intendedInputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.intendedInputs query index created')
 
   // ASSERT: check process intent output query edge
   readResponse = await alice.call('observation', 'process', 'query_processes', { params: { intendedOutputs: oIntentId } })
-  t.equal(readResponse.Ok && readResponse.Ok.length, 1, 'process.intendedOutputs query succeeded')
-  t.equal(readResponse.Ok[0] && readResponse.Ok[0].process && readResponse.Ok[0].process.id, processId, 'process.intendedOutputs query index created')
+  t.equal(readResponse.Ok.results && readResponse.Ok.results.length, 1, 'process.intendedOutputs query succeeded')
+  t.equal(readResponse.Ok.results[0] && readResponse.Ok.results[0].process && readResponse.Ok.results[0].process.id, processId, 'process.intendedOutputs query index created')
 
 
   // TODO: modify
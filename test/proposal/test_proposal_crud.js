@@ -115,7 +115,7 @@ runner.registerScenario('Proposal record API', async (s, t) => {
   })
 
   t.equal(queryForDeleted.errors.length, 1, 'querying deleted record is an error')
-  t.notEqual(-1, queryForDeleted.errors[0].message.indexOf('No entry at this address'), 'correct error reported')
+  t.notEqual(-1, queryForDeleted.errors[0].message.indexOf('Deleted'), 'correct error reported')
 })
 
 runner.run()
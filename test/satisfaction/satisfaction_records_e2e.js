@@ -79,23 +79,23 @@ runner.registerScenario('satisfactions can be written and read between DNAs by a
 
   // ASSERT: check intent query indexes
   readResponse = await alice.call('planning', 'satisfaction', 'query_satisfactions', { params: { satisfies: intentId } })
-  t.equal(readResponse.Ok.length, 1, 'read satisfactions by intent OK')
-  t.equal(readResponse.Ok[0].satisfaction.id, satisfactionId, 'Satisfaction.satisfies indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'read satisfactions by intent OK')
+  t.equal(readResponse.Ok.results[0].satisfaction.id, satisfactionId, 'Satisfaction.satisfies indexed correctly')
 
   // ASSERT: check event query indexes
   readResponse = await alice.call('observation', 'satisfaction', 'query_satisfactions', { params: { satisfiedBy: eventId } })
-  t.equal(readResponse.Ok.length, 1, 'read satisfactions by event OK')
-  t.equal(readResponse.Ok[0].satisfaction.id, satisfactionId, 'Satisfaction.satisfiedBy indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'read satisfactions by event OK')
+  t.equal(readResponse.Ok.results[0].satisfaction.id, satisfactionId, 'Satisfaction.satisfiedBy indexed correctly')
 
   // ASSERT: check intent satisfaction query indexes
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { satisfiedBy: satisfactionId } })
-  t.equal(readResponse.Ok.length, 1, 'indexing satisfactions for intent query OK')
-  t.equal(readResponse.Ok[0].intent.id, intentId, 'intent query 1 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'indexing satisfactions for intent query OK')
+  t.equal(readResponse.Ok.results[0].intent.id, intentId, 'intent query 1 indexed correctly')
 
   // ASSERT: check event satisfaction query indexes
   readResponse = await alice.call('observation', 'economic_event', 'query_events', { params: { satisfies: satisfactionId } })
-  t.equal(readResponse.Ok.length, 1, 'indexing satisfactions for event query OK')
-  t.equal(readResponse.Ok[0].economicEvent.id, eventId, 'event query 1 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'indexing satisfactions for event query OK')
+  t.equal(readResponse.Ok.results[0].economicEvent.id, eventId, 'event query 1 indexed correctly')
 
 
 
@@ -127,9 +127,9 @@ runner.registerScenario('satisfactions can be written and read between DNAs by a
 
   // ASSERT: check intent query indices
   readResponse = await alice.call('planning', 'satisfaction', 'query_satisfactions', { params: { satisfies: intentId } })
-  t.equal(readResponse.Ok.length, 2, 'appending satisfactions for read OK')
-  t.equal(readResponse.Ok[0].satisfaction.id, satisfactionId, 'satisfaction 1 indexed correctly')
-  t.equal(readResponse.Ok[1].satisfaction.id, satisfactionId2, 'satisfaction 2 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 2, 'appending satisfactions for read OK')
+  t.equal(readResponse.Ok.results[0].satisfaction.id, satisfactionId, 'satisfaction 1 indexed correctly')
+  t.equal(readResponse.Ok.results[1].satisfaction.id, satisfactionId2, 'satisfaction 2 indexed correctly')
 
   // ASSERT: check intent field refs
   readResponse = await alice.call('planning', 'intent', 'get_intent', { address: intentId })
@@ -139,13 +139,13 @@ runner.registerScenario('satisfactions can be written and read between DNAs by a
 
   // ASSERT: check commitment query indexes
   readResponse = await alice.call('planning', 'satisfaction', 'query_satisfactions', { params: { satisfiedBy: commitmentId } })
-  t.equal(readResponse.Ok.length, 1, 'read satisfactions by commitment OK')
-  t.equal(readResponse.Ok[0].satisfaction.id, satisfactionId2, 'Satisfaction.satisfiedBy indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'read satisfactions by commitment OK')
+  t.equal(readResponse.Ok.results[0].satisfaction.id, satisfactionId2, 'Satisfaction.satisfiedBy indexed correctly')
 
   // ASSERT: check intent satisfaction query indexes
   readResponse = await alice.call('planning', 'intent', 'query_intents', { params: { satisfiedBy: satisfactionId2 } })
-  t.equal(readResponse.Ok.length, 1, 'appending satisfactions for intent query OK')
-  t.equal(readResponse.Ok[0].intent.id, intentId, 'intent query 2 indexed correctly')
+  t.equal(readResponse.Ok.results.length, 1, 'appending satisfactions for intent query OK')
+  t.equal(readResponse.Ok.results[0].intent.id, intentId, 'intent query 2 indexed correctly')
 })
 
 runner.run()
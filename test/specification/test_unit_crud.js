@@ -110,7 +110,61 @@ runner.registerScenario('Unit record API', async (s, t) => {
   })
 
   t.equal(queryForDeleted.errors.length, 1, 'querying deleted record is an error')
-  t.notEqual(-1, queryForDeleted.errors[0].message.indexOf('No entry at this address'), 'correct error reported')
+  t.notEqual(-1, queryForDeleted.errors[0].message.indexOf('NotFound'), 'correct error reported')
+})
+
+runner.registerScenario('Unit IDs are unique', async (s, t) => {
+  const alice = await buildPlayer(s, 'alice', config)
+
+  const createUnit = (unit) => alice.graphQL(`
+    mutation($rs: UnitCreateParams!) {
+      res: createUnit(unit: $rs) {
+        unit {
+          id
+        }
+      }
+    }
+    `, {
+    rs: unit,
+  })
+
+  const createResp = await createUnit({ label: 'metres', symbol: 'm' })
+  await s.consistency()
+  t.equal(createResp.data.res.unit.id, 'm', 'record created')
+
+  const duplicateResp = await createUnit({ label: 'miles', symbol: 'm' })
+  await s.consistency()
+  t.equal(duplicateResp.errors.length, 1, 'creating record with existing ID is an error')
+  t.notEqual(-1, duplicateResp.errors[0].message.indexOf('AlreadyExists'), 'correct error reported')
+
+  const otherResp = await createUnit({ label: 'litres', symbol: 'l' })
+  await s.consistency()
+
+  const renameResp = await alice.graphQL(`
+    mutation($rs: UnitUpdateParams!) {
+      res: updateUnit(unit: $rs) {
+        unit {
+          id
+        }
+      }
+    }
+    `, {
+    rs: { id: otherResp.data.res.unit.id, label: 'litres', symbol: 'm' },
+  })
+  t.equal(renameResp.errors.length, 1, 'renaming record to existing ID is an error')
+
+  const getResp = await alice.graphQL(`
+    query($id: ID!) {
+      res: unit(id: $id) {
+        id
+        label
+        symbol
+      }
+    }
+    `, {
+    id: 'm',
+  })
+  t.deepEqual(getResp.data.res, { id: 'm', label: 'metres', symbol: 'm' }, 'original record unaffected')
 })
 
 runner.run()